@@ -0,0 +1,82 @@
+//! Transition throughput of the request processing modes.
+//!
+//! Every iteration requests a transition for each entity (triggered in immediate mode,
+//! written as messages otherwise) and runs one frame. Run with
+//! `cargo bench`, or `cargo bench -- batched` for a single mode.
+
+use bevy::prelude::*;
+use bevy_fsm::{FSMPlugin, FSMState, FSMTransition, StateChangeRequest};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum AgentFSM {
+    Wander,
+    Flee,
+}
+
+impl FSMState for AgentFSM {}
+
+impl FSMTransition for AgentFSM {
+    fn can_transition(_: Self, _: Self) -> bool {
+        true
+    }
+}
+
+/// Payload making agents look like game entities, so table moves have a cost.
+#[derive(Component, Default)]
+#[allow(dead_code)]
+struct Body([f32; 16]);
+
+fn setup(plugin: FSMPlugin<AgentFSM>, agents: usize) -> (App, Vec<Entity>) {
+    let mut app = App::new();
+    app.add_plugins(plugin);
+    let entities = (0..agents)
+        .map(|_| {
+            app.world_mut()
+                .spawn((AgentFSM::Wander, Transform::default(), Body::default()))
+                .id()
+        })
+        .collect();
+    app.update();
+    (app, entities)
+}
+
+fn transitions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transitions");
+    group.sample_size(10);
+    for agents in [1_000, 10_000, 100_000] {
+        group.throughput(Throughput::Elements(agents as u64));
+        for mode in ["immediate", "buffered", "batched", "batched_parallel"] {
+            let plugin = match mode {
+                "buffered" => FSMPlugin::buffered(),
+                "batched" => FSMPlugin::batched(),
+                "batched_parallel" => FSMPlugin::batched().with_parallel_validation(),
+                _ => FSMPlugin::default(),
+            };
+            let (mut app, entities) = setup(plugin, agents);
+            let mut next = AgentFSM::Flee;
+            group.bench_function(BenchmarkId::new(mode, agents), |b| {
+                b.iter(|| {
+                    let requests = entities
+                        .iter()
+                        .map(|&entity| StateChangeRequest::new(entity, next));
+                    let world = app.world_mut();
+                    if mode == "immediate" {
+                        requests.for_each(|request| world.trigger(request));
+                    } else {
+                        world.write_message_batch(requests);
+                    }
+                    app.update();
+                    next = match next {
+                        AgentFSM::Wander => AgentFSM::Flee,
+                        AgentFSM::Flee => AgentFSM::Wander,
+                    };
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, transitions);
+criterion_main!(benches);
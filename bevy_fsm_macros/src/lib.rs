@@ -39,7 +39,8 @@ fn to_snake_case(s: &str) -> String {
 /// Derive macro for generating a default `FSMTransition` implementation.
 ///
 /// This macro generates a permissive `FSMTransition` implementation that allows all state
-/// transitions. Use this for simple state machines where any transition should be allowed.
+/// transitions. Use this for simple state machines where any transition should be allowed,
+/// or list the allowed edges in a `#[transitions(...)]` table (see below).
 ///
 /// # Requirements
 ///
@@ -50,6 +51,27 @@ fn to_snake_case(s: &str) -> String {
 ///
 /// Generates an implementation of `FSMTransition` with `can_transition` always returning `true`.
 ///
+/// # Transition Table
+///
+/// `#[transitions(From => To, ...)]` allows exactly the listed edges. An edge can carry a
+/// guard, `From => To if guard`, naming a `fn(&World, Entity) -> bool` or a
+/// `fn(&GuardCtx) -> bool` that is checked in the generated `can_transition_ctx`. Guarded edges
+/// stay part of the static graph, since `can_transition` can't see the world:
+///
+/// ```rust,ignore
+/// fn has_energy(world: &World, entity: Entity) -> bool {
+///     world.get::<Energy>(entity).is_some_and(|energy| energy.0 > 10)
+/// }
+///
+/// #[derive(Component, EnumEvent, FSMTransition, FSMState, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// #[transitions(Idle => Casting if has_energy, Casting => Idle, Casting => Stunned, Stunned => Idle)]
+/// enum MageFSM {
+///     Idle,
+///     Casting,
+///     Stunned,
+/// }
+/// ```
+///
 /// # Example (Zero Boilerplate)
 ///
 /// ```rust,ignore
@@ -100,12 +122,77 @@ fn to_snake_case(s: &str) -> String {
 /// # Panics
 ///
 /// - Panics if applied to a non-enum type
-#[proc_macro_derive(FSMTransition)]
+#[proc_macro_derive(FSMTransition, attributes(transitions))]
 pub fn derive_fsm_transition(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(fsm_transition_impl(&input).unwrap_or_else(syn::Error::into_compile_error))
+}
+
+/// One `From => To [if guard]` entry of a `#[transitions(...)]` table.
+struct Edge {
+    from: syn::Ident,
+    to: syn::Ident,
+    guard: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for Edge {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let from = input.parse()?;
+        input.parse::<syn::Token![=>]>()?;
+        let to = input.parse()?;
+        let guard = if input.parse::<Option<syn::Token![if]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { from, to, guard })
+    }
+}
+
+/// The variants with their `cfg` attributes, which are copied onto every generated item naming
+/// the variant, so items for compiled-out variants disappear together with the variant.
+fn collect_variant_cfgs(
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::Token![,]>,
+) -> Vec<(&syn::Ident, Vec<&syn::Attribute>)> {
+    variants
+        .iter()
+        .map(|v| {
+            let cfgs = v
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("cfg"))
+                .collect();
+            (&v.ident, cfgs)
+        })
+        .collect()
+}
+
+/// Number of enabled variants among those carrying `cfgs`, as a const expression built from a
+/// `()` slice, so it stays correct when variants are compiled out.
+fn enabled_count(cfgs: &[&Vec<&syn::Attribute>]) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            const ENABLED: &[()] = &[#( #(#cfgs)* () ),*];
+            ENABLED.len()
+        }
+    }
+}
+
+/// Edges of all `#[transitions(...)]` tables on the enum.
+fn parse_transition_table(attrs: &[syn::Attribute]) -> syn::Result<Vec<Edge>> {
+    let mut edges = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("transitions")) {
+        edges.extend(attr.parse_args_with(
+            syn::punctuated::Punctuated::<Edge, syn::Token![,]>::parse_terminated,
+        )?);
+    }
+    Ok(edges)
+}
+
+/// Generates the `FSMTransition` implementation for a parsed enum.
+fn fsm_transition_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let enum_name = &input.ident;
-    let generics = input.generics.clone();
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Verify it's an enum (though not strictly necessary for FSMTransition)
     assert!(
@@ -113,7 +200,119 @@ pub fn derive_fsm_transition(input: TokenStream) -> TokenStream {
         "FSMTransition can only be derived for enums"
     );
 
-    let expanded = quote! {
+    let edges = parse_transition_table(&input.attrs)?;
+    if !edges.is_empty() {
+        let Data::Enum(data) = &input.data else {
+            unreachable!()
+        };
+        let variant_cfgs = collect_variant_cfgs(&data.variants);
+        let all_cfgs: Vec<_> = variant_cfgs.iter().map(|(_, cfgs)| cfgs).collect();
+        let position = |ident: &syn::Ident| {
+            variant_cfgs
+                .iter()
+                .position(|(variant, _)| *variant == ident)
+                .ok_or_else(|| syn::Error::new_spanned(ident, "not a variant of this enum"))
+        };
+        // Matrix indices match `FSMState::index()`, skipping compiled-out variants
+        let count = enabled_count(&all_cfgs);
+        let index_arms = variant_cfgs.iter().enumerate().map(|(i, (variant, cfgs))| {
+            let before = enabled_count(&all_cfgs[..i]);
+            quote! { #(#cfgs)* #enum_name::#variant => #before, }
+        });
+        let edge_indices = edges
+            .iter()
+            .map(|Edge { from, to, .. }| {
+                let (from, to) = (position(from)?, position(to)?);
+                let (from_cfgs, to_cfgs) = (all_cfgs[from], all_cfgs[to]);
+                let (from, to) = (
+                    enabled_count(&all_cfgs[..from]),
+                    enabled_count(&all_cfgs[..to]),
+                );
+                Ok(quote! { #(#from_cfgs)* #(#to_cfgs)* (#from, #to) })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        let guard_arms: Vec<_> = edges
+            .iter()
+            .filter_map(|Edge { from, to, guard }| {
+                let guard = guard.as_ref()?;
+                Some(quote! {
+                    (#enum_name::#from, #enum_name::#to) => {
+                        bevy_fsm::rules::TransitionGuard::check(&#guard, ctx)
+                    }
+                })
+            })
+            .collect();
+        let ctx_method = if guard_arms.is_empty() {
+            quote! {
+                const READS_WORLD: bool = false;
+            }
+        } else {
+            quote! {
+                /// Checks the `if` guards of the table's edges.
+                ///
+                /// This is auto-generated by `#[derive(FSMTransition)]`.
+                fn can_transition_ctx(world: &bevy::prelude::World, entity: bevy::prelude::Entity, from: Self, to: Self) -> bool {
+                    <Self as bevy_fsm::FSMTransition>::try_transition_ctx(world, entity, from, to).is_ok()
+                }
+
+                /// Checks the `if` guards of the table's edges, keeping the reason they deny.
+                ///
+                /// This is auto-generated by `#[derive(FSMTransition)]`.
+                fn try_transition_ctx(world: &bevy::prelude::World, entity: bevy::prelude::Entity, from: Self, to: Self) -> Result<(), bevy_fsm::rules::DenyReason> {
+                    let ctx = bevy_fsm::rules::GuardCtx::new(world, entity);
+                    <Self as bevy_fsm::FSMTransition>::try_transition_guarded(&ctx, from, to)
+                }
+
+                /// Checks the `if` guards of the table's edges with the transition timing of
+                /// the entity.
+                ///
+                /// This is auto-generated by `#[derive(FSMTransition)]`.
+                fn try_transition_guarded(ctx: &bevy_fsm::rules::GuardCtx<'_>, from: Self, to: Self) -> Result<(), bevy_fsm::rules::DenyReason> {
+                    if !<Self as bevy_fsm::FSMTransition>::can_transition(from, to) {
+                        return Err(bevy_fsm::rules::DenyReason::default());
+                    }
+                    match (from, to) {
+                        #(#guard_arms)*
+                        _ => Ok(()),
+                    }
+                }
+            }
+        };
+        return Ok(quote! {
+            impl #impl_generics bevy_fsm::FSMTransition for #enum_name #ty_generics #where_clause {
+                /// The edges listed in `#[transitions(...)]`, built at compile time.
+                const ADJACENCY: Option<&'static bevy_fsm::rules::FSMAdjacency> = {
+                    const COUNT: usize = #count;
+                    const WORDS: [u64; (COUNT * COUNT).div_ceil(64)] =
+                        bevy_fsm::rules::FSMAdjacency::words(COUNT, &[#(#edge_indices),*]);
+                    Some(&bevy_fsm::rules::FSMAdjacency::new(COUNT, &WORDS))
+                };
+
+                fn adjacency_index(state: Self) -> usize {
+                    match state {
+                        #(#index_arms)*
+                    }
+                }
+
+                /// Allows the edges listed in `#[transitions(...)]`, with a bit test in
+                /// [`Self::ADJACENCY`].
+                ///
+                /// This is auto-generated by `#[derive(FSMTransition)]`.
+                fn can_transition(from: Self, to: Self) -> bool {
+                    <Self as bevy_fsm::FSMTransition>::ADJACENCY.is_some_and(|adjacency| {
+                        adjacency.contains(
+                            <Self as bevy_fsm::FSMTransition>::adjacency_index(from),
+                            <Self as bevy_fsm::FSMTransition>::adjacency_index(to),
+                        )
+                    })
+                }
+
+                #ctx_method
+            }
+        });
+    }
+
+    Ok(quote! {
         impl #impl_generics bevy_fsm::FSMTransition for #enum_name #ty_generics #where_clause {
             /// Default implementation: allows all transitions.
             ///
@@ -121,24 +320,71 @@ pub fn derive_fsm_transition(input: TokenStream) -> TokenStream {
             fn can_transition(_from: Self, _to: Self) -> bool {
                 true
             }
-        }
-    };
 
-    TokenStream::from(expanded)
+            const READS_WORLD: bool = false;
+        }
+    })
 }
 
 /// Derive macro for generating FSM state infrastructure.
 ///
 /// This macro extends `EnumEvent` with finite state machine functionality by implementing
 /// the `FSMState` trait with variant-specific event triggering. It must be used alongside
-/// `#[derive(EnumEvent)]`.
+/// `#[derive(EnumEvent)]` (or with `#[fsm(...)]`, see below).
 ///
 /// # Requirements
 ///
-/// - Must be applied to the same enum as `#[derive(EnumEvent)]`
+/// - Must be applied to the same enum as `#[derive(EnumEvent)]`, unless `#[fsm(...)]` is used
 /// - The enum must only have unit variants (no tuple or named fields)
 /// - Depends on types from `bevy_fsm` crate: `Enter<T>`, `Exit<T>`, `Transition<F, T>`, `FSMState`
 ///
+/// Generic enums are supported: the impl carries the enum's generics and where clause, and the
+/// variant types are referenced as `module::Variant<..>` with the same arguments. Variants
+/// behind `#[cfg(...)]` keep their attributes on every generated match arm, so compiled-out
+/// variants drop out of the impl with them.
+///
+/// # Options
+///
+/// `#[fsm(module = "name", vis = ...)]` makes the derive emit the variant types itself, in a
+/// module with the given name (default: the snake-case enum name) and visibility (default:
+/// `pub`). Use it *instead of* `#[derive(EnumEvent)]`, whose module name is fixed:
+///
+/// ```rust,ignore
+/// #[derive(Component, FSMTransition, FSMState, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// #[fsm(module = "life_states", vis = pub(crate))]
+/// enum LifeFSM {
+///     Alive,
+///     Dead,
+/// }
+///
+/// fn on_death(enter: On<Enter<life_states::Dead>>) {}
+/// ```
+///
+/// `#[fsm(no_pair_events)]` skips the per-pair `Transition<module::From, module::To>` dispatch,
+/// which is N×N match arms. Use it for large enums that only observe the generic
+/// `Transition<S, S>` (plus per-variant `Enter`/`Exit`, which stay linear).
+///
+/// `#[fsm(no_variant_events)]` skips all variant-specific events, leaving the trait's no-op
+/// defaults in place. The enum then needs neither `EnumEvent` nor a variant module; pair it
+/// with `FSMPlugin::minimal()` for FSMs only observed through the generic events.
+///
+/// On variants, `#[fsm(on_enter = path, on_exit = path)]` names entry and exit actions that
+/// `FSMPlugin` adds as observers of `Enter<module::Variant>` and `Exit<module::Variant>` (see
+/// `bevy_fsm::actions`). Each key can be repeated:
+///
+/// ```rust,ignore
+/// #[derive(Component, EnumEvent, FSMTransition, FSMState, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// enum MoveFSM {
+///     Idle,
+///     #[fsm(on_enter = spawn_dust, on_exit = stop_dust)]
+///     Running,
+/// }
+/// ```
+///
+/// With bevy_fsm's `auto-register` feature, the derive submits non-generic enums to
+/// `FSMAutoPlugin`, which then needs the `FSMPlugin` bounds (`Reflect` and friends).
+/// `#[fsm(no_auto_register)]` leaves an enum out.
+///
 /// # Generated Code
 ///
 /// For an enum named `MyFSM`, this generates:
@@ -146,7 +392,17 @@ pub fn derive_fsm_transition(input: TokenStream) -> TokenStream {
 /// 1. **`FSMState` implementation** with three methods:
 ///    - `trigger_enter_variant(ec, state)` - Fires `Enter<module::Variant>` events
 ///    - `trigger_exit_variant(ec, state)` - Fires `Exit<module::Variant>` events
-///    - `trigger_transition_variant(ec, from, to)` - Fires `Transition<module::From, module::To>` events
+///    - `trigger_transition_variant(ec, from, to, source)` - Fires `Transition<module::From, module::To>` events
+///
+/// 2. **Variant listing** as inherent items: `VARIANTS` (all states in declaration order),
+///    `COUNT`, `index()` and `from_index()`.
+///
+/// 3. **Variant names**: `name()` returning the variant name, and a `FromStr` impl parsing it
+///    back (`Err` is `bevy_fsm::FSMParseError`).
+///
+/// 4. **Type-state edges**, with a `#[transitions(...)]` table from `#[derive(FSMTransition)]`:
+///    `bevy_fsm::typestate::FSMVariant` for every variant type and `FSMEdgeTo` for every
+///    listed edge, checked by `FSMTypedPath` at compile time.
 ///
 /// # Example (Zero Boilerplate - All Transitions Allowed)
 ///
@@ -204,12 +460,108 @@ pub fn derive_fsm_transition(input: TokenStream) -> TokenStream {
 ///
 /// - Panics if applied to a non-enum type
 /// - Panics if any variant has fields (only unit variants are supported for FSM)
-#[proc_macro_derive(FSMState)]
+#[proc_macro_derive(FSMState, attributes(fsm))]
 pub fn derive_fsm_state(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(fsm_state_impl(&input).unwrap_or_else(syn::Error::into_compile_error))
+}
+
+/// Options read from `#[fsm(...)]` on the enum.
+struct FSMOptions {
+    /// Module name given by `module = "..."`.
+    name: Option<syn::Ident>,
+    /// Visibility given by `vis = ...`.
+    vis: Option<syn::Visibility>,
+    /// Cleared by `no_pair_events`.
+    pair_events: bool,
+    /// Cleared by `no_variant_events`.
+    variant_events: bool,
+    /// Cleared by `no_auto_register`.
+    auto_register: bool,
+}
+
+impl FSMOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self {
+            name: None,
+            vis: None,
+            pair_events: true,
+            variant_events: true,
+            auto_register: true,
+        };
+        for attr in attrs.iter().filter(|a| a.path().is_ident("fsm")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("module") {
+                    let name: syn::LitStr = meta.value()?.parse()?;
+                    options.name = Some(name.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("vis") {
+                    options.vis = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("no_pair_events") {
+                    options.pair_events = false;
+                    Ok(())
+                } else if meta.path.is_ident("no_variant_events") {
+                    options.variant_events = false;
+                    Ok(())
+                } else if meta.path.is_ident("no_auto_register") {
+                    options.auto_register = false;
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "expected `module = \"...\"`, `vis = ...`, `no_pair_events`, `no_variant_events` or `no_auto_register`",
+                    ))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+
+    /// Whether the derive emits the variant module itself instead of relying on `EnumEvent`.
+    fn emits_module(&self) -> bool {
+        self.name.is_some() || self.vis.is_some()
+    }
+}
+
+/// Entry and exit actions read from `#[fsm(on_enter = ..., on_exit = ...)]` on a variant.
+#[derive(Default)]
+struct VariantActions {
+    on_enter: Vec<syn::Path>,
+    on_exit: Vec<syn::Path>,
+}
+
+impl VariantActions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut actions = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("fsm")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("on_enter") {
+                    actions.on_enter.push(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("on_exit") {
+                    actions.on_exit.push(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `on_enter = ...` or `on_exit = ...`"))
+                }
+            })?;
+        }
+        Ok(actions)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.on_enter.is_empty() && self.on_exit.is_empty()
+    }
+}
+
+/// Generates the `FSMState` implementation for a parsed enum.
+fn fsm_state_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let enum_name = &input.ident;
-    let generics = input.generics.clone();
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let options = FSMOptions::parse(&input.attrs)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // Variant types live in the EnumEvent module and share the enum's generics. In
+    // expression position they need a turbofish (`Variant::<N>`).
+    let turbofish = ty_generics.as_turbofish();
 
     // Extract variants from enum
     let variants = match &input.data {
@@ -226,22 +578,129 @@ pub fn derive_fsm_state(input: TokenStream) -> TokenStream {
         );
     }
 
-    let variant_idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    // `cfg` attributes are copied onto every match arm naming the variant, so arms for
+    // compiled-out variants disappear together with the variant (and its event type).
+    let variant_cfgs = collect_variant_cfgs(variants);
+
+    // Generate the module name (same as EnumEvent uses) unless `#[fsm(module = "...")]` renames it
+    let fsm_module_name = options.name.clone().unwrap_or_else(|| {
+        syn::Ident::new(&to_snake_case(&enum_name.to_string()), enum_name.span())
+    });
+
+    // With `#[fsm(...)]` the variant types are emitted here, mirroring the ones EnumEvent
+    // generates, so the enum can drop `EnumEvent` and its fixed `pub mod`.
+    let variant_module = if options.emits_module() {
+        let vis = options
+            .vis
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote!(pub));
+        let params = &input.generics.params;
+        let generics_where = &input.generics.where_clause;
+        let structs = variant_cfgs.iter().map(|(variant, cfgs)| {
+            quote! {
+                #(#cfgs)*
+                #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+                pub struct #variant<#params> #generics_where;
+            }
+        });
+        let doc = format!("Variant types of [`{enum_name}`], generated by `#[derive(FSMState)]`.");
+        quote! {
+            #[doc = #doc]
+            #[allow(unused_imports)]
+            #vis mod #fsm_module_name {
+                use super::*;
+                #(#structs)*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // `add_actions` registering the variants' entry and exit actions
+    let mut action_calls = Vec::new();
+    for (variant, (_, cfgs)) in variants.iter().zip(&variant_cfgs) {
+        let actions = VariantActions::parse(&variant.attrs)?;
+        if actions.is_empty() {
+            continue;
+        }
+        if !options.variant_events {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                "`on_enter` and `on_exit` need the variant events, remove `no_variant_events`",
+            ));
+        }
+        let ident = &variant.ident;
+        let variant_ty = quote! { #fsm_module_name::#ident #ty_generics };
+        for (event, paths) in [
+            (quote! { bevy_fsm::Enter }, &actions.on_enter),
+            (quote! { bevy_fsm::Exit }, &actions.on_exit),
+        ] {
+            for path in paths {
+                action_calls.push(quote! {
+                    #(#cfgs)*
+                    bevy_fsm::actions::add_action::<Self, #event<#variant_ty>, _>(world, #path);
+                });
+            }
+        }
+    }
+    let actions_method = if action_calls.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            /// Adds the entry and exit actions declared with `#[fsm(on_enter = ..., on_exit = ...)]`.
+            fn add_actions(world: &mut bevy::prelude::World) {
+                #(#action_calls)*
+            }
+        }
+    };
 
-    // Generate the module name (same as EnumEvent uses)
-    let module_name_str = to_snake_case(&enum_name.to_string());
-    let fsm_module_name = syn::Ident::new(&module_name_str, enum_name.span());
+    // Type-state impls for `FSMTypedPath`, only when a table lists the edges and the variant
+    // types exist
+    let edges = parse_transition_table(&input.attrs)?;
+    let typestate = if !edges.is_empty() && (options.variant_events || options.emits_module()) {
+        let cfgs_of = |ident: &syn::Ident| {
+            variant_cfgs
+                .iter()
+                .find(|(variant, _)| *variant == ident)
+                .map(|(_, cfgs)| cfgs.clone())
+                .unwrap_or_default()
+        };
+        let variant_impls = variant_cfgs.iter().map(|(variant, cfgs)| {
+            quote! {
+                #(#cfgs)*
+                impl #impl_generics bevy_fsm::typestate::FSMVariant<#enum_name #ty_generics> for #fsm_module_name::#variant #ty_generics #where_clause {
+                    const STATE: #enum_name #ty_generics = #enum_name::#variant;
+                }
+            }
+        });
+        let edge_impls = edges.iter().map(|Edge { from, to, .. }| {
+            let (from_cfgs, to_cfgs) = (cfgs_of(from), cfgs_of(to));
+            quote! {
+                #(#from_cfgs)*
+                #(#to_cfgs)*
+                impl #impl_generics bevy_fsm::typestate::FSMEdgeTo<#fsm_module_name::#to #ty_generics> for #fsm_module_name::#from #ty_generics #where_clause {}
+            }
+        });
+        quote! {
+            #(#variant_impls)*
+            #(#edge_impls)*
+        }
+    } else {
+        quote! {}
+    };
 
     // Generate Enter event triggers for each variant
-    let enter_triggers: Vec<_> = variant_idents
+    let enter_triggers: Vec<_> = variant_cfgs
         .iter()
-        .map(|variant| {
+        .map(|(variant, cfgs)| {
             let variant_ty = quote! { #fsm_module_name::#variant #ty_generics };
             quote! {
+                #(#cfgs)*
                 #enum_name::#variant => {
                     commands.trigger(bevy_fsm::Enter::<#variant_ty> {
                         entity,
-                        state: #variant_ty,
+                        state: #fsm_module_name::#variant #turbofish,
+                        previous: None,
                     });
                 }
             }
@@ -249,15 +708,17 @@ pub fn derive_fsm_state(input: TokenStream) -> TokenStream {
         .collect();
 
     // Generate Exit event triggers for each variant
-    let exit_triggers: Vec<_> = variant_idents
+    let exit_triggers: Vec<_> = variant_cfgs
         .iter()
-        .map(|variant| {
+        .map(|(variant, cfgs)| {
             let variant_ty = quote! { #fsm_module_name::#variant #ty_generics };
             quote! {
+                #(#cfgs)*
                 #enum_name::#variant => {
                     commands.trigger(bevy_fsm::Exit::<#variant_ty> {
                         entity,
-                        state: #variant_ty,
+                        state: #fsm_module_name::#variant #turbofish,
+                        next: None,
                     });
                 }
             }
@@ -266,25 +727,46 @@ pub fn derive_fsm_state(input: TokenStream) -> TokenStream {
 
     // Generate all pairs of transition types (N × N combinations)
     let mut transition_triggers = Vec::new();
-    for from_variant in &variant_idents {
-        for to_variant in &variant_idents {
+    for (from_variant, from_cfgs) in &variant_cfgs {
+        for (to_variant, to_cfgs) in &variant_cfgs {
             let from_ty = quote! { #fsm_module_name::#from_variant #ty_generics };
             let to_ty = quote! { #fsm_module_name::#to_variant #ty_generics };
             transition_triggers.push(quote! {
+                #(#from_cfgs)*
+                #(#to_cfgs)*
                 (#enum_name::#from_variant, #enum_name::#to_variant) => {
                     commands.trigger(bevy_fsm::Transition::<#from_ty, #to_ty> {
                         entity,
-                        from: #from_ty,
-                        to: #to_ty,
+                        from: #fsm_module_name::#from_variant #turbofish,
+                        to: #fsm_module_name::#to_variant #turbofish,
+                        source,
                     });
                 }
             });
         }
     }
 
-    let expanded = quote! {
-        // Implement the FSMState trait methods
-        impl #impl_generics bevy_fsm::FSMState for #enum_name #ty_generics #where_clause {
+    // The pair dispatch is the only part of the output that grows quadratically with the number
+    // of variants; `#[fsm(no_pair_events)]` leaves the trait's no-op default in place.
+    let transition_method = if options.pair_events && options.variant_events {
+        quote! {
+            /// Triggers variant-specific Transition event.
+            ///
+            /// This method is generated by `#[derive(FSMState)]` and is used internally
+            /// by the bevy_fsm framework to fire Transition events between specific state variants.
+            fn trigger_transition_variant(commands: &mut bevy::prelude::Commands, entity: bevy::prelude::Entity, from: Self, to: Self, source: Option<bevy::prelude::Entity>) {
+                match (from, to) {
+                    #(#transition_triggers)*
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Without variant events the trait's no-op defaults stay in place
+    let variant_methods = if options.variant_events {
+        quote! {
             /// Triggers variant-specific Enter event.
             ///
             /// This method is generated by `#[derive(FSMState)]` and is used internally
@@ -305,19 +787,105 @@ pub fn derive_fsm_state(input: TokenStream) -> TokenStream {
                 }
             }
 
-            /// Triggers variant-specific Transition event.
-            ///
-            /// This method is generated by `#[derive(FSMState)]` and is used internally
-            /// by the bevy_fsm framework to fire Transition events between specific state variants.
-            fn trigger_transition_variant(commands: &mut bevy::prelude::Commands, entity: bevy::prelude::Entity, from: Self, to: Self) {
-                match (from, to) {
-                    #(#transition_triggers)*
-                }
+            #transition_method
+
+            #actions_method
+        }
+    } else {
+        quote! {}
+    };
+
+    // Variant listing. Counts and indices are built from `()` slices carrying the variants'
+    // `cfg` attributes, so they stay correct when variants are compiled out.
+    let all_cfgs: Vec<_> = variant_cfgs.iter().map(|(_, cfgs)| cfgs).collect();
+    let count = enabled_count(&all_cfgs);
+    let variant_list = variant_cfgs.iter().map(|(variant, cfgs)| {
+        quote! { #(#cfgs)* #enum_name::#variant }
+    });
+    let name_arms = variant_cfgs.iter().map(|(variant, cfgs)| {
+        let name = variant.to_string();
+        quote! { #(#cfgs)* #enum_name::#variant => #name, }
+    });
+    let parse_arms = variant_cfgs.iter().map(|(variant, cfgs)| {
+        let name = variant.to_string();
+        quote! { #(#cfgs)* #name => Ok(#enum_name::#variant), }
+    });
+    let enum_name_str = enum_name.to_string();
+    let index_arms = variant_cfgs.iter().enumerate().map(|(i, (variant, cfgs))| {
+        let before = enabled_count(&all_cfgs[..i]);
+        quote! { #(#cfgs)* #enum_name::#variant => #before, }
+    });
+
+    // Only concrete types can be submitted; generic FSMs are added by hand
+    let registration = if cfg!(feature = "auto-register")
+        && options.auto_register
+        && input.generics.params.is_empty()
+    {
+        quote! {
+            bevy_fsm::auto_register::inventory::submit! {
+                bevy_fsm::auto_register::FSMRegistration::new::<#enum_name>(#enum_name_str)
             }
         }
+    } else {
+        quote! {}
     };
 
-    TokenStream::from(expanded)
+    Ok(quote! {
+        #variant_module
+        #registration
+        #typestate
+
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            /// All states, in declaration order.
+            pub const VARIANTS: [Self; #count] = [#(#variant_list),*];
+
+            /// Number of states.
+            pub const COUNT: usize = #count;
+
+            /// Name of the variant, as written in the enum.
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    #(#name_arms)*
+                }
+            }
+
+            /// Position of this state in [`Self::VARIANTS`].
+            pub const fn index(&self) -> usize {
+                match self {
+                    #(#index_arms)*
+                }
+            }
+
+            /// State at `index` in [`Self::VARIANTS`], if in range.
+            pub const fn from_index(index: usize) -> Option<Self> {
+                if index < Self::COUNT {
+                    Some(Self::VARIANTS[index])
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl #impl_generics core::str::FromStr for #enum_name #ty_generics #where_clause {
+            type Err = bevy_fsm::FSMParseError;
+
+            /// Parses a variant name (case-sensitive, as returned by `name()`).
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    #(#parse_arms)*
+                    _ => Err(bevy_fsm::FSMParseError {
+                        fsm: #enum_name_str,
+                        name: s.to_string(),
+                    }),
+                }
+            }
+        }
+
+        // Implement the FSMState trait methods
+        impl #impl_generics bevy_fsm::FSMState for #enum_name #ty_generics #where_clause {
+            #variant_methods
+        }
+    })
 }
 
 #[cfg(test)]
@@ -332,4 +900,191 @@ mod tests {
         assert_eq!(to_snake_case("FSM"), "fsm");
         assert_eq!(to_snake_case("MyHTTPSConnection"), "my_https_connection");
     }
+
+    #[test]
+    fn fsm_state_supports_const_generics_and_cfg_variants() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum SlotFSM<const N: usize> {
+                Empty,
+                #[cfg(feature = "ammo")]
+                Loaded,
+            }
+        };
+        let output = fsm_state_impl(&input).unwrap().to_string();
+
+        assert!(output.contains("impl < const N : usize > bevy_fsm :: FSMState for SlotFSM < N >"));
+        assert!(output.contains("Enter :: < slot_fsm :: Empty < N > >"));
+        assert!(output.contains("state : slot_fsm :: Empty :: < N >"));
+        // Enter + Exit arm, Empty <-> Loaded arms, twice on the Loaded -> Loaded arm, and
+        // the variant listing: two counts, the VARIANTS entry, the index, name and parse arms
+        assert_eq!(
+            output.matches("# [cfg (feature = \"ammo\")]").count(),
+            2 + 2 + 2 + 6
+        );
+    }
+
+    #[test]
+    fn fsm_attribute_emits_renamed_module() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[fsm(module = "life_states", vis = pub(crate))]
+            enum LifeFSM {
+                Alive,
+                Dead,
+            }
+        };
+        let output = fsm_state_impl(&input).unwrap().to_string();
+
+        assert!(output.contains("pub (crate) mod life_states"));
+        assert!(output.contains("pub struct Alive < >"));
+        assert!(output.contains("Enter :: < life_states :: Dead > "));
+        assert!(!output.contains("life_fsm"));
+        assert!(output.contains("pub const VARIANTS : [Self ;"));
+        assert!(output.contains("LifeFSM :: Alive => \"Alive\""));
+        assert!(output.contains("\"Dead\" => Ok (LifeFSM :: Dead)"));
+        assert!(output.contains("LifeFSM :: Dead => { const ENABLED : & [()] = & [()] ;"));
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[fsm(name = "life_states")]
+            enum LifeFSM { Alive }
+        };
+        assert!(fsm_state_impl(&input).is_err());
+    }
+
+    #[test]
+    fn no_pair_events_skips_transition_dispatch() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[fsm(no_pair_events)]
+            enum LifeFSM {
+                Alive,
+                Dead,
+            }
+        };
+        let output = fsm_state_impl(&input).unwrap().to_string();
+
+        assert!(output.contains("fn trigger_enter_variant"));
+        assert!(!output.contains("trigger_transition_variant"));
+        assert!(!output.contains("mod life_fsm"));
+    }
+
+    #[test]
+    fn no_variant_events_skips_all_variant_dispatch() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[fsm(no_variant_events)]
+            enum LifeFSM {
+                Alive,
+                Dead,
+            }
+        };
+        let output = fsm_state_impl(&input).unwrap().to_string();
+
+        assert!(output.contains("impl bevy_fsm :: FSMState for LifeFSM"));
+        assert!(!output.contains("trigger_"));
+        assert!(!output.contains("life_fsm"));
+        assert!(output.contains("pub const VARIANTS"));
+    }
+
+    #[test]
+    fn transition_table_generates_rules_and_guards() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[transitions(Idle => Casting if guards::has_energy, Casting => Idle)]
+            enum MageFSM {
+                Idle,
+                Casting,
+            }
+        };
+        let output = fsm_transition_impl(&input).unwrap().to_string();
+
+        // Idle -> Casting is (0, 1), Casting -> Idle is (1, 0)
+        assert!(output
+            .contains("const ADJACENCY : Option < & 'static bevy_fsm :: rules :: FSMAdjacency >"));
+        assert!(output.contains("FSMAdjacency :: words (COUNT , & [({ const ENABLED : & [()] = & [] ; ENABLED . len () } , { const ENABLED : & [()] = & [()] ; ENABLED . len () }) ,"));
+        assert!(output.contains(
+            "MageFSM :: Casting => { const ENABLED : & [()] = & [()] ; ENABLED . len () } ,"
+        ));
+        assert!(output.contains(
+            "(MageFSM :: Idle , MageFSM :: Casting) => { bevy_fsm :: rules :: TransitionGuard :: check (& guards :: has_energy , ctx) }"
+        ));
+
+        let output = fsm_state_impl(&input).unwrap().to_string();
+        assert!(output
+            .contains("impl bevy_fsm :: typestate :: FSMVariant < MageFSM > for mage_fsm :: Idle"));
+        assert!(output.contains(
+            "impl bevy_fsm :: typestate :: FSMEdgeTo < mage_fsm :: Idle > for mage_fsm :: Casting"
+        ));
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[transitions(Idle => Casting)]
+            enum MageFSM { Idle, Casting }
+        };
+        let output = fsm_transition_impl(&input).unwrap().to_string();
+        assert!(!output.contains("can_transition_ctx"));
+        let output = fsm_state_impl(&input).unwrap().to_string();
+        assert!(!output.contains("FSMEdgeTo < mage_fsm :: Idle >"));
+
+        for input in [
+            syn::parse_quote! {
+                #[transitions(Idle -> Casting)]
+                enum MageFSM { Idle, Casting }
+            },
+            syn::parse_quote! {
+                #[transitions(Idle => Flying)]
+                enum MageFSM { Idle, Casting }
+            },
+        ] {
+            assert!(fsm_transition_impl(&input).is_err());
+        }
+    }
+
+    #[test]
+    fn variant_actions_generate_add_actions() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum MoveFSM {
+                Idle,
+                #[fsm(on_enter = spawn_dust, on_enter = effects::shake, on_exit = stop_dust)]
+                Running,
+            }
+        };
+        let output = fsm_state_impl(&input).unwrap().to_string();
+
+        assert!(output.contains("fn add_actions"));
+        assert!(output.contains(
+            "add_action :: < Self , bevy_fsm :: Enter < move_fsm :: Running > , _ > (world , spawn_dust)"
+        ));
+        assert!(output.contains("(world , effects :: shake)"));
+        assert!(
+            output.contains("bevy_fsm :: Exit < move_fsm :: Running > , _ > (world , stop_dust)")
+        );
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[fsm(no_variant_events)]
+            enum MoveFSM {
+                #[fsm(on_enter = spawn_dust)]
+                Running,
+            }
+        };
+        assert!(fsm_state_impl(&input).is_err());
+    }
+
+    #[cfg(feature = "auto-register")]
+    #[test]
+    fn auto_register_submits_concrete_enums_only() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum LifeFSM { Alive, Dead }
+        };
+        let output = fsm_state_impl(&input).unwrap().to_string();
+        assert!(output.contains("FSMRegistration :: new :: < LifeFSM > (\"LifeFSM\")"));
+
+        for input in [
+            syn::parse_quote! {
+                #[fsm(no_auto_register)]
+                enum LifeFSM { Alive, Dead }
+            },
+            syn::parse_quote! {
+                enum SlotFSM<const N: usize> { Empty, Loaded }
+            },
+        ] {
+            let output = fsm_state_impl(&input).unwrap().to_string();
+            assert!(!output.contains("FSMRegistration"));
+        }
+    }
 }
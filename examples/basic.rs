@@ -80,6 +80,7 @@ fn setup(mut commands: Commands) {
 }
 
 /// System to trigger state transitions for demonstration
+#[allow(clippy::too_many_arguments)]
 fn trigger_transitions(
     mut commands: Commands,
     query: Query<(Entity, &LifeFSM, &Name), With<TestEntity>>,
@@ -98,10 +99,7 @@ fn trigger_transitions(
         if *elapsed >= 1.0 && !*triggered_10 {
             *triggered_10 = true;
             println!("\n--- Triggering transition: {} Alive -> Dying ---", name);
-            commands.trigger(StateChangeRequest {
-                entity,
-                next: LifeFSM::Dying,
-            });
+            commands.trigger(StateChangeRequest::new(entity, LifeFSM::Dying));
         }
 
         // At ~2 seconds: Dying -> Alive (resurrection)
@@ -111,30 +109,21 @@ fn trigger_transitions(
                 "\n--- Triggering transition: {} Dying -> Alive (Resurrection!) ---",
                 name
             );
-            commands.trigger(StateChangeRequest {
-                entity,
-                next: LifeFSM::Alive,
-            });
+            commands.trigger(StateChangeRequest::new(entity, LifeFSM::Alive));
         }
 
         // At ~3 seconds: Alive -> Dying again
         if *elapsed >= 3.0 && !*triggered_30 && state == LifeFSM::Alive {
             *triggered_30 = true;
             println!("\n--- Triggering transition: {} Alive -> Dying ---", name);
-            commands.trigger(StateChangeRequest {
-                entity,
-                next: LifeFSM::Dying,
-            });
+            commands.trigger(StateChangeRequest::new(entity, LifeFSM::Dying));
         }
 
         // At ~4 seconds: Dying -> Dead
         if *elapsed >= 4.0 && !*triggered_40 && state == LifeFSM::Dying {
             *triggered_40 = true;
             println!("\n--- Triggering transition: {} Dying -> Dead ---", name);
-            commands.trigger(StateChangeRequest {
-                entity,
-                next: LifeFSM::Dead,
-            });
+            commands.trigger(StateChangeRequest::new(entity, LifeFSM::Dead));
         }
 
         // At ~5 seconds: Exit
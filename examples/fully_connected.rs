@@ -90,7 +90,7 @@ fn cycle_states(
             if *frame < 50 {
                 println!("{} transitioning: {:?} -> {:?}", name, state, next);
             }
-            commands.trigger(StateChangeRequest { entity, next });
+            commands.trigger(StateChangeRequest::new(entity, next));
         }
     }
 }
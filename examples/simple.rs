@@ -83,7 +83,7 @@ fn cycle_states(
 
             if let Some(next) = next_state {
                 println!("\n{} transitioning: {:?} -> {:?}", name, state, next);
-                commands.trigger(StateChangeRequest { entity, next });
+                commands.trigger(StateChangeRequest::new(entity, next));
             }
         }
     }
@@ -111,10 +111,7 @@ fn drive_state_transitions(
                 "  {}xpecting transition",
                 if allowed { "E" } else { "NOT e" }
             );
-            commands.trigger(StateChangeRequest {
-                entity,
-                next: target,
-            });
+            commands.trigger(StateChangeRequest::new(entity, target));
         }
     }
 }
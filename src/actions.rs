@@ -0,0 +1,200 @@
+//! Entry and exit actions declared on the state enum.
+//!
+//! `#[fsm(on_enter = ..., on_exit = ...)]` on a variant of a `#[derive(FSMState)]` enum names
+//! functions that [`FSMPlugin`](crate::FSMPlugin) adds as observers of the variant's `Enter`
+//! and `Exit` events, keeping a state's behavior next to its declaration:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{Enter, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum MoveFSM {
+//!     Idle,
+//!     #[fsm(on_enter = spawn_dust, on_exit = stop_dust)]
+//!     Running,
+//! }
+//!
+//! // An observer of the variant event
+//! fn spawn_dust(enter: On<Enter<move_fsm::Running>>, mut commands: Commands) {
+//!     commands.entity(enter.entity).insert(Name::new("dusty"));
+//! }
+//!
+//! // A system getting the entity as input
+//! fn stop_dust(In(entity): In<Entity>, mut commands: Commands) {
+//!     commands.entity(entity).remove::<Name>();
+//! }
+//! ```
+//!
+//! An action is an observer of the variant's `Enter<module::Variant>` or
+//! `Exit<module::Variant>`, a system taking the entity as `In<Entity>`, or a system without
+//! input. Systems run through commands after the event, like a one-shot system. Actions need
+//! the variant events, so they are not added by [`FSMPlugin::minimal`](crate::FSMPlugin::minimal)
+//! and can't be combined with `#[fsm(no_variant_events)]`. Their observers are named after the
+//! function and added under the hierarchy group of the FSM.
+
+use bevy::ecs::system::IntoObserverSystem;
+use bevy::prelude::*;
+
+use crate::{attach_observer_to_group, observer_name, FSMObserverMarker};
+
+/// A function usable as an entry or exit action for the event `E`.
+///
+/// Implemented for observers of `E`, systems taking `In<Entity>` and systems without input;
+/// `M` only tells the implementations apart.
+pub trait FSMAction<E: EntityEvent, M> {
+    /// Adds the observer running the action, returning its entity.
+    fn add(self, world: &mut World) -> Entity;
+}
+
+#[doc(hidden)]
+pub struct IsObserverAction;
+
+#[doc(hidden)]
+pub struct IsEntitySystemAction;
+
+#[doc(hidden)]
+pub struct IsSystemAction;
+
+impl<E: EntityEvent, B: Bundle, M, T> FSMAction<E, (IsObserverAction, B, M)> for T
+where
+    T: IntoObserverSystem<E, B, M>,
+{
+    fn add(self, world: &mut World) -> Entity {
+        world.add_observer(self).id()
+    }
+}
+
+impl<E: EntityEvent, M, T> FSMAction<E, (IsEntitySystemAction, M)> for T
+where
+    T: IntoSystem<In<Entity>, (), M> + 'static,
+{
+    fn add(self, world: &mut World) -> Entity {
+        let system = world.register_system(self);
+        world
+            .add_observer(move |event: On<E>, mut commands: Commands| {
+                commands.run_system_with(system, event.event().event_target());
+            })
+            .id()
+    }
+}
+
+impl<E: EntityEvent, M, T> FSMAction<E, (IsSystemAction, M)> for T
+where
+    T: IntoSystem<(), (), M> + 'static,
+{
+    fn add(self, world: &mut World) -> Entity {
+        let system = world.register_system(self);
+        world
+            .add_observer(move |_: On<E>, mut commands: Commands| {
+                commands.run_system(system);
+            })
+            .id()
+    }
+}
+
+/// Adds `action` for the event `E` under the hierarchy group of `S`.
+///
+/// Called by the code `#[derive(FSMState)]` generates for `on_enter` and `on_exit`.
+pub fn add_action<S: Send + Sync + 'static, E: EntityEvent, M>(
+    world: &mut World,
+    action: impl FSMAction<E, M>,
+) {
+    let name = observer_name(&action);
+    let entity = action.add(world);
+    world
+        .entity_mut(entity)
+        .insert((Name::new(name), FSMObserverMarker::<S>::default()));
+    attach_observer_to_group::<S>(world, entity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, Exit, FSMPlugin, FSMState, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum MoveFSM {
+        Idle,
+        Running,
+    }
+
+    /// Stand-in for the variant type the derive would generate.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct Running;
+
+    impl FSMTransition for MoveFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    // What `#[fsm(on_enter = spawn_dust, on_exit = stop_dust)]` on `Running` expands to
+    impl FSMState for MoveFSM {
+        fn trigger_enter_variant(commands: &mut Commands, entity: Entity, state: Self) {
+            if state == MoveFSM::Running {
+                commands.trigger(Enter {
+                    entity,
+                    state: Running,
+                    previous: None,
+                });
+            }
+        }
+
+        fn trigger_exit_variant(commands: &mut Commands, entity: Entity, state: Self) {
+            if state == MoveFSM::Running {
+                commands.trigger(Exit {
+                    entity,
+                    state: Running,
+                    next: None,
+                });
+            }
+        }
+
+        fn add_actions(world: &mut World) {
+            add_action::<Self, Enter<Running>, _>(world, spawn_dust);
+            add_action::<Self, Exit<Running>, _>(world, stop_dust);
+            add_action::<Self, Exit<Running>, _>(world, count_stops);
+        }
+    }
+
+    #[derive(Component)]
+    struct Dust;
+
+    #[derive(Resource, Default)]
+    struct Stops(usize);
+
+    fn spawn_dust(enter: On<Enter<Running>>, mut commands: Commands) {
+        commands.entity(enter.entity).insert(Dust);
+    }
+
+    fn stop_dust(In(entity): In<Entity>, mut commands: Commands) {
+        commands.entity(entity).remove::<Dust>();
+    }
+
+    fn count_stops(mut stops: ResMut<Stops>) {
+        stops.0 += 1;
+    }
+
+    #[test]
+    fn declared_actions_run_on_enter_and_exit() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<MoveFSM>::default());
+        app.init_resource::<Stops>();
+        let runner = app.world_mut().spawn(MoveFSM::Running).id();
+        app.update();
+        assert!(app.world().entity(runner).contains::<Dust>());
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(runner, MoveFSM::Idle));
+        app.update();
+        assert!(!app.world().entity(runner).contains::<Dust>());
+        assert_eq!(app.world().resource::<Stops>().0, 1);
+
+        let mut names = app
+            .world_mut()
+            .query_filtered::<&Name, With<FSMObserverMarker<MoveFSM>>>();
+        let names: Vec<_> = names.iter(app.world()).map(Name::as_str).collect();
+        assert!(names.contains(&"spawn_dust"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Play animations when entering FSM states.
+//!
+//! Enabled by the `animation` feature. Map states to nodes of the entity's
+//! [`AnimationGraph`] on the plugin, and the mapped animation is played whenever the state is
+//! entered:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy::animation::graph::AnimationNodeIndex;
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # use std::time::Duration;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum MoveFSM { Idle, Run }
+//! # let (idle_node, run_node) = (AnimationNodeIndex::new(1), AnimationNodeIndex::new(2));
+//! # let mut app = App::new();
+//! app.add_plugins(
+//!     FSMPlugin::<MoveFSM>::default()
+//!         .with_animation(MoveFSM::Idle, idle_node)
+//!         .with_crossfade(MoveFSM::Run, run_node, Duration::from_millis(200)),
+//! );
+//! ```
+//!
+//! The animation plays on the [`AnimationPlayer`] of the FSM entity itself, of the entity
+//! named by [`FSMAnimationTarget`], or else of the first descendant carrying one (the usual
+//! layout for glTF scenes). Crossfades use the player's [`AnimationTransitions`] component
+//! when present.
+
+use std::time::Duration;
+
+use bevy::animation::graph::AnimationNodeIndex;
+use bevy::animation::prelude::{AnimationPlayer, AnimationTransitions};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::propagation;
+use crate::{attach_observer_to_group, Enter, FSMObserverMarker, FSMState};
+
+/// Animation mapped to a state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FSMAnimation {
+    /// The node of the animation graph to play.
+    pub node: AnimationNodeIndex,
+    /// Fade-in duration when an [`AnimationTransitions`] component is present.
+    pub crossfade: Duration,
+    /// Whether the animation loops.
+    pub repeat: bool,
+}
+
+/// Points an FSM entity at the entity carrying its [`AnimationPlayer`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FSMAnimationTarget(pub Entity);
+
+/// State-to-animation mapping for `S`, inserted by [`FSMPlugin`](crate::FSMPlugin).
+#[derive(Resource, Debug, Clone)]
+pub struct FSMAnimationMap<S: FSMState + core::hash::Hash> {
+    animations: HashMap<S, FSMAnimation>,
+}
+
+impl<S: FSMState + core::hash::Hash> FSMAnimationMap<S> {
+    /// The animation mapped to `state`, if any.
+    pub fn get(&self, state: S) -> Option<&FSMAnimation> {
+        self.animations.get(&state)
+    }
+}
+
+/// Inserts the mapping. Does nothing for an empty mapping.
+pub(crate) fn register<S: FSMState + core::hash::Hash>(
+    app: &mut App,
+    animations: &HashMap<S, FSMAnimation>,
+) {
+    if animations.is_empty() {
+        return;
+    }
+    app.insert_resource(FSMAnimationMap {
+        animations: animations.clone(),
+    });
+}
+
+/// Adds the observer playing the mapping under the hierarchy group of `S`, if there is one.
+pub(crate) fn add_observer<S: FSMState + core::hash::Hash>(world: &mut World) {
+    if !world.contains_resource::<FSMAnimationMap<S>>() {
+        return;
+    }
+    let observer = world
+        .add_observer(play_state_animation::<S>)
+        .insert((
+            Name::new("play_state_animation"),
+            FSMObserverMarker::<S>::default(),
+        ))
+        .id();
+    attach_observer_to_group::<S>(world, observer);
+}
+
+/// Observer playing the mapped animation when a state is entered.
+#[allow(clippy::needless_pass_by_value)]
+pub fn play_state_animation<S: FSMState + core::hash::Hash>(
+    trigger: On<Enter<S>>,
+    map: Res<FSMAnimationMap<S>>,
+    q_targets: Query<&FSMAnimationTarget>,
+    q_children: Query<&Children>,
+    mut q_players: Query<(&mut AnimationPlayer, Option<&mut AnimationTransitions>)>,
+) {
+    if propagation::bubbled(&trigger) {
+        return;
+    }
+    let event = trigger.event();
+    let Some(animation) = map.get(event.state) else {
+        return;
+    };
+
+    let player_entity = match q_targets.get(event.entity) {
+        Ok(target) => Some(target.0),
+        Err(_) if q_players.contains(event.entity) => Some(event.entity),
+        Err(_) => q_children
+            .iter_descendants(event.entity)
+            .find(|&descendant| q_players.contains(descendant)),
+    };
+    let Some(Ok((mut player, transitions))) = player_entity.map(|e| q_players.get_mut(e)) else {
+        return;
+    };
+
+    let active = match transitions {
+        Some(mut transitions) => transitions.play(&mut player, animation.node, animation.crossfade),
+        None => {
+            player.stop_all();
+            player.play(animation.node)
+        }
+    };
+    if animation.repeat {
+        active.repeat();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum MoveFSM {
+        Idle,
+        Run,
+    }
+
+    impl FSMState for MoveFSM {}
+
+    impl FSMTransition for MoveFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn entering_a_state_plays_its_animation_on_a_descendant_player() {
+        let idle = AnimationNodeIndex::new(1);
+        let run = AnimationNodeIndex::new(2);
+        let mut app = App::new();
+        app.add_plugins(
+            FSMPlugin::<MoveFSM>::default()
+                .with_animation(MoveFSM::Idle, idle)
+                .with_animation(MoveFSM::Run, run),
+        );
+
+        let character = app.world_mut().spawn(MoveFSM::Idle).id();
+        let rig = app
+            .world_mut()
+            .spawn((AnimationPlayer::default(), ChildOf(character)))
+            .id();
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(character, MoveFSM::Run));
+        app.world_mut().flush();
+
+        let player = app.world().get::<AnimationPlayer>(rig).unwrap();
+        assert!(player.is_playing_animation(run));
+        assert!(!player.is_playing_animation(idle));
+    }
+}
@@ -0,0 +1,185 @@
+//! Play sounds when entering or exiting FSM states.
+//!
+//! Enabled by the `audio` feature. Fill an [`FSMAudioMap`] resource (typically in a startup
+//! system, once the handles are loaded) and add [`FSMAudioPlugin`]; door-open and door-close
+//! sounds for FSM-driven props then need no custom observers:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::audio::{FSMAudioMap, FSMAudioPlugin};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum DoorFSM { Open, Closed }
+//! fn load_door_sounds(mut commands: Commands, assets: Res<AssetServer>) {
+//!     commands.insert_resource(
+//!         FSMAudioMap::<DoorFSM>::default()
+//!             .on_enter(DoorFSM::Open, assets.load("sounds/door_open.ogg"))
+//!             .on_enter(DoorFSM::Closed, assets.load("sounds/door_close.ogg")),
+//!     );
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins((FSMPlugin::<DoorFSM>::default(), FSMAudioPlugin::<DoorFSM>::default()))
+//!     .add_systems(Startup, load_door_sounds);
+//! ```
+//!
+//! Each sound is spawned as a self-despawning child of the FSM entity, so spatial sounds
+//! follow it.
+
+use bevy::audio::{AudioPlayer, AudioSource, PlaybackSettings};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::propagation;
+use crate::{Enter, Exit, FSMState};
+
+/// Sounds played on Enter/Exit of the states of `S`.
+#[derive(Resource, Debug, Clone)]
+pub struct FSMAudioMap<S: FSMState + core::hash::Hash> {
+    enter: HashMap<S, Handle<AudioSource>>,
+    exit: HashMap<S, Handle<AudioSource>>,
+    spatial: bool,
+}
+
+impl<S: FSMState + core::hash::Hash> Default for FSMAudioMap<S> {
+    fn default() -> Self {
+        Self {
+            enter: HashMap::default(),
+            exit: HashMap::default(),
+            spatial: false,
+        }
+    }
+}
+
+impl<S: FSMState + core::hash::Hash> FSMAudioMap<S> {
+    /// Play `sound` whenever `state` is entered.
+    #[must_use]
+    pub fn on_enter(mut self, state: S, sound: Handle<AudioSource>) -> Self {
+        self.enter.insert(state, sound);
+        self
+    }
+
+    /// Play `sound` whenever `state` is exited.
+    #[must_use]
+    pub fn on_exit(mut self, state: S, sound: Handle<AudioSource>) -> Self {
+        self.exit.insert(state, sound);
+        self
+    }
+
+    /// Play sounds spatially at the FSM entity's position.
+    #[must_use]
+    pub fn spatial(mut self, spatial: bool) -> Self {
+        self.spatial = spatial;
+        self
+    }
+
+    fn play(&self, commands: &mut Commands, entity: Entity, sound: &Handle<AudioSource>) {
+        let settings = PlaybackSettings::DESPAWN.with_spatial(self.spatial);
+        commands.spawn((
+            AudioPlayer::new(sound.clone()),
+            settings,
+            Transform::default(),
+            ChildOf(entity),
+        ));
+    }
+}
+
+/// Plugin playing the sounds of [`FSMAudioMap<S>`].
+pub struct FSMAudioPlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMAudioPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: FSMState + core::hash::Hash> Plugin for FSMAudioPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FSMAudioMap<S>>()
+            .add_observer(play_enter_sound::<S>)
+            .add_observer(play_exit_sound::<S>);
+    }
+}
+
+/// Observer playing the sound mapped to the entered state.
+#[allow(clippy::needless_pass_by_value)]
+pub fn play_enter_sound<S: FSMState + core::hash::Hash>(
+    trigger: On<Enter<S>>,
+    map: Res<FSMAudioMap<S>>,
+    mut commands: Commands,
+) {
+    if propagation::bubbled(&trigger) {
+        return;
+    }
+    let event = trigger.event();
+    if let Some(sound) = map.enter.get(&event.state) {
+        map.play(&mut commands, event.entity, sound);
+    }
+}
+
+/// Observer playing the sound mapped to the exited state.
+#[allow(clippy::needless_pass_by_value)]
+pub fn play_exit_sound<S: FSMState + core::hash::Hash>(
+    trigger: On<Exit<S>>,
+    map: Res<FSMAudioMap<S>>,
+    mut commands: Commands,
+) {
+    if propagation::bubbled(&trigger) {
+        return;
+    }
+    let event = trigger.event();
+    if let Some(sound) = map.exit.get(&event.state) {
+        map.play(&mut commands, event.entity, sound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Open,
+        Closed,
+    }
+
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn transitions_spawn_mapped_sounds_as_children() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<DoorFSM>::default(),
+            FSMAudioPlugin::<DoorFSM>::default(),
+        ));
+        let open = Handle::<AudioSource>::default();
+        app.insert_resource(
+            FSMAudioMap::<DoorFSM>::default()
+                .on_enter(DoorFSM::Open, open.clone())
+                .on_exit(DoorFSM::Closed, open.clone()),
+        );
+
+        let door = app.world_mut().spawn(DoorFSM::Closed).id();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(door, DoorFSM::Open));
+        app.world_mut().flush();
+
+        let children = app.world().get::<Children>(door).unwrap();
+        assert_eq!(children.len(), 2);
+        assert!(children
+            .iter()
+            .all(|child| app.world().get::<AudioPlayer>(child).is_some()));
+    }
+}
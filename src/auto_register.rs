@@ -0,0 +1,256 @@
+//! Registering every FSM type in the binary automatically.
+//!
+//! With the `auto-register` feature, `#[derive(FSMState)]` submits each (non-generic) enum to
+//! a link-time registry, and [`FSMAutoPlugin`] adds an `FSMPlugin` for all of them. A newly
+//! created FSM can't be forgotten in the plugin list:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMAutoPlugin, FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum DoorFSM { Open, Closed }
+//! # let mut app = App::new();
+//! // Configured plugins go first; FSMAutoPlugin skips types that already have one
+//! app.add_plugins(FSMPlugin::<DoorFSM>::buffered())
+//!     .add_plugins(FSMAutoPlugin);
+//! ```
+//!
+//! Every submitted type needs the `FSMPlugin` bounds (`Reflect`, `FromReflect`, ...). Leave a
+//! type out with `#[fsm(no_auto_register)]`; generic FSMs are never submitted and have to be
+//! added by hand. Types are registered in name order, so the observer hierarchy doesn't depend
+//! on link order.
+//!
+//! # Checking for missing plugins
+//!
+//! Projects that keep an explicit plugin list can use the registry to catch omissions instead.
+//! Without its `FSMPlugin`, an FSM's requests are silently ignored. In debug builds,
+//! [`FSMPluginCheck`] logs a warning the first time such a type is spawned or requested, and
+//! [`missing_fsm_plugins`] lists the types without a plugin, e.g. for a test asserting the
+//! list is empty:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{auto_register::missing_fsm_plugins, FSMPluginCheck};
+//! # fn game_plugins(_: &mut App) {}
+//! let mut app = App::new();
+//! app.add_plugins((game_plugins, FSMPluginCheck));
+//! assert_eq!(missing_fsm_plugins(app.world()), Vec::<&str>::new());
+//! ```
+
+use bevy::prelude::*;
+use bevy::reflect::{GetTypeRegistration, Typed};
+
+use crate::{fsm_plugin_added, FSMPlugin, FSMState, StateChangeRequest};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// An FSM type submitted by `#[derive(FSMState)]`.
+#[doc(hidden)]
+pub struct FSMRegistration {
+    name: &'static str,
+    register: fn(&mut App),
+    plugin_added: fn(&World) -> bool,
+    check: fn(&mut App),
+}
+
+impl FSMRegistration {
+    /// Registration adding `FSMPlugin::<S>::default()`.
+    pub const fn new<S>(name: &'static str) -> Self
+    where
+        S: FSMState
+            + core::hash::Hash
+            + Component
+            + Reflect
+            + FromReflect
+            + Typed
+            + GetTypeRegistration,
+    {
+        Self {
+            name,
+            register: register::<S>,
+            plugin_added: fsm_plugin_added::<S>,
+            check: check::<S>,
+        }
+    }
+}
+
+inventory::collect!(FSMRegistration);
+
+fn register<S>(app: &mut App)
+where
+    S: FSMState
+        + core::hash::Hash
+        + Component
+        + Reflect
+        + FromReflect
+        + Typed
+        + GetTypeRegistration,
+{
+    if !fsm_plugin_added::<S>(app.world()) {
+        app.add_plugins(FSMPlugin::<S>::default());
+    }
+}
+
+/// Warns the first time `S` is spawned or requested without an `FSMPlugin<S>`.
+fn check<S: FSMState>(app: &mut App) {
+    fn warn_once<S: FSMState>(world: &World, warned: &mut bool, what: core::fmt::Arguments) {
+        if !*warned && !fsm_plugin_added::<S>(world) {
+            *warned = true;
+            log::warn!(
+                "{what}, but FSMPlugin::<{}> was never added: its requests are ignored and no FSM events fire",
+                ShortName::of::<S>()
+            );
+        }
+    }
+
+    app.world_mut()
+        .add_observer(|add: On<Add, S>, world: &World, mut warned: Local<bool>| {
+            warn_once::<S>(
+                world,
+                &mut warned,
+                format_args!("{} was spawned with {}", add.entity, ShortName::of::<S>()),
+            );
+        })
+        .insert(Name::new("fsm_plugin_check"));
+    app.world_mut()
+        .add_observer(
+            |request: On<StateChangeRequest<S>>, world: &World, mut warned: Local<bool>| {
+                warn_once::<S>(
+                    world,
+                    &mut warned,
+                    format_args!(
+                        "{} was requested to change its {}",
+                        request.entity,
+                        ShortName::of::<S>()
+                    ),
+                );
+            },
+        )
+        .insert(Name::new("fsm_plugin_check"));
+}
+
+/// Names of all FSM types submitted by `#[derive(FSMState)]`, sorted.
+pub fn registered_fsm_types() -> Vec<&'static str> {
+    sorted_registrations()
+        .into_iter()
+        .map(|registration| registration.name)
+        .collect()
+}
+
+/// Names of the FSM types submitted by `#[derive(FSMState)]` that have no `FSMPlugin`, sorted.
+pub fn missing_fsm_plugins(world: &World) -> Vec<&'static str> {
+    sorted_registrations()
+        .into_iter()
+        .filter(|registration| !(registration.plugin_added)(world))
+        .map(|registration| registration.name)
+        .collect()
+}
+
+/// Plugin adding `FSMPlugin::default()` for every FSM type submitted by `#[derive(FSMState)]`
+/// that doesn't have one yet.
+#[derive(Default)]
+pub struct FSMAutoPlugin;
+
+impl Plugin for FSMAutoPlugin {
+    fn build(&self, app: &mut App) {
+        for registration in sorted_registrations() {
+            (registration.register)(app);
+        }
+    }
+}
+
+/// Debug-build plugin warning when an FSM type submitted by `#[derive(FSMState)]` is spawned
+/// or requested without its `FSMPlugin`. Does nothing in release builds.
+#[derive(Default)]
+pub struct FSMPluginCheck;
+
+impl Plugin for FSMPluginCheck {
+    fn build(&self, app: &mut App) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        for registration in sorted_registrations() {
+            (registration.check)(app);
+        }
+    }
+}
+
+fn sorted_registrations() -> Vec<&'static FSMRegistration> {
+    let mut registrations: Vec<_> = inventory::iter::<FSMRegistration>().collect();
+    registrations.sort_unstable_by_key(|registration| registration.name);
+    registrations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fsm_groups, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum BeaconFSM {
+        Dark,
+    }
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum AlarmFSM {
+        Silent,
+    }
+
+    impl FSMState for BeaconFSM {}
+    impl FSMState for AlarmFSM {}
+
+    impl FSMTransition for BeaconFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    impl FSMTransition for AlarmFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    // What `#[derive(FSMState)]` emits outside of this crate
+    inventory::submit! { FSMRegistration::new::<BeaconFSM>("BeaconFSM") }
+    inventory::submit! { FSMRegistration::new::<AlarmFSM>("AlarmFSM") }
+
+    #[test]
+    fn auto_plugin_adds_missing_plugins_in_name_order() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<BeaconFSM>::buffered());
+        app.add_plugins(FSMAutoPlugin);
+        app.world_mut().spawn((BeaconFSM::Dark, AlarmFSM::Silent));
+        app.update();
+
+        assert_eq!(registered_fsm_types(), vec!["AlarmFSM", "BeaconFSM"]);
+        assert!(fsm_plugin_added::<AlarmFSM>(app.world()));
+        // The configured plugin was kept
+        assert!(app
+            .world()
+            .contains_resource::<Messages<crate::StateChangeRequest<BeaconFSM>>>());
+        let groups: Vec<String> = fsm_groups(app.world())
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(groups, vec!["AlarmFSM", "BeaconFSM"]);
+    }
+    #[test]
+    fn missing_plugins_are_listed_and_checked() {
+        let mut app = App::new();
+        app.add_plugins((FSMPlugin::<AlarmFSM>::default(), FSMPluginCheck));
+        app.world_mut().spawn((BeaconFSM::Dark, AlarmFSM::Silent));
+        app.update();
+
+        assert_eq!(missing_fsm_plugins(app.world()), vec!["BeaconFSM"]);
+        let mut checks = app.world_mut().query::<&Name>();
+        let checks = checks
+            .iter(app.world())
+            .filter(|name| name.as_str() == "fsm_plugin_check")
+            .count();
+        // Spawn and request observers for both submitted types, in debug builds
+        assert_eq!(checks, if cfg!(debug_assertions) { 4 } else { 0 });
+    }
+}
@@ -0,0 +1,331 @@
+//! High-throughput processing of state change requests.
+//!
+//! Every applied transition normally triggers `Exit`, `Transition` and `Enter` (plus their
+//! variant-specific versions) and flushes commands in between, so observers see a consistent
+//! world at each step. With tens of thousands of entities changing state per frame that
+//! dispatch dominates. [`FSMPlugin::batched`](crate::FSMPlugin::batched) buffers requests like
+//! [`buffered`](crate::buffered) mode, then validates and applies the whole batch in one
+//! system without triggering any transition events. The applied transitions are written as
+//! [`AnyTransition`] messages instead, to be read by regular systems:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{AnyTransition, FSMPlugin, FSMState, FSMSystems, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum AgentFSM { Wander, Flee }
+//! fn on_agent_transitions(mut transitions: MessageReader<AnyTransition<AgentFSM>>) {
+//!     for transition in transitions.read() {
+//!         // react to transition.entity entering transition.to
+//!     }
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins(FSMPlugin::<AgentFSM>::batched())
+//!     .add_systems(Update, on_agent_transitions.after(FSMSystems));
+//! ```
+//!
+//! Requests are validated exactly like in the other modes and denials still trigger
+//! `TransitionDenied`. What batched mode gives up:
+//!
+//! - `Exit`, `Transition` and `Enter` observers don't run for batched transitions (initial
+//!   `Enter` events on spawn and `Exit` events on removal still fire).
+//! - Child propagation and `TransitioningTo<S>` are not used, since no observer can run in
+//!   the middle of a transition.
+//!
+//! The new state is still inserted, so `StateChanged<S>`, [`FSMPopulation`] and state caps
+//! keep working. Run `cargo bench` to compare the modes.
+//!
+//! # Parallel validation
+//!
+//! With [`FSMPlugin::with_parallel_validation`](crate::FSMPlugin::with_parallel_validation),
+//! the first request of each entity is validated on the `ComputeTaskPool` before anything is
+//! applied; only applying is sequential. Validation then sees the world as it was before the
+//! batch, so `can_transition_ctx` must not depend on transitions of other entities in the same
+//! batch. Further requests for an entity that already has one in the batch, and all requests
+//! of FSM types with state caps, are validated sequentially as usual.
+//!
+//! [`FSMPopulation`]: crate::population::FSMPopulation
+
+use bevy::ecs::entity::EntityHashSet;
+use bevy::prelude::*;
+use bevy::tasks::{ComputeTaskPool, ParallelSlice, TaskPool};
+
+use crate::buffered::{drain_requests, resolve_conflicts, ConflictPolicy};
+use crate::debounce;
+use crate::hysteresis;
+use crate::population::FSMStateCaps;
+use crate::redirect::redirect;
+use crate::retry;
+use crate::ticket::{self, TicketStatus};
+use crate::{
+    assert_targeted, decide_transition, insert_state, rules_details, AnyTransition, FSMPaused,
+    FSMState, StateChangeRequest, TransitionDenied, TransitionVerdict,
+};
+
+/// Validates and applies all buffered requests in send order, writing [`AnyTransition`]
+/// messages instead of triggering transition events.
+pub fn process_batched_requests<S: FSMState + core::hash::Hash>(world: &mut World) {
+    process_batch::<S>(world, ConflictPolicy::ApplyAll, false);
+}
+
+pub(crate) fn process_batch<S: FSMState + core::hash::Hash>(
+    world: &mut World,
+    policy: ConflictPolicy,
+    parallel: bool,
+) {
+    let mut superseded = Vec::new();
+    let requests = resolve_conflicts(drain_requests::<S>(world), policy, &mut superseded);
+    ticket::supersede(world, superseded);
+    // Caps depend on the transitions applied before, so they need sequential validation
+    let validated = if parallel && !world.contains_resource::<FSMStateCaps<S>>() {
+        validate_in_parallel(world, &requests)
+    } else {
+        Vec::new()
+    };
+    let mut applied = Vec::with_capacity(requests.len());
+    for (i, request) in requests.into_iter().enumerate() {
+        let StateChangeRequest {
+            entity,
+            next,
+            priority,
+            source,
+            ..
+        } = request;
+        let Some(&cur) = world.get::<S>(entity) else {
+            assert_targeted(&request);
+            ticket::resolve(
+                world,
+                &request,
+                TicketStatus::Denied(TransitionVerdict::NoFSM),
+            );
+            continue;
+        };
+        let next = redirect(world, entity, next);
+        if cur == next {
+            ticket::resolve(
+                world,
+                &request,
+                TicketStatus::Denied(TransitionVerdict::Unchanged),
+            );
+            continue;
+        }
+        let verdict = match validated.get(i) {
+            Some(&Some((from, verdict))) if from == cur => verdict,
+            _ => decide_transition(world, entity, cur, next, priority),
+        };
+        match verdict {
+            TransitionVerdict::Allowed if !hysteresis::settle(world, entity, cur, next) => {
+                ticket::resolve(world, &request, TicketStatus::Superseded);
+            }
+            TransitionVerdict::Allowed => {
+                insert_state(world, entity, cur, next);
+                debounce::record::<S>(world, entity);
+                applied.push(AnyTransition {
+                    entity,
+                    from: Some(cur),
+                    to: next,
+                    source,
+                });
+                ticket::resolve(world, &request, TicketStatus::Applied);
+            }
+            TransitionVerdict::Paused => {
+                let queued = world
+                    .get_mut::<FSMPaused<S>>(entity)
+                    .is_some_and(|mut paused| paused.queue(request));
+                if !queued {
+                    ticket::resolve(world, &request, TicketStatus::Denied(verdict));
+                }
+            }
+            TransitionVerdict::Debounced if debounce::hold(world, request) => {}
+            reason if retry::park(world, request, reason) => {}
+            reason => {
+                let details = rules_details(world, entity, cur, next, reason);
+                world.trigger(TransitionDenied {
+                    entity,
+                    from: cur,
+                    to: next,
+                    reason,
+                    source,
+                    details,
+                });
+                ticket::resolve(world, &request, TicketStatus::Denied(reason));
+            }
+        }
+    }
+    world.write_message_batch(applied);
+    world.flush();
+}
+
+/// Validates the first request of each entity against the current world on the
+/// `ComputeTaskPool`, returning the state it was validated from and the verdict.
+fn validate_in_parallel<S: FSMState + core::hash::Hash>(
+    world: &World,
+    requests: &[StateChangeRequest<S>],
+) -> Vec<Option<(S, TransitionVerdict)>> {
+    let mut seen = EntityHashSet::default();
+    let first: Vec<bool> = requests
+        .iter()
+        .map(|request| seen.insert(request.entity))
+        .collect();
+    let indexed: Vec<(StateChangeRequest<S>, bool)> = requests.iter().copied().zip(first).collect();
+
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let chunk_size = indexed.len().div_ceil(pool.thread_num()).max(256);
+    indexed
+        .par_chunk_map(pool, chunk_size, |_, chunk| {
+            chunk
+                .iter()
+                .map(|&(request, first)| {
+                    let cur = *world.get::<S>(request.entity)?;
+                    let next = redirect(world, request.entity, request.next);
+                    (first && cur != next).then(|| {
+                        let verdict =
+                            decide_transition(world, request.entity, cur, next, request.priority);
+                        (cur, verdict)
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, FSMTransition, StateChanged};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum AgentFSM {
+        Wander,
+        Flee,
+        Dead,
+    }
+
+    impl FSMState for AgentFSM {}
+
+    impl FSMTransition for AgentFSM {
+        fn can_transition(from: Self, _: Self) -> bool {
+            from != AgentFSM::Dead
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Log {
+        entered: usize,
+        denied: usize,
+        transitions: Vec<(Entity, Option<AgentFSM>, AgentFSM)>,
+    }
+
+    #[test]
+    fn batch_applies_requests_as_messages_without_transition_events() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<AgentFSM>::batched());
+        app.init_resource::<Log>();
+        app.add_observer(|_: On<Enter<AgentFSM>>, mut log: ResMut<Log>| log.entered += 1);
+        app.add_observer(|_: On<TransitionDenied<AgentFSM>>, mut log: ResMut<Log>| {
+            log.denied += 1;
+        });
+        app.add_systems(
+            Update,
+            (|mut transitions: MessageReader<AnyTransition<AgentFSM>>, mut log: ResMut<Log>| {
+                for t in transitions.read() {
+                    log.transitions.push((t.entity, t.from, t.to));
+                }
+            })
+            .after(crate::FSMSystems),
+        );
+
+        let a = app.world_mut().spawn(AgentFSM::Wander).id();
+        let b = app.world_mut().spawn(AgentFSM::Dead).id();
+        app.world_mut().flush();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(a, AgentFSM::Flee));
+        app.world_mut()
+            .trigger(StateChangeRequest::new(b, AgentFSM::Flee));
+        app.update();
+
+        let log = app.world().resource::<Log>();
+        // Only the initial Enter events of both spawns
+        assert_eq!(log.entered, 2);
+        assert_eq!(log.denied, 1);
+        assert_eq!(
+            log.transitions,
+            vec![(a, Some(AgentFSM::Wander), AgentFSM::Flee)]
+        );
+        assert_eq!(*app.world().get::<AgentFSM>(a).unwrap(), AgentFSM::Flee);
+        assert!(app.world().get::<StateChanged<AgentFSM>>(a).is_some());
+    }
+
+    #[test]
+    fn batch_restarts_timers_and_records_history() {
+        use crate::{FSMHistory, FSMTimer};
+        use core::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<AgentFSM>::batched());
+        let agent = app
+            .world_mut()
+            .spawn((
+                AgentFSM::Wander,
+                FSMTimer::<AgentFSM>::with_elapsed(Duration::from_secs(3)),
+                FSMHistory::<AgentFSM>::new(4),
+            ))
+            .id();
+        app.world_mut().flush();
+        app.world_mut()
+            .write_message(StateChangeRequest::new(agent, AgentFSM::Flee));
+        app.update();
+
+        let world = app.world();
+        assert_eq!(world.get::<AgentFSM>(agent), Some(&AgentFSM::Flee));
+        assert!(world.get::<FSMTimer<AgentFSM>>(agent).unwrap().elapsed() < Duration::from_secs(3));
+        assert_eq!(
+            world.get::<FSMHistory<AgentFSM>>(agent).unwrap().previous(),
+            Some(AgentFSM::Wander)
+        );
+    }
+
+    #[test]
+    fn parallel_validation_matches_sequential_results() {
+        let run = |parallel: bool| {
+            let mut app = App::new();
+            let plugin = FSMPlugin::<AgentFSM>::batched();
+            app.add_plugins(if parallel {
+                plugin.with_parallel_validation()
+            } else {
+                plugin
+            });
+            let agents: Vec<_> = (0..1000)
+                .map(|i| {
+                    let state = if i % 3 == 0 {
+                        AgentFSM::Dead
+                    } else {
+                        AgentFSM::Wander
+                    };
+                    app.world_mut().spawn(state).id()
+                })
+                .collect();
+            for &agent in &agents {
+                app.world_mut()
+                    .write_message(StateChangeRequest::new(agent, AgentFSM::Flee));
+            }
+            // A second request for the same entity is validated against the first one's result
+            app.world_mut()
+                .write_message(StateChangeRequest::new(agents[1], AgentFSM::Dead));
+            app.update();
+            agents
+                .iter()
+                .map(|&agent| *app.world().get::<AgentFSM>(agent).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let parallel = run(true);
+        assert_eq!(parallel, run(false));
+        assert_eq!(parallel[0], AgentFSM::Dead);
+        assert_eq!(parallel[1], AgentFSM::Dead);
+        assert_eq!(parallel[2], AgentFSM::Flee);
+    }
+}
@@ -0,0 +1,265 @@
+//! Building blocks for AI stacks (behavior trees, utility AI) on top of FSMs.
+//!
+//! Enabled by the `behavior` feature. Nothing here depends on a particular AI crate; the
+//! pieces map directly onto their leaf nodes:
+//!
+//! - **Conditions / scorers**: [`FSMCondition`] checks whether an entity is in one of a set
+//!   of states, [`state_score`] maps the current state to a utility score.
+//! - **Actions**: insert an [`FSMAction`] on the actor. [`FSMBehaviorPlugin`] issues the
+//!   request and tracks its [`ActionStatus`]: `Running` until the target state's Enter event
+//!   fires (`Success`), or `Failure` if the transition is denied.
+//!
+//! Wiring it into e.g. `big-brain` or `bevy_behave` is a matter of mapping statuses:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::behavior::{ActionStatus, FSMAction};
+//! # use bevy_fsm::{FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum GuardFSM { Patrol, Chase }
+//! fn start_chase(mut commands: Commands, guard: Entity) {
+//!     commands.entity(guard).insert(FSMAction::new(GuardFSM::Chase));
+//! }
+//!
+//! fn poll_chase(q: Query<&FSMAction<GuardFSM>>, guard: Entity) -> Option<bool> {
+//!     match q.get(guard).ok()?.status() {
+//!         ActionStatus::Requested | ActionStatus::Running => None,
+//!         ActionStatus::Success => Some(true),
+//!         ActionStatus::Failure => Some(false),
+//!     }
+//! }
+//! ```
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::propagation;
+use crate::{validate_with_priority, Enter, FSMState, StateChangeRequest};
+
+/// Leaf condition: true if the entity's FSM is in one of the given states.
+#[derive(Debug, Clone)]
+pub struct FSMCondition<S: FSMState + core::hash::Hash> {
+    states: HashSet<S>,
+}
+
+impl<S: FSMState + core::hash::Hash> FSMCondition<S> {
+    /// Condition matching any of `states`.
+    pub fn in_states(states: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            states: states.into_iter().collect(),
+        }
+    }
+
+    /// Evaluates the condition. Entities without an `S` component never match.
+    pub fn evaluate(&self, world: &World, entity: Entity) -> bool {
+        world
+            .get::<S>(entity)
+            .is_some_and(|state| self.states.contains(state))
+    }
+}
+
+/// Scorer: looks up the entity's current state in `scores`, defaulting to `0.0`.
+pub fn state_score<S: FSMState>(world: &World, entity: Entity, scores: &[(S, f32)]) -> f32 {
+    let Some(&state) = world.get::<S>(entity) else {
+        return 0.0;
+    };
+    scores
+        .iter()
+        .find(|(candidate, _)| *candidate == state)
+        .map_or(0.0, |(_, score)| *score)
+}
+
+/// Progress of an [`FSMAction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionStatus {
+    /// Inserted, request not sent yet.
+    Requested,
+    /// Request sent, waiting for the target state to be entered.
+    Running,
+    /// The entity entered the target state.
+    Success,
+    /// The transition was denied or the entity has no `S` component.
+    Failure,
+}
+
+/// Action node: drives the actor's FSM into `target` and reports the outcome.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FSMAction<S: FSMState> {
+    target: S,
+    priority: u32,
+    status: ActionStatus,
+}
+
+impl<S: FSMState> FSMAction<S> {
+    /// Action transitioning into `target` with the default priority.
+    pub fn new(target: S) -> Self {
+        Self {
+            target,
+            priority: 0,
+            status: ActionStatus::Requested,
+        }
+    }
+
+    /// Send the request with an interrupt priority.
+    #[must_use]
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The state this action drives towards.
+    pub fn target(&self) -> S {
+        self.target
+    }
+
+    /// Current progress.
+    pub fn status(&self) -> ActionStatus {
+        self.status
+    }
+}
+
+/// Plugin running [`FSMAction`]s for the FSM type `S`.
+pub struct FSMBehaviorPlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMBehaviorPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: FSMState + core::hash::Hash> Plugin for FSMBehaviorPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, start_fsm_actions::<S>)
+            .add_observer(complete_fsm_actions::<S>);
+    }
+}
+
+/// Sends requests for newly inserted actions, failing those that would be denied.
+pub fn start_fsm_actions<S: FSMState + core::hash::Hash>(world: &mut World) {
+    let mut query = world.query::<(Entity, &FSMAction<S>)>();
+    let pending: Vec<(Entity, FSMAction<S>)> = query
+        .iter(world)
+        .filter(|(_, action)| action.status == ActionStatus::Requested)
+        .map(|(entity, action)| (entity, *action))
+        .collect();
+
+    for (entity, action) in pending {
+        let status = match world.get::<S>(entity) {
+            Some(&state) if state == action.target => ActionStatus::Success,
+            Some(_)
+                if validate_with_priority(world, entity, action.target, action.priority)
+                    .is_allowed() =>
+            {
+                ActionStatus::Running
+            }
+            _ => ActionStatus::Failure,
+        };
+        if let Some(mut stored) = world.get_mut::<FSMAction<S>>(entity) {
+            stored.status = status;
+        }
+        if status == ActionStatus::Running {
+            world.trigger(
+                StateChangeRequest::new(entity, action.target).with_priority(action.priority),
+            );
+            world.flush();
+        }
+    }
+}
+
+/// Marks running actions as successful once their target state is entered.
+#[allow(clippy::needless_pass_by_value)]
+pub fn complete_fsm_actions<S: FSMState>(
+    trigger: On<Enter<S>>,
+    mut actions: Query<&mut FSMAction<S>>,
+) {
+    if propagation::bubbled(&trigger) {
+        return;
+    }
+    let event = trigger.event();
+    if let Ok(mut action) = actions.get_mut(event.entity) {
+        if action.status == ActionStatus::Running && action.target == event.state {
+            action.status = ActionStatus::Success;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMOverride, FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum GuardFSM {
+        Patrol,
+        Chase,
+        Stunned,
+    }
+
+    impl FSMState for GuardFSM {}
+
+    impl FSMTransition for GuardFSM {
+        fn can_transition(from: Self, _to: Self) -> bool {
+            from != GuardFSM::Stunned
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins((
+            FSMPlugin::<GuardFSM>::default(),
+            FSMBehaviorPlugin::<GuardFSM>::default(),
+        ));
+        app
+    }
+
+    #[test]
+    fn conditions_and_scores_read_state() {
+        let mut world = World::new();
+        let guard = world.spawn(GuardFSM::Chase).id();
+
+        assert!(
+            FSMCondition::in_states([GuardFSM::Chase, GuardFSM::Stunned]).evaluate(&world, guard)
+        );
+        assert!(!FSMCondition::in_states([GuardFSM::Patrol]).evaluate(&world, guard));
+        let scores = [(GuardFSM::Patrol, 0.2), (GuardFSM::Chase, 0.9)];
+        assert_eq!(state_score(&world, guard, &scores), 0.9);
+    }
+
+    #[test]
+    fn actions_succeed_or_fail() {
+        let mut app = app();
+        let guard = app
+            .world_mut()
+            .spawn((GuardFSM::Patrol, FSMAction::new(GuardFSM::Chase)))
+            .id();
+        let stunned = app
+            .world_mut()
+            .spawn((GuardFSM::Stunned, FSMAction::new(GuardFSM::Chase)))
+            .id();
+        let locked = app
+            .world_mut()
+            .spawn((
+                GuardFSM::Patrol,
+                FSMOverride::<GuardFSM>::deny_all(),
+                FSMAction::new(GuardFSM::Chase),
+            ))
+            .id();
+
+        app.update();
+
+        let status = |app: &App, e| app.world().get::<FSMAction<GuardFSM>>(e).unwrap().status();
+        assert_eq!(status(&app, guard), ActionStatus::Success);
+        assert_eq!(
+            *app.world().get::<GuardFSM>(guard).unwrap(),
+            GuardFSM::Chase
+        );
+        assert_eq!(status(&app, stunned), ActionStatus::Failure);
+        assert_eq!(status(&app, locked), ActionStatus::Failure);
+    }
+}
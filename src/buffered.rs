@@ -0,0 +1,294 @@
+//! Buffered processing of state change requests.
+//!
+//! By default [`FSMPlugin`](crate::FSMPlugin) applies each [`StateChangeRequest`] the moment
+//! it is triggered. In buffered mode ([`FSMPlugin::buffered`](crate::FSMPlugin::buffered))
+//! requests are collected as messages instead and applied by a system, one after another,
+//! with pending commands flushed in between. The deterministic variant
+//! ([`FSMPlugin::deterministic`](crate::FSMPlugin::deterministic)) sorts the buffer by entity
+//! index first, so lockstep and rollback simulations apply the same requests in the same
+//! order on every peer.
+//!
+//! Requests can be sent either way:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::StateChangeRequest;
+//! # #[derive(Component, Clone, Copy)]
+//! # enum LifeFSM { Dying }
+//! fn kill(mut commands: Commands, mut requests: MessageWriter<StateChangeRequest<LifeFSM>>) {
+//!     # let entity = Entity::PLACEHOLDER;
+//!     // Triggered requests are forwarded into the buffer...
+//!     commands.trigger(StateChangeRequest::new(entity, LifeFSM::Dying));
+//!     // ...and so are messages written directly.
+//!     requests.write(StateChangeRequest::new(entity, LifeFSM::Dying));
+//! }
+//! ```
+//!
+//! Requests issued while the buffer is being processed (e.g. from an `Enter` observer) are
+//! applied the next time the system runs.
+
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+
+use crate::ticket;
+use crate::{apply_request, FSMState, StateChangeRequest};
+
+/// Observer forwarding triggered requests into the `StateChangeRequest<S>` message buffer.
+#[allow(clippy::needless_pass_by_value)]
+pub fn buffer_state_request<S: FSMState>(
+    trigger: On<StateChangeRequest<S>>,
+    mut requests: MessageWriter<StateChangeRequest<S>>,
+) {
+    requests.write(*trigger.event());
+}
+
+/// How to handle several buffered requests for the same entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Apply every request in order; the last one that passes validation wins.
+    #[default]
+    ApplyAll,
+    /// Keep only the first request sent for each entity.
+    FirstWins,
+    /// Keep only the last request sent for each entity.
+    LastWins,
+    /// Keep only the request with the highest priority; ties go to the first one sent.
+    HighestPriority,
+}
+
+/// Applies all buffered requests in the order they were sent.
+pub fn process_buffered_requests<S: FSMState + core::hash::Hash>(world: &mut World) {
+    process_requests::<S>(world, false, ConflictPolicy::ApplyAll);
+}
+
+/// Applies all buffered requests, sorted by entity index.
+///
+/// Requests for the same entity keep the order they were sent in.
+pub fn process_sorted_requests<S: FSMState + core::hash::Hash>(world: &mut World) {
+    process_requests::<S>(world, true, ConflictPolicy::ApplyAll);
+}
+
+/// Drains the buffer, resolves conflicts per entity and applies what is left.
+pub(crate) fn process_requests<S: FSMState + core::hash::Hash>(
+    world: &mut World,
+    sorted: bool,
+    policy: ConflictPolicy,
+) {
+    let mut superseded = Vec::new();
+    let mut requests = resolve_conflicts(drain_requests::<S>(world), policy, &mut superseded);
+    ticket::supersede(world, superseded);
+    if sorted {
+        requests.sort_by_key(|request| request.entity.index_u32());
+    }
+
+    for request in requests {
+        apply_request(world, request);
+    }
+}
+
+/// Reduces `requests` to one per entity according to `policy`, keeping send order and
+/// collecting the dropped requests in `superseded`.
+pub(crate) fn resolve_conflicts<S: FSMState>(
+    requests: Vec<StateChangeRequest<S>>,
+    policy: ConflictPolicy,
+    superseded: &mut Vec<StateChangeRequest<S>>,
+) -> Vec<StateChangeRequest<S>> {
+    if policy == ConflictPolicy::ApplyAll {
+        return requests;
+    }
+
+    let mut resolved: Vec<StateChangeRequest<S>> = Vec::with_capacity(requests.len());
+    let mut index: EntityHashMap<usize> = EntityHashMap::default();
+    for request in requests {
+        let Some(&i) = index.get(&request.entity) else {
+            index.insert(request.entity, resolved.len());
+            resolved.push(request);
+            continue;
+        };
+        let kept = &mut resolved[i];
+        let replace = match policy {
+            ConflictPolicy::ApplyAll | ConflictPolicy::FirstWins => false,
+            ConflictPolicy::LastWins => true,
+            ConflictPolicy::HighestPriority => request.priority > kept.priority,
+        };
+        if replace {
+            superseded.push(core::mem::replace(kept, request));
+        } else {
+            superseded.push(request);
+        }
+    }
+    resolved
+}
+
+pub(crate) fn drain_requests<S: FSMState>(world: &mut World) -> Vec<StateChangeRequest<S>> {
+    world
+        .resource_mut::<Messages<StateChangeRequest<S>>>()
+        .drain()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, FSMSystems, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[reflect(Component)]
+    enum TurnFSM {
+        Waiting,
+        Acting,
+        Done,
+    }
+
+    impl FSMState for TurnFSM {}
+
+    impl FSMTransition for TurnFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (TurnFSM::Waiting, TurnFSM::Acting) | (TurnFSM::Acting, TurnFSM::Done)
+            )
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct EnterOrder(Vec<(Entity, TurnFSM)>);
+
+    fn record(trigger: On<Enter<TurnFSM>>, mut order: ResMut<EnterOrder>) {
+        order
+            .0
+            .push((trigger.event().entity, trigger.event().state));
+    }
+
+    #[test]
+    fn buffered_mode_applies_in_send_order_during_update() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(FSMPlugin::<TurnFSM>::buffered());
+        app.init_resource::<EnterOrder>();
+        app.add_observer(record);
+
+        let a = app.world_mut().spawn(TurnFSM::Waiting).id();
+        let b = app.world_mut().spawn(TurnFSM::Waiting).id();
+        app.world_mut().resource_mut::<EnterOrder>().0.clear();
+
+        app.world_mut()
+            .write_message(StateChangeRequest::new(b, TurnFSM::Acting));
+        app.world_mut()
+            .trigger(StateChangeRequest::new(a, TurnFSM::Acting));
+        app.world_mut().flush();
+        assert_eq!(*app.world().get::<TurnFSM>(a).unwrap(), TurnFSM::Waiting);
+
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<EnterOrder>().0,
+            vec![(b, TurnFSM::Acting), (a, TurnFSM::Acting)]
+        );
+    }
+
+    #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+    struct GameplaySet;
+
+    #[derive(Resource, Default)]
+    struct Seen(Vec<TurnFSM>);
+
+    #[test]
+    fn configured_schedule_and_sets_order_processing() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            FSMPlugin::<TurnFSM>::default()
+                .in_schedule(PostUpdate)
+                .in_set(GameplaySet),
+        );
+        app.init_resource::<Seen>();
+        let e = app.world_mut().spawn(TurnFSM::Waiting).id();
+        let before = move |q: Query<&TurnFSM>, mut seen: ResMut<Seen>| {
+            seen.0.push(*q.get(e).unwrap());
+        };
+        let after = move |q: Query<&TurnFSM>, mut seen: ResMut<Seen>| {
+            seen.0.push(*q.get(e).unwrap());
+        };
+        app.add_systems(
+            PostUpdate,
+            (before.before(GameplaySet), after.after(FSMSystems)),
+        );
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, TurnFSM::Acting));
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<Seen>().0,
+            vec![TurnFSM::Waiting, TurnFSM::Acting]
+        );
+    }
+
+    #[test]
+    fn conflict_policies_pick_one_request_per_entity() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let requests = vec![
+            StateChangeRequest::new(a, TurnFSM::Acting).with_priority(1),
+            StateChangeRequest::new(b, TurnFSM::Acting),
+            StateChangeRequest::new(a, TurnFSM::Done).with_priority(3),
+            StateChangeRequest::new(a, TurnFSM::Waiting).with_priority(3),
+        ];
+        let targets = |policy| -> Vec<_> {
+            resolve_conflicts(requests.clone(), policy, &mut Vec::new())
+                .into_iter()
+                .map(|request| (request.entity, request.next))
+                .collect()
+        };
+
+        assert_eq!(targets(ConflictPolicy::ApplyAll).len(), 4);
+        assert_eq!(
+            targets(ConflictPolicy::FirstWins),
+            vec![(a, TurnFSM::Acting), (b, TurnFSM::Acting)]
+        );
+        assert_eq!(
+            targets(ConflictPolicy::LastWins),
+            vec![(a, TurnFSM::Waiting), (b, TurnFSM::Acting)]
+        );
+        assert_eq!(
+            targets(ConflictPolicy::HighestPriority),
+            vec![(a, TurnFSM::Done), (b, TurnFSM::Acting)]
+        );
+    }
+
+    #[test]
+    fn deterministic_mode_sorts_by_entity_and_waits_for_fixed_update() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(FSMPlugin::<TurnFSM>::default().deterministic());
+        app.init_resource::<EnterOrder>();
+        app.add_observer(record);
+
+        let a = app.world_mut().spawn(TurnFSM::Waiting).id();
+        let b = app.world_mut().spawn(TurnFSM::Waiting).id();
+        app.world_mut().resource_mut::<EnterOrder>().0.clear();
+
+        // Sent in reverse entity order, mixing triggers and messages
+        app.world_mut()
+            .trigger(StateChangeRequest::new(b, TurnFSM::Acting));
+        app.world_mut()
+            .write_message(StateChangeRequest::new(b, TurnFSM::Done));
+        app.world_mut()
+            .write_message(StateChangeRequest::new(a, TurnFSM::Acting));
+        app.world_mut().flush();
+        assert_eq!(*app.world().get::<TurnFSM>(b).unwrap(), TurnFSM::Waiting);
+
+        app.world_mut().run_schedule(FixedUpdate);
+
+        assert_eq!(
+            app.world().resource::<EnterOrder>().0,
+            vec![
+                (a, TurnFSM::Acting),
+                (b, TurnFSM::Acting),
+                (b, TurnFSM::Done)
+            ]
+        );
+    }
+}
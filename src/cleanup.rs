@@ -0,0 +1,405 @@
+//! Cancelling pending work when an FSM goes away.
+//!
+//! When an entity despawns or loses its `S` component, `FSMPlugin` cancels everything the
+//! crate still had scheduled for it in one place: requests parked for a retry ([`FSMRetry`]),
+//! stepped paths ([`FSMPath`]), requests held by [`FSMDebounce`] or a buffered [`FSMPaused`],
+//! a deferred initial `Enter`, the [`FSMTimer`], and the
+//! [`TransitionTicket`](crate::TransitionTicket)s still pending, which resolve as
+//! [`TicketStatus::Cancelled`](crate::TicketStatus::Cancelled).
+//! Configuration such as `FSMOverride` or `FSMHistory` stays on the entity.
+//!
+//! [`FSMCleanup`] is triggered afterwards, after the `Exit` events of the last state, for
+//! teardown of your own:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMCleanup, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum TurretFSM { Idle, Firing }
+//! # #[derive(Resource, Default)]
+//! # struct Targeting(Vec<Entity>);
+//! # let mut app = App::new();
+//! app.add_observer(|cleanup: On<FSMCleanup<TurretFSM>>, mut targeting: ResMut<Targeting>| {
+//!     targeting.0.retain(|&turret| turret != cleanup.entity);
+//! });
+//! ```
+//!
+//! On despawn the entity is gone by the time [`FSMCleanup`] fires, so only global observers
+//! see it and they must not expect to access the entity.
+//!
+//! Removing the component by hand keeps that configuration. [`remove_fsm`], or the
+//! [`remove_fsm`](crate::FSMEntityCommandsExt::remove_fsm) entity command, takes the whole
+//! FSM off the entity at once: the `Exit` events and [`FSMCleanup`] fire as above, then the
+//! crate components of `S` are removed ([`FSMOverride`], [`FSMOverrideStack`],
+//! [`FSMHistory`], [`FSMPaused`], [`FSMDebounce`], [`FSMHysteresis`], [`FSMRedirect`],
+//! [`StateChanged`], ..., and with their features `VisibleInState`, `FSMAction` and
+//! `FSMStateLabel`) and [`FSMRemoved`] is triggered last:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMEntityCommandsExt, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum TurretFSM { Idle, Firing }
+//! fn disarm(mut commands: Commands, turrets: Query<Entity, With<TurretFSM>>) {
+//!     for turret in &turrets {
+//!         commands.entity(turret).remove_fsm::<TurretFSM>();
+//!     }
+//! }
+//! ```
+//!
+//! [`swap_fsm`], or the [`swap_fsm`](crate::FSMEntityCommandsExt::swap_fsm) entity command,
+//! replaces one FSM with another: it removes `A` like [`remove_fsm`], then inserts `B` in its
+//! initial state, which fires the initial `Enter` events of `B` as for any new FSM component.
+//! E.g. `commands.entity(player).swap_fsm::<OnFootFSM, _>(VehicleFSM::Driving)`.
+
+use bevy::prelude::*;
+use core::hash::Hash;
+
+use crate::debounce::FSMDebounce;
+use crate::hysteresis::FSMHysteresis;
+use crate::path::FSMPath;
+use crate::query::{FSMHistory, FSMTimer};
+use crate::redirect::FSMRedirect;
+use crate::retry::FSMRetry;
+use crate::rules::FSMOverride;
+use crate::stack::FSMOverrideStack;
+use crate::ticket;
+use crate::{
+    FSMPaused, FSMState, InitialEnterPending, PendingTransition, SkipInitialEnter, StateChanged,
+    TransitioningTo,
+};
+
+/// Event fired once the crate cleaned up after an entity whose `S` component was removed.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct FSMCleanup<S: Copy + Send + Sync + 'static> {
+    pub entity: Entity,
+    /// The last state of the entity.
+    pub state: S,
+    /// Whether the entity was despawned, rather than only losing its `S` component.
+    pub despawned: bool,
+}
+
+/// Observer cancelling the pending work of an FSM whose component is removed, then
+/// triggering [`FSMCleanup`].
+///
+/// **Note**: This is automatically registered when using `FSMPlugin`.
+#[allow(clippy::needless_pass_by_value)]
+pub fn on_fsm_cleanup<S: FSMState>(trigger: On<Remove, S>, mut commands: Commands, q: Query<&S>) {
+    let entity = trigger.entity;
+    if let Ok(&state) = q.get(entity) {
+        commands.queue(move |world: &mut World| clean_up(world, entity, state));
+    }
+}
+
+fn clean_up<S: FSMState>(world: &mut World, entity: Entity, state: S) {
+    let despawned = match world.get_entity_mut(entity) {
+        // Inserted again in the meantime: nothing to cancel
+        Ok(entity) if entity.contains::<S>() => return,
+        Ok(mut entity) => {
+            entity.remove::<(FSMRetry<S>, FSMPath<S>, FSMTimer<S>, InitialEnterPending<S>)>();
+            if let Some(mut debounce) = entity.get_mut::<FSMDebounce<S>>() {
+                debounce.queued = None;
+            }
+            if let Some(mut paused) = entity.get_mut::<FSMPaused<S>>() {
+                paused.queued.clear();
+            }
+            false
+        }
+        Err(_) => true,
+    };
+    ticket::cancel::<S>(world, entity);
+    world.trigger(FSMCleanup {
+        entity,
+        state,
+        despawned,
+    });
+}
+
+/// Event fired last by [`remove_fsm`], once the `S` component and the crate components of
+/// `S` are gone from the entity.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct FSMRemoved<S: Copy + Send + Sync + 'static> {
+    pub entity: Entity,
+    /// The last state of the entity.
+    pub state: S,
+}
+
+/// Removes the FSM `S` from `entity` along with its crate components, triggering the `Exit`
+/// events of the last state, [`FSMCleanup`] and finally [`FSMRemoved`].
+///
+/// Returns `false`, leaving the entity untouched, if it does not exist or has no `S`. Also
+/// returns `false` if an observer inserted `S` again or despawned the entity in the meantime,
+/// in which case the remaining components are left alone and [`FSMRemoved`] does not fire.
+pub fn remove_fsm<S: FSMState + Hash>(world: &mut World, entity: Entity) -> bool {
+    let Some(&state) = world.get::<S>(entity) else {
+        return false;
+    };
+    world.entity_mut(entity).remove::<S>();
+    // Runs the Exit events and the cleanup queued by the removal observers
+    world.flush();
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return false;
+    };
+    if entity_mut.contains::<S>() {
+        return false;
+    }
+    entity_mut.remove::<(
+        (
+            FSMOverride<S>,
+            FSMOverrideStack<S>,
+            FSMHistory<S>,
+            FSMPaused<S>,
+        ),
+        (
+            FSMDebounce<S>,
+            FSMHysteresis<S>,
+            FSMRedirect<S>,
+            FSMTimer<S>,
+        ),
+        (FSMRetry<S>, FSMPath<S>, StateChanged<S>, TransitioningTo<S>),
+        (
+            PendingTransition<S>,
+            InitialEnterPending<S>,
+            SkipInitialEnter<S>,
+        ),
+    )>();
+    #[cfg(feature = "visibility")]
+    entity_mut.remove::<crate::visibility::VisibleInState<S>>();
+    #[cfg(feature = "behavior")]
+    entity_mut.remove::<crate::behavior::FSMAction<S>>();
+    #[cfg(feature = "debug-overlay")]
+    entity_mut.remove::<(
+        crate::debug_overlay::FSMStateLabel<S>,
+        crate::debug_overlay::Labelled<S>,
+    )>();
+    world.trigger(FSMRemoved { entity, state });
+    world.flush();
+    true
+}
+
+/// Replaces the FSM `A` of `entity` with the FSM `B` in the state `initial`.
+///
+/// `A` is taken off as by [`remove_fsm`], then `B` is inserted and its initial `Enter` events
+/// fire. Returns `false` without touching the entity if it has no `A` or already has a `B`,
+/// and without inserting `B` if [`remove_fsm`] did not complete.
+pub fn swap_fsm<A, B>(world: &mut World, entity: Entity, initial: B) -> bool
+where
+    A: FSMState + Hash,
+    B: FSMState,
+{
+    if world.get::<B>(entity).is_some() || !remove_fsm::<A>(world, entity) {
+        return false;
+    }
+    world.entity_mut(entity).insert(initial);
+    world.flush();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Enter, Exit, FSMEntityCommandsExt, FSMPlugin, FSMTransition, StateChangeRequest,
+        TicketStatus,
+    };
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum TurretFSM {
+        Idle,
+        Firing,
+    }
+
+    impl FSMState for TurretFSM {}
+
+    impl FSMTransition for TurretFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Cleaned(Vec<(Entity, TurretFSM, bool)>);
+
+    #[test]
+    fn removal_and_despawn_cancel_pending_work() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<TurretFSM>::default());
+        app.init_resource::<Cleaned>();
+        app.add_observer(
+            |cleanup: On<FSMCleanup<TurretFSM>>, mut cleaned: ResMut<Cleaned>| {
+                cleaned
+                    .0
+                    .push((cleanup.entity, cleanup.state, cleanup.despawned));
+            },
+        );
+        let paused = || FSMPaused::<TurretFSM>::buffered();
+        let removed = app
+            .world_mut()
+            .spawn((TurretFSM::Idle, FSMTimer::<TurretFSM>::default(), paused()))
+            .id();
+        let despawned = app.world_mut().spawn((TurretFSM::Idle, paused())).id();
+
+        let mut commands = app.world_mut().commands();
+        let tickets = [removed, despawned].map(|turret| {
+            commands
+                .entity(turret)
+                .request_fsm_tracked(StateChangeRequest::to(TurretFSM::Firing))
+        });
+        app.world_mut().flush();
+        assert_eq!(tickets[0].status(), TicketStatus::Pending);
+
+        app.world_mut().entity_mut(removed).remove::<TurretFSM>();
+        app.world_mut().despawn(despawned);
+        app.world_mut().flush();
+
+        assert!(tickets
+            .iter()
+            .all(|ticket| ticket.status() == TicketStatus::Cancelled));
+        let turret = app.world().entity(removed);
+        assert!(!turret.contains::<FSMTimer<TurretFSM>>());
+        assert!(turret
+            .get::<FSMPaused<TurretFSM>>()
+            .unwrap()
+            .queued()
+            .is_empty());
+        assert_eq!(
+            app.world().resource::<Cleaned>().0,
+            vec![
+                (removed, TurretFSM::Idle, false),
+                (despawned, TurretFSM::Idle, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn remove_fsm_takes_its_components_along() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<TurretFSM>::default());
+        app.init_resource::<Cleaned>();
+        app.add_observer(
+            |removed: On<FSMRemoved<TurretFSM>>, mut cleaned: ResMut<Cleaned>| {
+                cleaned.0.push((removed.entity, removed.state, false));
+            },
+        );
+        let turret = app
+            .world_mut()
+            .spawn((
+                TurretFSM::Idle,
+                FSMHistory::<TurretFSM>::new(4),
+                FSMOverride::<TurretFSM>::whitelist([(TurretFSM::Idle, TurretFSM::Firing)]),
+            ))
+            .id();
+        #[cfg(feature = "visibility")]
+        app.world_mut()
+            .entity_mut(turret)
+            .insert(crate::visibility::VisibleInState::new([TurretFSM::Firing]));
+        #[cfg(feature = "behavior")]
+        app.world_mut()
+            .entity_mut(turret)
+            .insert(crate::behavior::FSMAction::new(TurretFSM::Idle));
+        #[cfg(feature = "debug-overlay")]
+        app.world_mut()
+            .entity_mut(turret)
+            .insert(crate::debug_overlay::Labelled::<TurretFSM>(
+                std::marker::PhantomData,
+            ));
+        app.world_mut()
+            .trigger(StateChangeRequest::new(turret, TurretFSM::Firing));
+        app.world_mut().flush();
+
+        app.world_mut()
+            .commands()
+            .entity(turret)
+            .remove_fsm::<TurretFSM>();
+        app.world_mut().flush();
+
+        let entity = app.world().entity(turret);
+        assert!(!entity.contains::<TurretFSM>());
+        assert!(!entity.contains::<FSMHistory<TurretFSM>>());
+        assert!(!entity.contains::<FSMOverride<TurretFSM>>());
+        assert!(!entity.contains::<FSMTimer<TurretFSM>>());
+        assert!(!entity.contains::<StateChanged<TurretFSM>>());
+        #[cfg(feature = "visibility")]
+        assert!(!entity.contains::<crate::visibility::VisibleInState<TurretFSM>>());
+        #[cfg(feature = "behavior")]
+        assert!(!entity.contains::<crate::behavior::FSMAction<TurretFSM>>());
+        #[cfg(feature = "debug-overlay")]
+        assert!(!entity.contains::<crate::debug_overlay::Labelled<TurretFSM>>());
+        assert_eq!(
+            app.world().resource::<Cleaned>().0,
+            vec![(turret, TurretFSM::Firing, false)]
+        );
+        assert!(!remove_fsm::<TurretFSM>(app.world_mut(), turret));
+    }
+
+    #[test]
+    fn swap_fsm_exits_one_machine_and_enters_the_other() {
+        #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum OnFootFSM {
+            Walking,
+        }
+
+        impl FSMState for OnFootFSM {}
+
+        impl FSMTransition for OnFootFSM {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+        }
+
+        #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum VehicleFSM {
+            Driving,
+        }
+
+        impl FSMState for VehicleFSM {}
+
+        impl FSMTransition for VehicleFSM {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+        }
+
+        #[derive(Resource, Default)]
+        struct Log(Vec<&'static str>);
+
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<OnFootFSM>::default(),
+            FSMPlugin::<VehicleFSM>::default(),
+        ));
+        app.init_resource::<Log>();
+        app.add_observer(|_: On<Exit<OnFootFSM>>, mut log: ResMut<Log>| log.0.push("exit"));
+        app.add_observer(|_: On<FSMRemoved<OnFootFSM>>, mut log: ResMut<Log>| {
+            log.0.push("removed");
+        });
+        app.add_observer(|_: On<Enter<VehicleFSM>>, mut log: ResMut<Log>| log.0.push("enter"));
+        let player = app
+            .world_mut()
+            .spawn((OnFootFSM::Walking, FSMHistory::<OnFootFSM>::new(4)))
+            .id();
+        app.world_mut().flush();
+
+        app.world_mut()
+            .commands()
+            .entity(player)
+            .swap_fsm::<OnFootFSM, _>(VehicleFSM::Driving);
+        app.world_mut().flush();
+
+        let entity = app.world().entity(player);
+        assert!(!entity.contains::<OnFootFSM>());
+        assert!(!entity.contains::<FSMHistory<OnFootFSM>>());
+        assert_eq!(entity.get::<VehicleFSM>(), Some(&VehicleFSM::Driving));
+        assert_eq!(
+            app.world().resource::<Log>().0,
+            vec!["exit", "removed", "enter"]
+        );
+        assert!(!swap_fsm::<OnFootFSM, _>(
+            app.world_mut(),
+            player,
+            VehicleFSM::Driving
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+//! The clock measuring durations for the timing features.
+//!
+//! [`FSMDebounce`](crate::FSMDebounce), [`FSMHysteresis`](crate::FSMHysteresis) and
+//! [`FSMTimer`](crate::FSMTimer) read the time through the [`FSMClock`] resource. It defaults
+//! to Bevy's `Time` resource, which works the same in wasm builds and on headless servers
+//! stepping `FixedUpdate`. Insert another clock to measure in real time, or to advance time by
+//! hand in tests and lockstep simulations:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::FSMClock;
+//! # use core::time::Duration;
+//! # let mut app = App::new();
+//! app.insert_resource(FSMClock::manual());
+//!
+//! // Each step advances the FSM clock by exactly one tick
+//! app.world_mut()
+//!     .resource_mut::<FSMClock>()
+//!     .advance(Duration::from_millis(50));
+//! app.update();
+//! ```
+//!
+//! Without an `FSMClock` resource the `Time` clock is used. A single FSM type can use its own
+//! clock, e.g. real time for menus that keep animating while the game is paused, with
+//! [`FSMPlugin::with_clock`](crate::FSMPlugin::with_clock), which inserts an [`FSMTypeClock`].
+//! Systems can read the clock of an FSM type with the [`FSMTime`] system param.
+//!
+//! An [`FSMTimeScale`] on an entity speeds up or slows down all of these for that entity alone,
+//! e.g. for haste and slow effects.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+/// Resource selecting the clock of the timing features.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FSMClock {
+    /// The `Time` resource: virtual time in `Update`, fixed time in `FixedUpdate`.
+    #[default]
+    Time,
+    /// Virtual time, also in `FixedUpdate`.
+    Virtual,
+    /// Real time, ignoring pauses and the speed of virtual time.
+    Real,
+    /// Fixed time, also outside of `FixedUpdate`. Advances in steps of the fixed timestep.
+    Fixed,
+    /// Time only advanced by [`FSMClock::advance`].
+    Manual {
+        /// Total time advanced.
+        elapsed: Duration,
+        /// Length of the last advance.
+        delta: Duration,
+    },
+}
+
+impl FSMClock {
+    /// Manual clock starting at zero.
+    #[must_use]
+    pub fn manual() -> Self {
+        Self::Manual {
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+        }
+    }
+
+    /// Advances a manual clock by `by`, which becomes its delta until the next advance, like
+    /// `Time::advance_by`. Other clocks are left alone.
+    pub fn advance(&mut self, by: Duration) {
+        if let Self::Manual { elapsed, delta } = self {
+            *elapsed += by;
+            *delta = by;
+        }
+    }
+}
+
+/// Resource selecting the clock of the FSM type `S`, overriding the [`FSMClock`] resource.
+///
+/// Inserted by [`FSMPlugin::with_clock`](crate::FSMPlugin::with_clock). A manual clock of
+/// one type is advanced through this resource, which dereferences to its [`FSMClock`].
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct FSMTypeClock<S> {
+    /// The clock of `S`.
+    #[deref]
+    pub clock: FSMClock,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S> FSMTypeClock<S> {
+    /// Use `clock` for the FSM type `S`.
+    #[must_use]
+    pub fn new(clock: FSMClock) -> Self {
+        Self {
+            clock,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Component scaling the time of the timing features of all FSMs on the entity.
+///
+/// `FSMTimeScale(0.5)` makes every [`FSMTimer`](crate::FSMTimer) advance at half speed and
+/// doubles the time [`FSMDebounce`](crate::FSMDebounce) and
+/// [`FSMHysteresis`](crate::FSMHysteresis) windows take. Negative scales count as zero. A new
+/// scale applies to debounce and dwell windows that are already running as a whole.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct FSMTimeScale(pub f32);
+
+impl Default for FSMTimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl FSMTimeScale {
+    /// `duration` as experienced by the entity.
+    pub fn apply(self, duration: Duration) -> Duration {
+        duration.mul_f64(f64::from(self.0.max(0.0)))
+    }
+}
+
+/// System param reading the time of the clock of the FSM type `S`: its [`FSMTypeClock`], else
+/// the [`FSMClock`] resource, else `Time`.
+#[derive(SystemParam)]
+pub struct FSMTime<'w, S: Send + Sync + 'static> {
+    type_clock: Option<Res<'w, FSMTypeClock<S>>>,
+    clock: Option<Res<'w, FSMClock>>,
+    time: Option<Res<'w, Time>>,
+    virtual_time: Option<Res<'w, Time<Virtual>>>,
+    real_time: Option<Res<'w, Time<Real>>>,
+    fixed_time: Option<Res<'w, Time<Fixed>>>,
+}
+
+impl<S: Send + Sync + 'static> FSMTime<'_, S> {
+    /// Time elapsed since startup, or `None` if the clock's resource is missing.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.read().map(|(elapsed, _)| elapsed)
+    }
+
+    /// Length of the last tick, or `None` if the clock's resource is missing.
+    pub fn delta(&self) -> Option<Duration> {
+        self.read().map(|(_, delta)| delta)
+    }
+
+    fn read(&self) -> Option<(Duration, Duration)> {
+        let clock = self
+            .type_clock
+            .as_deref()
+            .map(|type_clock| type_clock.clock)
+            .or(self.clock.as_deref().copied())
+            .unwrap_or_default();
+        match clock {
+            FSMClock::Time => self.time.as_deref().map(read),
+            FSMClock::Virtual => self.virtual_time.as_deref().map(read),
+            FSMClock::Real => self.real_time.as_deref().map(read),
+            FSMClock::Fixed => self.fixed_time.as_deref().map(read),
+            FSMClock::Manual { elapsed, delta } => Some((elapsed, delta)),
+        }
+    }
+}
+
+/// Time elapsed on the clock of the FSM type `S`, for code with world access.
+pub(crate) fn elapsed<S: Send + Sync + 'static>(world: &World) -> Option<Duration> {
+    let clock = world
+        .get_resource::<FSMTypeClock<S>>()
+        .map(|type_clock| type_clock.clock)
+        .or(world.get_resource::<FSMClock>().copied())
+        .unwrap_or_default();
+    let (elapsed, _) = match clock {
+        FSMClock::Time => world.get_resource::<Time>().map(read),
+        FSMClock::Virtual => world.get_resource::<Time<Virtual>>().map(read),
+        FSMClock::Real => world.get_resource::<Time<Real>>().map(read),
+        FSMClock::Fixed => world.get_resource::<Time<Fixed>>().map(read),
+        FSMClock::Manual { elapsed, delta } => Some((elapsed, delta)),
+    }?;
+    Some(elapsed)
+}
+
+fn read<T: Default>(time: &Time<T>) -> (Duration, Duration) {
+    (time.elapsed(), time.delta())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMDebounce, FSMPlugin, FSMState, FSMTimer, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Open,
+        Closed,
+    }
+
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn manual_clock_drives_the_timing_features() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<DoorFSM>::default());
+        app.insert_resource(FSMClock::manual());
+        let door = app
+            .world_mut()
+            .spawn((
+                DoorFSM::Open,
+                FSMTimer::<DoorFSM>::default(),
+                FSMDebounce::<DoorFSM>::new(Duration::from_secs(1)),
+            ))
+            .id();
+        let step = |app: &mut App, millis: u64| {
+            app.world_mut()
+                .resource_mut::<FSMClock>()
+                .advance(Duration::from_millis(millis));
+            app.update();
+        };
+        let request = |app: &mut App, next: DoorFSM| {
+            app.world_mut().trigger(StateChangeRequest::new(door, next));
+            app.world_mut().flush();
+            *app.world().get::<DoorFSM>(door).unwrap()
+        };
+        let timer = |app: &App| {
+            app.world()
+                .get::<FSMTimer<DoorFSM>>(door)
+                .unwrap()
+                .elapsed()
+        };
+
+        step(&mut app, 300);
+        assert_eq!(timer(&app), Duration::from_millis(300));
+        assert_eq!(request(&mut app, DoorFSM::Closed), DoorFSM::Closed);
+        // Within the debounce window of the first transition
+        assert_eq!(request(&mut app, DoorFSM::Open), DoorFSM::Closed);
+
+        step(&mut app, 500);
+        assert_eq!(timer(&app), Duration::from_millis(500));
+        assert_eq!(request(&mut app, DoorFSM::Open), DoorFSM::Closed);
+        step(&mut app, 500);
+        assert_eq!(request(&mut app, DoorFSM::Open), DoorFSM::Open);
+    }
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum MenuFSM {
+        Shown,
+    }
+
+    impl FSMState for MenuFSM {}
+
+    impl FSMTransition for MenuFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn fsm_types_can_use_their_own_clock() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<DoorFSM>::default(),
+            FSMPlugin::<MenuFSM>::default().with_clock(FSMClock::manual()),
+        ));
+        app.init_resource::<Time>();
+        let door = app
+            .world_mut()
+            .spawn((DoorFSM::Open, FSMTimer::<DoorFSM>::default()))
+            .id();
+        let menu = app
+            .world_mut()
+            .spawn((MenuFSM::Shown, FSMTimer::<MenuFSM>::default()))
+            .id();
+
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(100));
+        app.world_mut()
+            .resource_mut::<FSMTypeClock<MenuFSM>>()
+            .advance(Duration::from_millis(30));
+        app.update();
+
+        assert_eq!(
+            app.world()
+                .get::<FSMTimer<DoorFSM>>(door)
+                .unwrap()
+                .elapsed(),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            app.world()
+                .get::<FSMTimer<MenuFSM>>(menu)
+                .unwrap()
+                .elapsed(),
+            Duration::from_millis(30)
+        );
+    }
+
+    #[test]
+    fn time_scale_stretches_the_timers_of_an_entity() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<DoorFSM>::default());
+        app.insert_resource(FSMClock::manual());
+        let debounce = || FSMDebounce::<DoorFSM>::new(Duration::from_secs(1));
+        let slowed = app
+            .world_mut()
+            .spawn((
+                DoorFSM::Open,
+                FSMTimer::<DoorFSM>::default(),
+                debounce(),
+                FSMTimeScale(0.5),
+            ))
+            .id();
+        let normal = app
+            .world_mut()
+            .spawn((DoorFSM::Open, FSMTimer::<DoorFSM>::default(), debounce()))
+            .id();
+        let request = |app: &mut App, entity: Entity, next: DoorFSM| {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(entity, next));
+            app.world_mut().flush();
+            *app.world().get::<DoorFSM>(entity).unwrap()
+        };
+        let timer = |app: &App, entity| {
+            app.world()
+                .get::<FSMTimer<DoorFSM>>(entity)
+                .unwrap()
+                .elapsed()
+        };
+
+        for entity in [slowed, normal] {
+            assert_eq!(request(&mut app, entity, DoorFSM::Closed), DoorFSM::Closed);
+        }
+        app.world_mut()
+            .resource_mut::<FSMClock>()
+            .advance(Duration::from_millis(1200));
+        app.update();
+
+        assert_eq!(timer(&app, slowed), Duration::from_millis(600));
+        assert_eq!(timer(&app, normal), Duration::from_millis(1200));
+        // Only 600ms passed for the slowed door, still within its debounce window
+        assert_eq!(request(&mut app, slowed, DoorFSM::Open), DoorFSM::Closed);
+        assert_eq!(request(&mut app, normal, DoorFSM::Open), DoorFSM::Open);
+    }
+}
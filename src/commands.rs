@@ -0,0 +1,400 @@
+//! [`EntityCommands`] extension for per-entity FSM behavior, and a [`Commands`] extension for
+//! requests targeting many entities.
+//!
+//! [`FSMEntityCommandsExt::allow_transition`] and [`FSMEntityCommandsExt::deny_transition`]
+//! edit the entity's [`FSMOverride`], e.g. to unlock an ability at runtime.
+//! [`FSMEntityCommandsExt::request_fsm_path`] walks the transition graph towards a target
+//! state, [`FSMEntityCommandsExt::remove_fsm`] takes the FSM off the entity and
+//! [`FSMEntityCommandsExt::swap_fsm`] replaces it with another one.
+//!
+//! Observers added with [`FSMEntityCommandsExt::observe_fsm`] only see the FSM events of the
+//! entity they were added to, and are despawned together with it. Use them for per-instance
+//! reactions, e.g. one boss reacting to its own phase changes:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{Enter, FSMEntityCommandsExt, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum BossFSM { PhaseOne, Enraged }
+//! fn spawn_boss(mut commands: Commands) {
+//!     commands
+//!         .spawn(BossFSM::PhaseOne)
+//!         .observe_fsm(|enter: On<Enter<boss_fsm::Enraged>>| {
+//!             println!("{} is enraged", enter.event().entity);
+//!         });
+//! }
+//! ```
+
+use bevy::ecs::message::Messages;
+use bevy::ecs::query::QueryFilter;
+use bevy::ecs::system::IntoObserverSystem;
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+
+use core::hash::Hash;
+
+use crate::cleanup::{remove_fsm, swap_fsm};
+use crate::graph::FSMGraph;
+use crate::path::FSMPath;
+use crate::ticket::{self, TransitionTicket};
+use crate::{apply_request, observer_name, FSMOverride, FSMState, StateChangeRequest};
+
+/// [`EntityCommands`] extension for entity-scoped FSM observers.
+pub trait FSMEntityCommandsExt {
+    /// Adds an observer that only reacts to FSM events (`Enter`, `Exit`, `Transition`, ...)
+    /// targeting this entity.
+    ///
+    /// The observer is named after its type, like observers added with
+    /// [`FSMAppExt::add_fsm_observer`](crate::FSMAppExt::add_fsm_observer), and is despawned
+    /// when the entity is.
+    fn observe_fsm<E: EntityEvent, B: Bundle, M>(
+        &mut self,
+        observer: impl IntoObserverSystem<E, B, M>,
+    ) -> &mut Self;
+
+    /// Unlocks `from -> to` in the entity's [`FSMOverride`] (see
+    /// [`FSMOverride::add_whitelist`]).
+    ///
+    /// Without an override, inserts a whitelist of this edge with rules enabled, so every
+    /// other transition keeps following the `FSMTransition` rules.
+    fn allow_transition<S: FSMState + Hash>(&mut self, from: S, to: S) -> &mut Self;
+
+    /// Locks `from -> to` in the entity's [`FSMOverride`] (see
+    /// [`FSMOverride::add_blacklist`]).
+    ///
+    /// Without an override, inserts a blacklist of this edge with rules enabled, so every
+    /// other transition keeps following the `FSMTransition` rules.
+    fn deny_transition<S: FSMState + Hash>(&mut self, from: S, to: S) -> &mut Self;
+
+    /// Requests `next` on this entity with the default priority and no source.
+    fn request_fsm_state<S: FSMState>(&mut self, next: S) -> &mut Self;
+
+    /// Triggers `request` on this entity, replacing whatever entity it targeted, e.g.
+    /// `request_fsm(StateChangeRequest::to(LifeFSM::Dead).with_priority(10))`.
+    fn request_fsm<S: FSMState>(&mut self, request: StateChangeRequest<S>) -> &mut Self;
+
+    /// Like [`request_fsm`](Self::request_fsm), returning a [`TransitionTicket`] that
+    /// resolves to whether the request was applied, denied or superseded (see [`ticket`]).
+    fn request_fsm_tracked<S: FSMState>(
+        &mut self,
+        request: StateChangeRequest<S>,
+    ) -> TransitionTicket;
+
+    /// Requests every step of the shortest legal path from the current state to `target`,
+    /// in order (see [`path`](crate::path)).
+    ///
+    /// Does nothing if the entity has no `S` or `target` is unreachable.
+    fn request_fsm_path<S: FSMState + Hash + FromReflect + Typed>(
+        &mut self,
+        target: S,
+    ) -> &mut Self;
+
+    /// Like [`request_fsm_path`](Self::request_fsm_path), requesting one step per frame
+    /// through an [`FSMPath`] component.
+    fn request_fsm_path_stepped<S: FSMState + Hash + FromReflect + Typed>(
+        &mut self,
+        target: S,
+    ) -> &mut Self;
+
+    /// Removes the FSM `S` together with its crate components, firing `Exit` for the last
+    /// state and [`FSMRemoved`](crate::FSMRemoved) at the end (see [`remove_fsm`]).
+    fn remove_fsm<S: FSMState + Hash>(&mut self) -> &mut Self;
+
+    /// Replaces the FSM `A` with the FSM `B` in the state `initial`, firing the `Exit` events
+    /// of `A` and then the initial `Enter` events of `B` (see [`swap_fsm`]).
+    fn swap_fsm<A: FSMState + Hash, B: FSMState>(&mut self, initial: B) -> &mut Self;
+}
+
+impl FSMEntityCommandsExt for EntityCommands<'_> {
+    fn observe_fsm<E: EntityEvent, B: Bundle, M>(
+        &mut self,
+        observer: impl IntoObserverSystem<E, B, M>,
+    ) -> &mut Self {
+        let name = observer_name(&observer);
+        let entity = self.id();
+        self.commands()
+            .spawn((Observer::new(observer).with_entity(entity), Name::new(name)));
+        self
+    }
+
+    fn allow_transition<S: FSMState + Hash>(&mut self, from: S, to: S) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            if let Some(mut config) = entity.get_mut::<FSMOverride<S>>() {
+                config.add_whitelist([(from, to)]);
+            } else {
+                entity.insert(FSMOverride::whitelist([(from, to)]).with_rules());
+            }
+        })
+    }
+
+    fn deny_transition<S: FSMState + Hash>(&mut self, from: S, to: S) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            if let Some(mut config) = entity.get_mut::<FSMOverride<S>>() {
+                config.add_blacklist([(from, to)]);
+            } else {
+                entity.insert(FSMOverride::blacklist([(from, to)]).with_rules());
+            }
+        })
+    }
+
+    fn request_fsm_state<S: FSMState>(&mut self, next: S) -> &mut Self {
+        self.request_fsm(StateChangeRequest::to(next))
+    }
+
+    fn request_fsm<S: FSMState>(&mut self, request: StateChangeRequest<S>) -> &mut Self {
+        self.trigger(|entity| request.with_entity(entity))
+    }
+
+    fn request_fsm_tracked<S: FSMState>(
+        &mut self,
+        request: StateChangeRequest<S>,
+    ) -> TransitionTicket {
+        let ticket = TransitionTicket::new(self.id());
+        let tracked = ticket.clone();
+        self.queue(move |entity: EntityWorldMut| {
+            let request = StateChangeRequest {
+                ticket: Some(tracked.id()),
+                ..request.with_entity(entity.id())
+            };
+            let world = entity.into_world_mut();
+            ticket::register::<S>(world, &tracked);
+            world.trigger(request);
+        });
+        ticket
+    }
+
+    fn request_fsm_path<S: FSMState + Hash + FromReflect + Typed>(
+        &mut self,
+        target: S,
+    ) -> &mut Self {
+        self.queue(move |entity: EntityWorldMut| {
+            let id = entity.id();
+            let Some(path) = entity.get::<S>().and_then(|&cur| S::plan_path(cur, target)) else {
+                return;
+            };
+            let world = entity.into_world_mut();
+            for step in path {
+                world.trigger(StateChangeRequest::new(id, step));
+            }
+        })
+    }
+
+    fn request_fsm_path_stepped<S: FSMState + Hash + FromReflect + Typed>(
+        &mut self,
+        target: S,
+    ) -> &mut Self {
+        self.insert(FSMPath::new(target))
+    }
+
+    fn remove_fsm<S: FSMState + Hash>(&mut self) -> &mut Self {
+        self.queue(|entity: EntityWorldMut| {
+            let id = entity.id();
+            remove_fsm::<S>(entity.into_world_mut(), id);
+        })
+    }
+
+    fn swap_fsm<A: FSMState + Hash, B: FSMState>(&mut self, initial: B) -> &mut Self {
+        self.queue(move |entity: EntityWorldMut| {
+            let id = entity.id();
+            swap_fsm::<A, B>(entity.into_world_mut(), id, initial);
+        })
+    }
+}
+
+/// [`Commands`] extension for requests targeting many entities at once.
+pub trait FSMCommandsExt {
+    /// Requests `next` on every entity with `S` matching the query filter `F`.
+    ///
+    /// Entities are collected in one query pass when the command is applied, and each
+    /// request is validated as usual. With an immediate `FSMPlugin` the requests are applied
+    /// right away instead of triggering one observer event per entity; in buffered modes they
+    /// are written to the buffer as one batch.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMCommandsExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum EnemyFSM { Idle, Fleeing }
+    /// #[derive(Component)]
+    /// struct Enemy;
+    ///
+    /// fn boss_defeated(mut commands: Commands) {
+    ///     commands.request_fsm_state_filtered::<EnemyFSM, With<Enemy>>(EnemyFSM::Fleeing);
+    /// }
+    /// ```
+    fn request_fsm_state_filtered<S: FSMState + Hash, F: QueryFilter + 'static>(
+        &mut self,
+        next: S,
+    ) -> &mut Self;
+}
+
+impl FSMCommandsExt for Commands<'_, '_> {
+    fn request_fsm_state_filtered<S: FSMState + Hash, F: QueryFilter + 'static>(
+        &mut self,
+        next: S,
+    ) -> &mut Self {
+        self.queue(move |world: &mut World| {
+            let requests: Vec<_> = world
+                .query_filtered::<Entity, (With<S>, F)>()
+                .iter(world)
+                .map(|entity| StateChangeRequest::new(entity, next))
+                .collect();
+            // Buffered plugins register the requests as messages
+            if world.contains_resource::<Messages<StateChangeRequest<S>>>() {
+                world.write_message_batch(requests);
+            } else {
+                for request in requests {
+                    apply_request(world, request);
+                }
+            }
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, FSMTransition, RuleType, Transition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[reflect(Component)]
+    enum BossFSM {
+        PhaseOne,
+        Enraged,
+    }
+
+    impl FSMState for BossFSM {}
+
+    impl FSMTransition for BossFSM {
+        fn can_transition(from: Self, _to: Self) -> bool {
+            from == BossFSM::PhaseOne
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Enraged(Vec<Entity>);
+
+    #[test]
+    fn local_observers_only_see_their_entity_and_despawn_with_it() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<BossFSM>::default());
+        app.init_resource::<Enraged>();
+
+        let boss = app.world_mut().spawn(BossFSM::PhaseOne).id();
+        let other = app.world_mut().spawn(BossFSM::PhaseOne).id();
+        app.world_mut().commands().entity(boss).observe_fsm(
+            |enter: On<Enter<BossFSM>>, mut enraged: ResMut<Enraged>| {
+                if enter.event().state == BossFSM::Enraged {
+                    enraged.0.push(enter.event().entity);
+                }
+            },
+        );
+        app.world_mut().flush();
+        let observers = |world: &mut World| world.query::<&Observer>().iter(world).count();
+        let before = observers(app.world_mut());
+
+        for entity in [boss, other] {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(entity, BossFSM::Enraged));
+            app.world_mut().flush();
+        }
+        assert_eq!(app.world().resource::<Enraged>().0, vec![boss]);
+
+        app.world_mut().despawn(boss);
+        assert_eq!(observers(app.world_mut()), before - 1);
+    }
+
+    #[test]
+    fn transition_commands_edit_the_override() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<BossFSM>::default());
+        let boss = app.world_mut().spawn(BossFSM::Enraged).id();
+
+        app.world_mut()
+            .commands()
+            .entity(boss)
+            .allow_transition(BossFSM::Enraged, BossFSM::PhaseOne);
+        app.world_mut().flush();
+        let config = app.world().get::<FSMOverride<BossFSM>>(boss).unwrap();
+        assert_eq!(config.mode, RuleType::Whitelist);
+        assert!(config.call_rules);
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(boss, BossFSM::PhaseOne));
+        app.world_mut().flush();
+        assert_eq!(
+            *app.world().get::<BossFSM>(boss).unwrap(),
+            BossFSM::PhaseOne
+        );
+
+        app.world_mut()
+            .commands()
+            .entity(boss)
+            .deny_transition(BossFSM::Enraged, BossFSM::PhaseOne);
+        app.world_mut().flush();
+        let config = app.world().get::<FSMOverride<BossFSM>>(boss).unwrap();
+        assert!(!config.is_transition_allowed(BossFSM::Enraged, BossFSM::PhaseOne));
+    }
+
+    #[derive(Resource, Default)]
+    struct Sources(Vec<(Entity, Option<Entity>)>);
+
+    #[test]
+    fn request_fsm_targets_the_entity() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<BossFSM>::default());
+        app.init_resource::<Sources>();
+        app.add_observer(
+            |t: On<Transition<BossFSM, BossFSM>>, mut sources: ResMut<Sources>| {
+                sources.0.push((t.event().entity, t.event().source));
+            },
+        );
+        let boss = app.world_mut().spawn(BossFSM::PhaseOne).id();
+        let twin = app.world_mut().spawn(BossFSM::PhaseOne).id();
+        let hero = app.world_mut().spawn_empty().id();
+
+        let mut commands = app.world_mut().commands();
+        commands.entity(boss).request_fsm(
+            StateChangeRequest::to(BossFSM::Enraged)
+                .with_priority(1)
+                .with_source(hero),
+        );
+        commands.entity(twin).request_fsm_state(BossFSM::Enraged);
+        app.world_mut().flush();
+
+        assert_eq!(
+            app.world().resource::<Sources>().0,
+            vec![(boss, Some(hero)), (twin, None)]
+        );
+    }
+
+    #[derive(Component)]
+    struct Minion;
+
+    #[test]
+    fn filtered_requests_reach_every_matching_entity() {
+        for plugin in [FSMPlugin::<BossFSM>::default(), FSMPlugin::buffered()] {
+            let mut app = App::new();
+            app.add_plugins(plugin);
+            let minions: Vec<_> = (0..3)
+                .map(|_| app.world_mut().spawn((BossFSM::PhaseOne, Minion)).id())
+                .collect();
+            let boss = app.world_mut().spawn(BossFSM::PhaseOne).id();
+
+            app.world_mut()
+                .commands()
+                .request_fsm_state_filtered::<BossFSM, With<Minion>>(BossFSM::Enraged);
+            app.update();
+
+            let state = |entity| *app.world().get::<BossFSM>(entity).unwrap();
+            assert!(minions
+                .iter()
+                .all(|&minion| state(minion) == BossFSM::Enraged));
+            assert_eq!(state(boss), BossFSM::PhaseOne);
+        }
+    }
+}
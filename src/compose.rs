@@ -0,0 +1,144 @@
+//! Superstates hosting a whole inner FSM.
+//!
+//! Some states are machines of their own: while the player is `InCombat`, a `CombatFSM` decides
+//! between attacking and blocking. [`FSMAppExt::fsm_superstate`](crate::FSMAppExt::fsm_superstate)
+//! ties the inner FSM's lifetime to the hosting state, and [`FSMComposed`] queries both levels
+//! at once:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::compose::FSMComposed;
+//! # use bevy_fsm::{FSMAppExt, FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum PlayerFSM { Exploring, InCombat }
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum CombatFSM { Idle, Attacking, Blocking }
+//! # let mut app = App::new();
+//! app.add_plugins((FSMPlugin::<PlayerFSM>::default(), FSMPlugin::<CombatFSM>::default()))
+//!     .fsm_superstate(PlayerFSM::InCombat, CombatFSM::Idle);
+//!
+//! fn hud(players: Query<FSMComposed<PlayerFSM, CombatFSM>>) {
+//!     for player in &players {
+//!         match player.state() {
+//!             (PlayerFSM::InCombat, Some(CombatFSM::Blocking)) => { /* shield icon */ }
+//!             (PlayerFSM::InCombat, _) => { /* sword icon */ }
+//!             (PlayerFSM::Exploring, _) => { /* map icon */ }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Entering the hosting state inserts the inner FSM in its initial state, firing its initial
+//! `Enter`. Leaving it, removing the outer FSM or despawning the entity removes the inner FSM,
+//! firing its `Exit` before the outer transition continues. Superstates nest: the inner FSM can
+//! host another FSM in turn.
+
+use bevy::ecs::query::QueryData;
+
+use crate::FSMState;
+
+/// Query data for an outer FSM `O` together with the inner FSM `I` it may host.
+#[derive(QueryData)]
+pub struct FSMComposed<O: FSMState, I: FSMState> {
+    /// The outer state.
+    pub outer: &'static O,
+    /// The inner state, while a superstate hosts `I`.
+    pub inner: Option<&'static I>,
+}
+
+impl<O: FSMState, I: FSMState> FSMComposedItem<'_, '_, O, I> {
+    /// The effective composed state.
+    pub fn state(&self) -> (O, Option<I>) {
+        (*self.outer, self.inner.copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Exit, FSMAppExt, FSMPlugin, FSMTransition, StateChangeRequest};
+    use bevy::prelude::*;
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum PlayerFSM {
+        Exploring,
+        InCombat,
+    }
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum CombatFSM {
+        Idle,
+        Attacking,
+    }
+
+    impl FSMState for PlayerFSM {}
+    impl FSMState for CombatFSM {}
+
+    impl FSMTransition for PlayerFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    impl FSMTransition for CombatFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Exits(Vec<String>);
+
+    #[test]
+    fn superstates_host_and_exit_the_inner_fsm() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<PlayerFSM>::default(),
+            FSMPlugin::<CombatFSM>::default(),
+        ))
+        .fsm_superstate(PlayerFSM::InCombat, CombatFSM::Idle);
+        app.init_resource::<Exits>();
+        app.add_observer(|exit: On<Exit<PlayerFSM>>, mut exits: ResMut<Exits>| {
+            exits.0.push(format!("{:?}", exit.state));
+        });
+        app.add_observer(|exit: On<Exit<CombatFSM>>, mut exits: ResMut<Exits>| {
+            exits.0.push(format!("{:?}", exit.state));
+        });
+        let player = app.world_mut().spawn(PlayerFSM::Exploring).id();
+        let veteran = app.world_mut().spawn(PlayerFSM::InCombat).id();
+        app.update();
+
+        let state = |app: &mut App, entity| {
+            app.world_mut()
+                .query::<FSMComposed<PlayerFSM, CombatFSM>>()
+                .get(app.world(), entity)
+                .unwrap()
+                .state()
+        };
+        assert_eq!(state(&mut app, player), (PlayerFSM::Exploring, None));
+        // Spawned in the superstate
+        assert_eq!(
+            state(&mut app, veteran),
+            (PlayerFSM::InCombat, Some(CombatFSM::Idle))
+        );
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(player, PlayerFSM::InCombat));
+        app.world_mut().flush();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(player, CombatFSM::Attacking));
+        app.update();
+        assert_eq!(
+            state(&mut app, player),
+            (PlayerFSM::InCombat, Some(CombatFSM::Attacking))
+        );
+
+        app.world_mut().resource_mut::<Exits>().0.clear();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(player, PlayerFSM::Exploring));
+        app.update();
+        assert_eq!(state(&mut app, player), (PlayerFSM::Exploring, None));
+        assert_eq!(app.world().resource::<Exits>().0, ["InCombat", "Attacking"]);
+    }
+}
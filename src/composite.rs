@@ -0,0 +1,199 @@
+//! Conditions across several FSMs of the same entity.
+//!
+//! Cross-machine constraints ("can't reload while dead") otherwise end up as one world lookup
+//! per FSM. [`require_states!`](crate::require_states) builds a [`RequireStates`] from one state
+//! per FSM type, checked with a single entity lookup in `can_transition_ctx`, or with a single
+//! combined query as a run condition:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{require_states, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum LifeFSM { Alive, Dead }
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum WeaponFSM { Ready, Reloading }
+//! #[derive(Component, EnumEvent, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum AmmoFSM { Full, Empty, Refilling }
+//!
+//! impl FSMTransition for AmmoFSM {
+//!     fn can_transition(_: Self, _: Self) -> bool {
+//!         true
+//!     }
+//!
+//!     fn can_transition_ctx(world: &World, entity: Entity, _: Self, to: Self) -> bool {
+//!         // Can't refill while dead or in the middle of a reload animation
+//!         to != AmmoFSM::Refilling
+//!             || require_states!(LifeFSM::Alive, WeaponFSM::Ready).check(world, entity)
+//!     }
+//! }
+//!
+//! # fn aim_assist() {}
+//! # let mut app = App::new();
+//! app.add_systems(
+//!     Update,
+//!     // Runs while any entity is alive with its weapon ready
+//!     aim_assist.run_if(require_states!(LifeFSM::Alive, WeaponFSM::Ready).any_entity()),
+//! );
+//! ```
+//!
+//! Up to 8 FSM types can be combined. An entity missing one of the FSMs does not match.
+
+use bevy::ecs::query::{QueryData, QueryItem, ReadOnlyQueryData};
+use bevy::prelude::*;
+
+use crate::FSMState;
+
+/// A tuple with one state per FSM type, matched by [`RequireStates`].
+pub trait FSMStateTuple: Copy + Send + Sync + 'static {
+    /// Query data fetching all the FSM components at once.
+    type Data: ReadOnlyQueryData;
+
+    /// Whether `entity` is in all the states.
+    fn matches_entity(&self, entity: EntityRef) -> bool;
+
+    /// Whether the components fetched by [`Self::Data`] are in all the states.
+    fn matches_item(&self, item: QueryItem<'_, '_, Self::Data>) -> bool;
+}
+
+macro_rules! impl_fsm_state_tuple {
+    ($(($S:ident, $i:tt)),+) => {
+        impl<$($S: FSMState),+> FSMStateTuple for ($($S,)+) {
+            type Data = ($(&'static $S,)+);
+
+            fn matches_entity(&self, entity: EntityRef) -> bool {
+                $(entity.get::<$S>() == Some(&self.$i))&&+
+            }
+
+            fn matches_item(&self, item: QueryItem<'_, '_, Self::Data>) -> bool {
+                $(*item.$i == self.$i)&&+
+            }
+        }
+    };
+}
+
+impl_fsm_state_tuple!((A, 0));
+impl_fsm_state_tuple!((A, 0), (B, 1));
+impl_fsm_state_tuple!((A, 0), (B, 1), (C, 2));
+impl_fsm_state_tuple!((A, 0), (B, 1), (C, 2), (D, 3));
+impl_fsm_state_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4));
+impl_fsm_state_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5));
+impl_fsm_state_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6));
+impl_fsm_state_tuple!(
+    (A, 0),
+    (B, 1),
+    (C, 2),
+    (D, 3),
+    (E, 4),
+    (F, 5),
+    (G, 6),
+    (H, 7)
+);
+
+/// Condition requiring an entity to be in one state of each of several FSMs.
+///
+/// Usually built with [`require_states!`](crate::require_states).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequireStates<T>(pub T);
+
+impl<T: FSMStateTuple> RequireStates<T> {
+    /// Whether `entity` is in all the states, e.g. inside `can_transition_ctx`.
+    pub fn check(&self, world: &World, entity: Entity) -> bool {
+        world
+            .get_entity(entity)
+            .is_ok_and(|entity| self.0.matches_entity(entity))
+    }
+
+    /// Whether the already fetched `entity` is in all the states.
+    pub fn matches(&self, entity: EntityRef) -> bool {
+        self.0.matches_entity(entity)
+    }
+
+    /// Run condition passing while any entity is in all the states.
+    pub fn any_entity(self) -> impl FnMut(Query<T::Data>) -> bool + Clone
+    where
+        T::Data: QueryData<ReadOnly = T::Data>,
+    {
+        move |query: Query<T::Data>| query.iter().any(|item| self.0.matches_item(item))
+    }
+}
+
+/// Builds a [`RequireStates`](crate::composite::RequireStates) condition from one state per
+/// FSM type, e.g. `require_states!(LifeFSM::Alive, WeaponFSM::Ready)`.
+///
+/// See the [`composite`](crate::composite) module for usage.
+#[macro_export]
+macro_rules! require_states {
+    ($($state:expr),+ $(,)?) => {
+        $crate::composite::RequireStates(($($state,)+))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FSMTransition;
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Dead,
+    }
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum WeaponFSM {
+        Ready,
+        Reloading,
+    }
+
+    impl FSMState for LifeFSM {}
+    impl FSMState for WeaponFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    impl FSMTransition for WeaponFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Ran(u32);
+
+    #[test]
+    fn states_of_several_fsms_are_required_together() {
+        let mut app = App::new();
+        app.init_resource::<Ran>();
+        app.add_systems(
+            Update,
+            (|mut ran: ResMut<Ran>| ran.0 += 1)
+                .run_if(require_states!(LifeFSM::Alive, WeaponFSM::Ready).any_entity()),
+        );
+        let hero = app
+            .world_mut()
+            .spawn((LifeFSM::Alive, WeaponFSM::Reloading))
+            .id();
+        let ghost = app.world_mut().spawn(LifeFSM::Alive).id();
+
+        let ready = require_states!(LifeFSM::Alive, WeaponFSM::Ready);
+        assert!(!ready.check(app.world(), hero));
+        assert!(!ready.check(app.world(), ghost));
+        assert!(require_states!(LifeFSM::Alive).check(app.world(), ghost));
+        app.update();
+        assert_eq!(app.world().resource::<Ran>().0, 0);
+
+        app.world_mut().entity_mut(hero).insert(WeaponFSM::Ready);
+        assert!(ready.check(app.world(), hero));
+        app.update();
+        assert_eq!(app.world().resource::<Ran>().0, 1);
+
+        app.world_mut().entity_mut(hero).insert(LifeFSM::Dead);
+        assert!(!ready.matches(app.world().entity(hero)));
+        app.update();
+        assert_eq!(app.world().resource::<Ran>().0, 1);
+    }
+}
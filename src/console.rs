@@ -0,0 +1,278 @@
+//! Dev console commands for inspecting and driving FSMs at runtime.
+//!
+//! [`FSMConsolePlugin`] understands two commands:
+//!
+//! ```text
+//! fsm set <entity> <FSMType> <Variant>   request a (validated) state change
+//! fsm list <FSMType>                     list variants and every entity's current state
+//! ```
+//!
+//! Entities are written the way Bevy displays them (`3v1`), or as a bare index (`3`).
+//! Type and variant names are resolved through reflection, so every FSM type added via
+//! [`FSMPlugin`](crate::FSMPlugin) is available without extra registration.
+//!
+//! The plugin is console-agnostic: forward lines from your console of choice as
+//! [`FSMConsoleCommand`] messages and print the resulting [`FSMConsoleOutput`] messages,
+//! or call [`run_fsm_command`] directly from an exclusive system.
+
+use bevy::ecs::entity::EntityIndex;
+use bevy::prelude::*;
+
+use crate::reflect::{fsm_type_data, FSMReflectError};
+
+/// Plugin that processes [`FSMConsoleCommand`] messages every frame.
+pub struct FSMConsolePlugin;
+
+impl Plugin for FSMConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<FSMConsoleCommand>()
+            .add_message::<FSMConsoleOutput>()
+            .add_systems(Update, process_console_commands);
+    }
+}
+
+/// A console line to execute, e.g. `fsm set 3v1 LifeFSM Dead`.
+#[derive(Message, Debug, Clone)]
+pub struct FSMConsoleCommand(pub String);
+
+/// The result of one executed [`FSMConsoleCommand`].
+#[derive(Message, Debug, Clone)]
+pub struct FSMConsoleOutput(pub Result<String, FSMConsoleError>);
+
+/// Errors produced while executing a console command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FSMConsoleError {
+    /// The line is not a recognized `fsm` command.
+    Usage(String),
+    /// The entity argument could not be parsed or resolved.
+    InvalidEntity(String),
+    /// Resolving the FSM type, variant or component failed.
+    Reflect(FSMReflectError),
+}
+
+impl core::fmt::Display for FSMConsoleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Usage(line) => write!(
+                f,
+                "unrecognized command '{line}' (usage: fsm set <entity> <FSMType> <Variant> | fsm list <FSMType>)"
+            ),
+            Self::InvalidEntity(arg) => write!(f, "'{arg}' is not a valid entity"),
+            Self::Reflect(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FSMConsoleError {}
+
+impl From<FSMReflectError> for FSMConsoleError {
+    fn from(err: FSMReflectError) -> Self {
+        Self::Reflect(err)
+    }
+}
+
+/// Executes a single console line against the world and returns the text to display.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::console::run_fsm_command;
+/// fn debug_kill(world: &mut World) {
+///     match run_fsm_command(world, "fsm set 3v1 LifeFSM Dead") {
+///         Ok(output) => println!("{output}"),
+///         Err(err) => println!("error: {err}"),
+///     }
+/// }
+/// ```
+pub fn run_fsm_command(world: &mut World, line: &str) -> Result<String, FSMConsoleError> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+    match args.as_slice() {
+        ["fsm", "set", entity, fsm, variant] => {
+            let entity = parse_entity(world, entity)?;
+            let data = fsm_type_data(world, fsm)?;
+            let before = data.state_name(world, entity);
+            data.request(world, entity, variant)?;
+            let after = data.state_name(world, entity);
+            Ok(match (before, after) {
+                (Some(before), Some(after)) if before != after => {
+                    format!("{entity} {}: {before} -> {after}", data.type_name())
+                }
+                (_, Some(after)) if after == *variant => {
+                    format!("{entity} {}: already {after}", data.type_name())
+                }
+                (_, after) => format!(
+                    "{entity} {}: transition to {variant} denied (state: {})",
+                    data.type_name(),
+                    after.unwrap_or("<removed>")
+                ),
+            })
+        }
+        ["fsm", "list", fsm] => {
+            let data = fsm_type_data(world, fsm)?;
+            let mut entities = data.entities(world);
+            entities.sort();
+            let mut output = format!(
+                "{} [{}]: {} entities",
+                data.type_name(),
+                data.variant_names().join(", "),
+                entities.len()
+            );
+            for entity in entities {
+                let state = data.state_name(world, entity).unwrap_or("?");
+                output.push_str(&format!("\n  {entity}: {state}"));
+            }
+            Ok(output)
+        }
+        _ => Err(FSMConsoleError::Usage(line.trim().to_string())),
+    }
+}
+
+/// Parses `3v1` (as displayed by Bevy) or a bare index `3` into a live entity.
+fn parse_entity(world: &World, arg: &str) -> Result<Entity, FSMConsoleError> {
+    let invalid = || FSMConsoleError::InvalidEntity(arg.to_string());
+    let index = arg.split('v').next().unwrap_or(arg);
+    let index = index
+        .parse::<u32>()
+        .ok()
+        .and_then(EntityIndex::from_raw_u32)
+        .ok_or_else(invalid)?;
+    let entity = world.entities().resolve_from_index(index);
+
+    // A generation suffix must match the live entity exactly.
+    if arg.contains('v') && entity.to_string() != arg {
+        return Err(invalid());
+    }
+    if world.get_entity(entity).is_err() {
+        return Err(FSMConsoleError::Reflect(FSMReflectError::NoSuchEntity(
+            entity,
+        )));
+    }
+    Ok(entity)
+}
+
+/// Drains [`FSMConsoleCommand`] messages, executes them and writes [`FSMConsoleOutput`].
+pub fn process_console_commands(world: &mut World) {
+    let commands: Vec<FSMConsoleCommand> = world
+        .resource_mut::<Messages<FSMConsoleCommand>>()
+        .drain()
+        .collect();
+
+    for FSMConsoleCommand(line) in commands {
+        let output = run_fsm_command(world, &line);
+        world.write_message(FSMConsoleOutput(output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMState, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[reflect(Component)]
+    enum DoorFSM {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            !matches!((from, to), (DoorFSM::Open, DoorFSM::Locked))
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(FSMPlugin::<DoorFSM>::default());
+        app
+    }
+
+    #[test]
+    fn set_requests_validated_transition() {
+        let mut app = app();
+        let e = app.world_mut().spawn(DoorFSM::Open).id();
+
+        let output = run_fsm_command(app.world_mut(), &format!("fsm set {e} DoorFSM Closed"));
+        assert!(output.is_ok(), "{output:?}");
+        assert_eq!(*app.world().get::<DoorFSM>(e).unwrap(), DoorFSM::Closed);
+
+        // Bare index works too; Closed -> Locked is allowed
+        let index = e.index().to_string();
+        run_fsm_command(app.world_mut(), &format!("fsm set {index} DoorFSM Locked")).unwrap();
+        assert_eq!(*app.world().get::<DoorFSM>(e).unwrap(), DoorFSM::Locked);
+    }
+
+    #[test]
+    fn set_reports_denied_transition() {
+        let mut app = app();
+        let e = app.world_mut().spawn(DoorFSM::Open).id();
+
+        let output =
+            run_fsm_command(app.world_mut(), &format!("fsm set {e} DoorFSM Locked")).unwrap();
+        assert!(output.contains("denied"), "{output}");
+        assert_eq!(*app.world().get::<DoorFSM>(e).unwrap(), DoorFSM::Open);
+    }
+
+    #[test]
+    fn list_shows_variants_and_states() {
+        let mut app = app();
+        let a = app.world_mut().spawn(DoorFSM::Open).id();
+        let b = app.world_mut().spawn(DoorFSM::Locked).id();
+
+        let output = run_fsm_command(app.world_mut(), "fsm list DoorFSM").unwrap();
+        assert!(output.starts_with("DoorFSM [Open, Closed, Locked]: 2 entities"));
+        assert!(output.contains(&format!("{a}: Open")));
+        assert!(output.contains(&format!("{b}: Locked")));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let mut app = app();
+        let e = app.world_mut().spawn(DoorFSM::Open).id();
+
+        assert_eq!(
+            run_fsm_command(app.world_mut(), "fsm list WindowFSM"),
+            Err(FSMConsoleError::Reflect(FSMReflectError::UnknownFSM(
+                "WindowFSM".to_string()
+            )))
+        );
+        assert!(matches!(
+            run_fsm_command(app.world_mut(), &format!("fsm set {e} DoorFSM Ajar")),
+            Err(FSMConsoleError::Reflect(
+                FSMReflectError::UnknownVariant { .. }
+            ))
+        ));
+        assert!(matches!(
+            run_fsm_command(app.world_mut(), "fsm set nope DoorFSM Open"),
+            Err(FSMConsoleError::InvalidEntity(_))
+        ));
+        assert!(matches!(
+            run_fsm_command(app.world_mut(), "fsm frobnicate"),
+            Err(FSMConsoleError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn plugin_processes_command_messages() {
+        let mut app = app();
+        app.add_plugins(FSMConsolePlugin);
+        let e = app.world_mut().spawn(DoorFSM::Open).id();
+
+        app.world_mut()
+            .write_message(FSMConsoleCommand(format!("fsm set {e} DoorFSM Closed")));
+        app.update();
+
+        assert_eq!(*app.world().get::<DoorFSM>(e).unwrap(), DoorFSM::Closed);
+        let outputs: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Messages<FSMConsoleOutput>>()
+            .drain()
+            .collect();
+        assert_eq!(outputs.len(), 1);
+        assert!(outputs[0].0.is_ok());
+    }
+}
@@ -0,0 +1,223 @@
+//! Transition coverage reporting for QA and tests.
+//!
+//! [`FSMCoveragePlugin`] records every transition of `S` into an [`FSMTransitionLog`].
+//! [`FSMTransitionLog::coverage`] compares the log against the transition table declared by
+//! [`FSMTransition::can_transition`](crate::FSMTransition::can_transition) and lists the edges that were never exercised:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::coverage::{FSMCoveragePlugin, FSMTransitionLog};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum DoorFSM { Open, Closed }
+//! fn report_coverage(log: Res<FSMTransitionLog<DoorFSM>>) {
+//!     println!("{}", log.coverage());
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins((FSMPlugin::<DoorFSM>::default(), FSMCoveragePlugin::<DoorFSM>::default()))
+//!     .add_systems(Last, report_coverage.run_if(on_message::<AppExit>));
+//! ```
+//!
+//! The declared table is derived from the enum's unit variants via reflection (see
+//! [`graph`](crate::graph)). Rules that depend on the world
+//! ([`FSMTransition::can_transition_ctx`](crate::FSMTransition::can_transition_ctx)) or on
+//! `FSMOverride` are not part of it.
+
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+
+use crate::graph::FSMGraph;
+use crate::propagation;
+use crate::{FSMState, Transition};
+
+/// All `from -> to` pairs (`from != to`) that
+/// [`FSMTransition::can_transition`](crate::FSMTransition::can_transition) allows.
+///
+/// Shorthand for collecting [`FSMGraph::transitions`].
+pub fn declared_transitions<S: FSMState + FromReflect + Typed>() -> Vec<(S, S)> {
+    S::transitions().collect()
+}
+
+/// Every transition of `S` applied since the log was created or last cleared.
+#[derive(Resource, Debug, Clone)]
+pub struct FSMTransitionLog<S: Send + Sync + 'static> {
+    entries: Vec<(Entity, S, S)>,
+}
+
+impl<S: Send + Sync + 'static> Default for FSMTransitionLog<S> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<S: FSMState + FromReflect + Typed> FSMTransitionLog<S> {
+    /// Appends a transition.
+    pub fn record(&mut self, entity: Entity, from: S, to: S) {
+        self.entries.push((entity, from, to));
+    }
+
+    /// Recorded `(entity, from, to)` entries, oldest first.
+    pub fn entries(&self) -> &[(Entity, S, S)] {
+        &self.entries
+    }
+
+    /// Forgets all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Compares the log against [`declared_transitions`].
+    pub fn coverage(&self) -> FSMCoverageReport<S> {
+        let count = |from: S, to: S| {
+            self.entries
+                .iter()
+                .filter(|(_, f, t)| *f == from && *t == to)
+                .count()
+        };
+        let (exercised, untested) = declared_transitions::<S>()
+            .into_iter()
+            .map(|(from, to)| (from, to, count(from, to)))
+            .partition::<Vec<_>, _>(|&(_, _, hits)| hits > 0);
+        FSMCoverageReport {
+            exercised,
+            untested: untested
+                .into_iter()
+                .map(|(from, to, _)| (from, to))
+                .collect(),
+        }
+    }
+}
+
+/// Result of [`FSMTransitionLog::coverage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FSMCoverageReport<S> {
+    /// Declared edges that were taken, with their hit counts.
+    pub exercised: Vec<(S, S, usize)>,
+    /// Declared edges that were never taken.
+    pub untested: Vec<(S, S)>,
+}
+
+impl<S> FSMCoverageReport<S> {
+    /// Fraction of declared edges that were exercised (`1.0` for an empty table).
+    pub fn ratio(&self) -> f32 {
+        let total = self.exercised.len() + self.untested.len();
+        if total == 0 {
+            return 1.0;
+        }
+        self.exercised.len() as f32 / total as f32
+    }
+}
+
+impl<S: core::fmt::Debug> core::fmt::Display for FSMCoverageReport<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}/{} transitions exercised",
+            self.exercised.len(),
+            self.exercised.len() + self.untested.len()
+        )?;
+        for (from, to) in &self.untested {
+            write!(f, "\n  never taken: {from:?} -> {to:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Plugin recording the transitions of `S` into [`FSMTransitionLog<S>`].
+pub struct FSMCoveragePlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMCoveragePlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: FSMState + FromReflect + Typed> Plugin for FSMCoveragePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FSMTransitionLog<S>>()
+            .add_observer(record_transition::<S>);
+    }
+}
+
+/// Observer appending every transition to the [`FSMTransitionLog`].
+#[allow(clippy::needless_pass_by_value)]
+pub fn record_transition<S: FSMState + FromReflect + Typed>(
+    trigger: On<Transition<S, S>>,
+    mut log: ResMut<FSMTransitionLog<S>>,
+) {
+    if propagation::bubbled(&trigger) {
+        return;
+    }
+    let event = trigger.event();
+    log.record(event.entity, event.from, event.to);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (DoorFSM::Open, DoorFSM::Closed)
+                    | (DoorFSM::Closed, DoorFSM::Open)
+                    | (DoorFSM::Closed, DoorFSM::Locked)
+                    | (DoorFSM::Locked, DoorFSM::Closed)
+            )
+        }
+    }
+
+    #[test]
+    fn coverage_lists_untested_edges() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<DoorFSM>::default(),
+            FSMCoveragePlugin::<DoorFSM>::default(),
+        ));
+        let door = app.world_mut().spawn(DoorFSM::Open).id();
+        for next in [DoorFSM::Closed, DoorFSM::Open, DoorFSM::Closed] {
+            app.world_mut().trigger(StateChangeRequest::new(door, next));
+            app.world_mut().flush();
+        }
+
+        let report = app
+            .world()
+            .resource::<FSMTransitionLog<DoorFSM>>()
+            .coverage();
+        assert_eq!(
+            report.exercised,
+            vec![
+                (DoorFSM::Open, DoorFSM::Closed, 2),
+                (DoorFSM::Closed, DoorFSM::Open, 1),
+            ]
+        );
+        assert_eq!(
+            report.untested,
+            vec![
+                (DoorFSM::Closed, DoorFSM::Locked),
+                (DoorFSM::Locked, DoorFSM::Closed),
+            ]
+        );
+        assert_eq!(report.ratio(), 0.5);
+        assert!(report.to_string().contains("never taken: Closed -> Locked"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Rate limiting transitions per entity.
+//!
+//! [`FSMDebounce`] allows at most one transition per time window, protecting against UI
+//! double-clicks or AI flapping between `Aggro` and `Idle` every frame. Requests arriving
+//! within the window after a transition are denied with
+//! [`TransitionVerdict::Debounced`](crate::TransitionVerdict::Debounced), or with
+//! [`FSMDebounce::queue_latest`] held back and applied once the window has passed:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMDebounce, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # use core::time::Duration;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum EnemyFSM { Idle, Aggro }
+//! fn spawn_enemy(mut commands: Commands) {
+//!     commands.spawn((
+//!         EnemyFSM::Idle,
+//!         // Change state at most twice per second, keeping the last request
+//!         FSMDebounce::<EnemyFSM>::queue_latest(Duration::from_millis(500)),
+//!     ));
+//! }
+//! ```
+//!
+//! The window is measured with the [`FSMClock`](crate::FSMClock) and starts when a transition is
+//! applied. Without its time resource (e.g. `Time` in tests without `MinimalPlugins`) nothing is
+//! debounced. A queued
+//! request is replaced by every newer one and validated when it is finally applied.
+
+use bevy::prelude::*;
+use core::time::Duration;
+
+use crate::clock::{self, FSMTime, FSMTimeScale};
+use crate::ticket;
+use crate::{FSMPaused, FSMState, StateChangeRequest};
+
+/// What happens to requests arriving within the debounce window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebounceMode {
+    /// Deny them, firing `TransitionDenied`.
+    #[default]
+    Drop,
+    /// Keep the most recent one and apply it when the window has passed.
+    QueueLatest,
+}
+
+/// Component limiting the FSM `S` of an entity to one transition per `window`.
+#[derive(Component, Debug, Clone)]
+pub struct FSMDebounce<S: Copy + Send + Sync + 'static> {
+    /// Minimum time between two transitions.
+    pub window: Duration,
+    /// What happens to requests arriving within the window.
+    pub mode: DebounceMode,
+    last: Option<Duration>,
+    pub(crate) queued: Option<StateChangeRequest<S>>,
+}
+
+impl<S: Copy + Send + Sync + 'static> FSMDebounce<S> {
+    /// Deny requests arriving within `window` after a transition.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            mode: DebounceMode::Drop,
+            last: None,
+            queued: None,
+        }
+    }
+
+    /// Hold back the latest request arriving within `window` after a transition.
+    pub fn queue_latest(window: Duration) -> Self {
+        Self {
+            mode: DebounceMode::QueueLatest,
+            ..Self::new(window)
+        }
+    }
+
+    /// The request waiting for the window to pass, if any.
+    pub fn queued(&self) -> Option<&StateChangeRequest<S>> {
+        self.queued.as_ref()
+    }
+
+    fn is_open(&self, now: Duration, scale: Option<&FSMTimeScale>) -> bool {
+        self.last.is_none_or(|last| {
+            let passed = now.saturating_sub(last);
+            scale.map_or(passed, |scale| scale.apply(passed)) >= self.window
+        })
+    }
+}
+
+/// Whether `entity` transitioned less than its debounce window ago.
+pub(crate) fn is_debouncing<S: FSMState>(world: &World, entity: Entity) -> bool {
+    let (Some(debounce), Some(now)) = (
+        world.get::<FSMDebounce<S>>(entity),
+        clock::elapsed::<S>(world),
+    ) else {
+        return false;
+    };
+    !debounce.is_open(now, world.get::<FSMTimeScale>(entity))
+}
+
+/// Starts the debounce window of `entity`.
+pub(crate) fn record<S: FSMState>(world: &mut World, entity: Entity) {
+    let Some(now) = clock::elapsed::<S>(world) else {
+        return;
+    };
+    if let Some(mut debounce) = world.get_mut::<FSMDebounce<S>>(entity) {
+        debounce.last = Some(now);
+    }
+}
+
+/// Holds back a debounced `request` if the entity queues them, returning whether it did.
+pub(crate) fn hold<S: FSMState>(world: &mut World, request: StateChangeRequest<S>) -> bool {
+    let replaced = match world.get_mut::<FSMDebounce<S>>(request.entity) {
+        Some(mut debounce) if debounce.mode == DebounceMode::QueueLatest => {
+            debounce.queued.replace(request)
+        }
+        _ => return false,
+    };
+    ticket::supersede(world, replaced);
+    true
+}
+
+/// System re-issuing queued requests whose debounce window has passed, holding them while the
+/// FSM is paused.
+#[allow(clippy::type_complexity)]
+pub fn release_debounced_requests<S: FSMState>(
+    mut commands: Commands,
+    time: FSMTime<S>,
+    mut debounces: Query<(&mut FSMDebounce<S>, Option<&FSMTimeScale>), Without<FSMPaused<S>>>,
+) {
+    let Some(now) = time.elapsed() else {
+        return;
+    };
+    for (mut debounce, scale) in &mut debounces {
+        if debounce.queued.is_some() && debounce.is_open(now, scale) {
+            if let Some(request) = debounce.queued.take() {
+                commands.trigger(request);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, TransitionDenied, TransitionVerdict};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum EnemyFSM {
+        Idle,
+        Aggro,
+    }
+
+    impl FSMState for EnemyFSM {}
+
+    impl FSMTransition for EnemyFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Denied(Vec<TransitionVerdict>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<EnemyFSM>::default());
+        app.init_resource::<Time>();
+        app.init_resource::<Denied>();
+        app.add_observer(
+            |denied: On<TransitionDenied<EnemyFSM>>, mut log: ResMut<Denied>| {
+                log.0.push(denied.event().reason);
+            },
+        );
+        app
+    }
+
+    fn request(app: &mut App, entity: Entity, next: EnemyFSM) -> EnemyFSM {
+        app.world_mut()
+            .trigger(StateChangeRequest::new(entity, next));
+        app.world_mut().flush();
+        *app.world().get::<EnemyFSM>(entity).unwrap()
+    }
+
+    fn advance(app: &mut App, millis: u64) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(millis));
+    }
+
+    #[test]
+    fn requests_within_the_window_are_dropped() {
+        use EnemyFSM::*;
+
+        let mut app = app();
+        let enemy = app
+            .world_mut()
+            .spawn((Idle, FSMDebounce::<EnemyFSM>::new(Duration::from_secs(1))))
+            .id();
+
+        assert_eq!(request(&mut app, enemy, Aggro), Aggro);
+        advance(&mut app, 500);
+        assert_eq!(request(&mut app, enemy, Idle), Aggro);
+        advance(&mut app, 500);
+        assert_eq!(request(&mut app, enemy, Idle), Idle);
+        assert_eq!(
+            app.world().resource::<Denied>().0,
+            vec![TransitionVerdict::Debounced]
+        );
+    }
+
+    #[test]
+    fn latest_request_is_applied_after_the_window() {
+        use EnemyFSM::*;
+
+        let mut app = app();
+        let enemy = app
+            .world_mut()
+            .spawn((
+                Idle,
+                FSMDebounce::<EnemyFSM>::queue_latest(Duration::from_secs(1)),
+            ))
+            .id();
+
+        assert_eq!(request(&mut app, enemy, Aggro), Aggro);
+        // Flapping back and forth within the window: only the last request survives
+        request(&mut app, enemy, Idle);
+        request(&mut app, enemy, Aggro);
+        request(&mut app, enemy, Idle);
+        app.update();
+        assert_eq!(*app.world().get::<EnemyFSM>(enemy).unwrap(), Aggro);
+
+        advance(&mut app, 1000);
+        app.update();
+        assert_eq!(*app.world().get::<EnemyFSM>(enemy).unwrap(), Idle);
+        assert!(app.world().resource::<Denied>().0.is_empty());
+    }
+}
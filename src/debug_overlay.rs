@@ -0,0 +1,270 @@
+//! On-screen labels showing the current state of every FSM entity, for playtests.
+//!
+//! Enabled by the `debug-overlay` feature. [`FSMDebugOverlayPlugin`] puts a text label above
+//! each entity carrying `S` and a `Transform`, showing the state name and the time spent in
+//! it. The [`FSMDebugOverlay`] resource toggles all labels at once, e.g. from a debug key:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::debug_overlay::{FSMDebugOverlay, FSMDebugOverlayPlugin};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum EnemyFSM { Idle, Aggro }
+//! fn toggle_overlay(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<FSMDebugOverlay>) {
+//!     if keys.just_pressed(KeyCode::F3) {
+//!         overlay.enabled = !overlay.enabled;
+//!     }
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins((
+//!     FSMPlugin::<EnemyFSM>::default(),
+//!     FSMDebugOverlayPlugin::<EnemyFSM>::default().with_offset(Vec3::Y * 2.0),
+//! ))
+//! .add_systems(Update, toggle_overlay);
+//! ```
+//!
+//! Labels are UI text nodes positioned through the first active camera, so they work for 2D
+//! and 3D scenes alike. Entities outside the view hide their label. FSM types added with
+//! [`FSMVerbosity::Off`] get no labels.
+
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+use core::time::Duration;
+
+use crate::reflect::variant_name;
+use crate::{FSMIdentity, FSMObserverHierarchy, FSMState, FSMVerbosity};
+
+/// Global switch and display options of the debug overlay, shared by all FSM types.
+#[derive(Resource, Debug, Clone)]
+pub struct FSMDebugOverlay {
+    /// Whether labels are shown.
+    pub enabled: bool,
+    /// Whether labels show the time spent in the current state.
+    pub show_time: bool,
+    /// Font size of the labels.
+    pub font_size: f32,
+}
+
+impl Default for FSMDebugOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            show_time: true,
+            font_size: 14.0,
+        }
+    }
+}
+
+/// Plugin labelling entities with their current state of the FSM `S`.
+pub struct FSMDebugOverlayPlugin<S> {
+    offset: Vec3,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMDebugOverlayPlugin<S> {
+    fn default() -> Self {
+        Self {
+            offset: Vec3::Y,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S> FSMDebugOverlayPlugin<S> {
+    /// World-space offset of the label from the entity's origin (default: one unit up).
+    ///
+    /// Give FSM types sharing entities different offsets so their labels don't overlap.
+    #[must_use]
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl<S: FSMState + core::hash::Hash + Reflect + Typed> Plugin for FSMDebugOverlayPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FSMDebugOverlay>()
+            .insert_resource(LabelOffset::<S> {
+                offset: self.offset,
+                _phantom: std::marker::PhantomData,
+            })
+            .add_systems(
+                PostUpdate,
+                (spawn_state_labels::<S>, update_state_labels::<S>)
+                    .chain()
+                    .after(TransformSystems::Propagate),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct LabelOffset<S> {
+    offset: Vec3,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+/// Text node showing the state of `target`.
+#[derive(Component, Debug)]
+pub struct FSMStateLabel<S: FSMState> {
+    /// The labelled FSM entity.
+    pub target: Entity,
+    state: S,
+    since: Duration,
+}
+
+/// Marks an FSM entity whose `S` state already has a label.
+#[derive(Component)]
+pub(crate) struct Labelled<S>(pub(crate) std::marker::PhantomData<S>);
+
+#[allow(clippy::type_complexity)]
+fn spawn_state_labels<S: FSMState + core::hash::Hash>(
+    mut commands: Commands,
+    overlay: Res<FSMDebugOverlay>,
+    time: Res<Time>,
+    identity: Option<Res<FSMIdentity<S>>>,
+    hierarchy: Option<Res<FSMObserverHierarchy>>,
+    targets: Query<(Entity, &S), (With<GlobalTransform>, Without<Labelled<S>>)>,
+) {
+    let identity = identity.as_deref();
+    if identity.is_some_and(|identity| identity.verbosity == FSMVerbosity::Off) {
+        return;
+    }
+    let name = FSMIdentity::<S>::name(identity, hierarchy.as_deref());
+    for (target, &state) in &targets {
+        commands
+            .entity(target)
+            .insert(Labelled::<S>(std::marker::PhantomData));
+        commands.spawn((
+            Name::new(format!("{name} label")),
+            FSMStateLabel {
+                target,
+                state,
+                since: time.elapsed(),
+            },
+            Text::default(),
+            TextFont {
+                font_size: overlay.font_size,
+                ..default()
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Visibility::Hidden,
+        ));
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn update_state_labels<S: FSMState + core::hash::Hash + Reflect + Typed>(
+    mut commands: Commands,
+    overlay: Res<FSMDebugOverlay>,
+    offset: Res<LabelOffset<S>>,
+    time: Res<Time>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    targets: Query<(&S, &GlobalTransform)>,
+    mut labels: Query<(
+        Entity,
+        &mut FSMStateLabel<S>,
+        &mut Text,
+        &mut Node,
+        &mut Visibility,
+    )>,
+) {
+    let camera = cameras.iter().find(|(camera, _)| camera.is_active);
+    for (label, mut state_label, mut text, mut node, mut visibility) in &mut labels {
+        let Ok((&state, transform)) = targets.get(state_label.target) else {
+            // The target lost its FSM or was despawned
+            commands.entity(label).despawn();
+            if let Ok(mut target) = commands.get_entity(state_label.target) {
+                target.try_remove::<Labelled<S>>();
+            }
+            continue;
+        };
+        if state != state_label.state {
+            state_label.state = state;
+            state_label.since = time.elapsed();
+        }
+
+        let position = camera.and_then(|(camera, camera_transform)| {
+            let world = transform.translation() + offset.offset;
+            camera.world_to_viewport(camera_transform, world).ok()
+        });
+        let Some(position) = position.filter(|_| overlay.enabled) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        node.left = Val::Px(position.x);
+        node.top = Val::Px(position.y);
+
+        let name = variant_name(&state).unwrap_or("?");
+        text.0 = if overlay.show_time {
+            let seconds = (time.elapsed() - state_label.since).as_secs_f32();
+            format!("{name} ({seconds:.1}s)")
+        } else {
+            name.to_string()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum EnemyFSM {
+        Idle,
+        Aggro,
+    }
+
+    impl FSMState for EnemyFSM {}
+
+    impl FSMTransition for EnemyFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    fn label(app: &mut App) -> Option<(EnemyFSM, Entity)> {
+        let world = app.world_mut();
+        let mut labels = world.query::<&FSMStateLabel<EnemyFSM>>();
+        labels
+            .iter(world)
+            .next()
+            .map(|label| (label.state, label.target))
+    }
+
+    #[test]
+    fn labels_follow_state_and_despawn_with_fsm() {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        app.add_plugins((
+            FSMPlugin::<EnemyFSM>::default(),
+            FSMDebugOverlayPlugin::<EnemyFSM>::default(),
+        ));
+        let enemy = app
+            .world_mut()
+            .spawn((
+                EnemyFSM::Idle,
+                Transform::default(),
+                GlobalTransform::default(),
+            ))
+            .id();
+        app.update();
+        app.update();
+        assert_eq!(label(&mut app), Some((EnemyFSM::Idle, enemy)));
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(enemy, EnemyFSM::Aggro));
+        app.update();
+        assert_eq!(label(&mut app), Some((EnemyFSM::Aggro, enemy)));
+
+        app.world_mut().entity_mut(enemy).remove::<EnemyFSM>();
+        app.update();
+        assert_eq!(label(&mut app), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! The transition graph declared by [`FSMTransition::can_transition`].
+//!
+//! [`FSMGraph`] is implemented for every reflected FSM type and enumerates the edges the
+//! rules allow, e.g. to find the shortest legal sequence of states between two states:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::graph::FSMGraph;
+//! # use bevy_fsm::{FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! #[derive(Component, EnumEvent, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum LifeFSM { Alive, Dying, Dead }
+//!
+//! impl FSMTransition for LifeFSM {
+//!     fn can_transition(from: Self, to: Self) -> bool {
+//!         matches!((from, to), (LifeFSM::Alive, LifeFSM::Dying) | (LifeFSM::Dying, LifeFSM::Dead))
+//!     }
+//! }
+//!
+//! assert_eq!(
+//!     LifeFSM::plan_path(LifeFSM::Alive, LifeFSM::Dead),
+//!     Some(vec![LifeFSM::Dying, LifeFSM::Dead])
+//! );
+//! ```
+//!
+//! States are the enum's unit variants, found via reflection. Like
+//! [`declared_transitions`](crate::coverage::declared_transitions), the graph ignores
+//! [`FSMTransition::can_transition_ctx`] and `FSMOverride`, which depend on the world.
+
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+use std::collections::VecDeque;
+
+use crate::reflect::state_from_name;
+use crate::{FSMState, FSMTransition};
+
+/// Iterators and path planning over the transition graph of an FSM type.
+pub trait FSMGraph: Sized {
+    /// All states, in declaration order.
+    fn states() -> Vec<Self>;
+
+    /// All `from -> to` pairs (`from != to`) the rules allow.
+    fn transitions() -> impl Iterator<Item = (Self, Self)>;
+
+    /// States reachable from `state` in one transition.
+    fn successors(state: Self) -> impl Iterator<Item = Self>;
+
+    /// Shortest sequence of states leading from `from` to `to`, excluding `from`.
+    ///
+    /// Each step is a transition the rules allow. Returns an empty path if `from == to`
+    /// and `None` if `to` is unreachable.
+    fn plan_path(from: Self, to: Self) -> Option<Vec<Self>>;
+}
+
+impl<S: FSMState + FromReflect + Typed> FSMGraph for S {
+    fn states() -> Vec<Self> {
+        let Ok(info) = S::type_info().as_enum() else {
+            return Vec::new();
+        };
+        info.variant_names()
+            .iter()
+            .filter_map(|name| state_from_name::<S>(name))
+            .collect()
+    }
+
+    fn transitions() -> impl Iterator<Item = (Self, Self)> {
+        let states = Self::states();
+        let edges: Vec<_> = states
+            .iter()
+            .flat_map(|&from| states.iter().map(move |&to| (from, to)))
+            .filter(|&(from, to)| from != to && <S as FSMTransition>::can_transition(from, to))
+            .collect();
+        edges.into_iter()
+    }
+
+    fn successors(state: Self) -> impl Iterator<Item = Self> {
+        Self::states()
+            .into_iter()
+            .filter(move |&to| to != state && <S as FSMTransition>::can_transition(state, to))
+    }
+
+    fn plan_path(from: Self, to: Self) -> Option<Vec<Self>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+        let states = Self::states();
+        let index = |state: S| states.iter().position(|&s| s == state);
+        let (start, goal) = (index(from)?, index(to)?);
+
+        // Breadth-first search, remembering from which state each state was first reached
+        let mut previous = vec![None; states.len()];
+        previous[start] = Some(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cur) = queue.pop_front() {
+            if cur == goal {
+                let mut path = Vec::new();
+                let mut step = goal;
+                while step != start {
+                    path.push(states[step]);
+                    step = previous[step]?;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (next, &state) in states.iter().enumerate() {
+                if previous[next].is_none()
+                    && <S as FSMTransition>::can_transition(states[cur], state)
+                {
+                    previous[next] = Some(cur);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Stunned,
+        Dying,
+        Dead,
+        Ghost,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            use LifeFSM::*;
+            matches!(
+                (from, to),
+                (Alive, Stunned) | (Stunned, Alive) | (Stunned, Dying) | (Dying, Dead)
+            )
+        }
+    }
+
+    #[test]
+    fn graph_enumerates_edges_and_plans_shortest_paths() {
+        use LifeFSM::*;
+
+        assert_eq!(LifeFSM::states(), vec![Alive, Stunned, Dying, Dead, Ghost]);
+        assert_eq!(LifeFSM::transitions().count(), 4);
+        assert_eq!(
+            LifeFSM::successors(Stunned).collect::<Vec<_>>(),
+            vec![Alive, Dying]
+        );
+        assert_eq!(
+            LifeFSM::plan_path(Alive, Dead),
+            Some(vec![Stunned, Dying, Dead])
+        );
+        assert_eq!(LifeFSM::plan_path(Dying, Dying), Some(vec![]));
+        assert_eq!(LifeFSM::plan_path(Dead, Alive), None);
+        assert_eq!(LifeFSM::plan_path(Alive, Ghost), None);
+    }
+}
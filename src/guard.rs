@@ -0,0 +1,150 @@
+//! Observers that only run while their target entity is in a given state.
+//!
+//! Observers of events that only matter in some states otherwise start with a manual check of
+//! the entity's state. [`when_in_state`] wraps an observer and skips it unless the event's
+//! target is currently in the given state:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::guard::{when_in_state, when_state};
+//! # use bevy_fsm::{FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum LifeFSM { Alive, Stunned, Dead }
+//! # #[derive(EntityEvent)]
+//! # struct Hit { entity: Entity }
+//! fn flinch(hit: On<Hit>, mut commands: Commands) {
+//!     // Only reached for living entities
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_observer(when_in_state(LifeFSM::Alive, flinch))
+//!     .add_observer(when_state(
+//!         |state: LifeFSM| state != LifeFSM::Dead,
+//!         |hit: On<Hit>| println!("{} was hit", hit.entity),
+//!     ));
+//! ```
+//!
+//! The target is the event's [`EntityEvent::event_target`]; entities without an `S` component
+//! skip the observer. The guard reads the committed state, so during a transition it sees the
+//! state being left until the new one is applied.
+
+use bevy::ecs::system::{
+    CombinatorSystem, Combine, IntoObserverSystem, ObserverSystem, RunSystemError, SystemIn,
+    SystemInput,
+};
+use bevy::prelude::*;
+use core::marker::PhantomData;
+
+use crate::FSMState;
+
+/// Wraps `observer` to run only while the event's target is in `state`.
+pub fn when_in_state<S, E, B, M>(
+    state: S,
+    observer: impl IntoObserverSystem<E, B, M>,
+) -> impl ObserverSystem<E, B>
+where
+    S: FSMState,
+    E: EntityEvent,
+    B: Bundle,
+{
+    when_state(move |current: S| current == state, observer)
+}
+
+/// Wraps `observer` to run only while the state of the event's target satisfies `condition`.
+pub fn when_state<S, E, B, M>(
+    condition: impl Fn(S) -> bool + Send + Sync + 'static,
+    observer: impl IntoObserverSystem<E, B, M>,
+) -> impl ObserverSystem<E, B>
+where
+    S: FSMState,
+    E: EntityEvent,
+    B: Bundle,
+{
+    let guard = IntoSystem::into_system(move |In(entity): In<Entity>, states: Query<&S>| {
+        states.get(entity).is_ok_and(|&state| condition(state))
+    });
+    let observer = IntoObserverSystem::into_system(observer);
+    let name = observer.name();
+    CombinatorSystem::<StateGuard<E, B>, _, _>::new(guard, observer, name)
+}
+
+/// [`Combine`] running the observer `B` only if the guard `A` accepts the event's target.
+#[doc(hidden)]
+pub struct StateGuard<E, B>(PhantomData<fn() -> (E, B)>);
+
+impl<E, Bd, A, B> Combine<A, B> for StateGuard<E, Bd>
+where
+    E: EntityEvent,
+    Bd: Bundle,
+    A: System<In = In<Entity>, Out = bool>,
+    B: ObserverSystem<E, Bd>,
+{
+    type In = On<'static, 'static, E, Bd>;
+    type Out = ();
+
+    fn combine<T>(
+        input: <Self::In as SystemInput>::Inner<'_>,
+        data: &mut T,
+        a: impl FnOnce(SystemIn<'_, A>, &mut T) -> Result<bool, RunSystemError>,
+        b: impl FnOnce(SystemIn<'_, B>, &mut T) -> Result<(), RunSystemError>,
+    ) -> Result<(), RunSystemError> {
+        if a(input.event_target(), data)? {
+            b(input, data)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FSMTransition;
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(EntityEvent)]
+    struct Hit {
+        entity: Entity,
+    }
+
+    #[derive(Resource, Default)]
+    struct Flinched(Vec<Entity>);
+
+    #[test]
+    fn guarded_observers_only_run_in_the_state() {
+        let mut world = World::new();
+        world.init_resource::<Flinched>();
+        world.add_observer(when_in_state(
+            LifeFSM::Alive,
+            |hit: On<Hit>, mut flinched: ResMut<Flinched>| flinched.0.push(hit.entity),
+        ));
+        let alive = world.spawn(LifeFSM::Alive).id();
+        let dead = world.spawn(LifeFSM::Dead).id();
+        let rock = world.spawn_empty().id();
+
+        for entity in [alive, dead, rock] {
+            world.trigger(Hit { entity });
+        }
+        world.entity_mut(alive).insert(LifeFSM::Dead);
+        world.entity_mut(dead).insert(LifeFSM::Alive);
+        for entity in [alive, dead, rock] {
+            world.trigger(Hit { entity });
+        }
+
+        assert_eq!(world.resource::<Flinched>().0, vec![alive, dead]);
+    }
+}
@@ -0,0 +1,336 @@
+//! The Bevy-free part of the validation core.
+//!
+//! Verdicts, deny reasons, override decisions and the edge and adjacency types are plain
+//! values: this module imports nothing from Bevy or from the plugin layer, so it can be
+//! reused by a server simulation or tested on its own. [`rules`](crate::rules) wraps it with
+//! the parts that need Bevy: the [`FSMTransition`](crate::FSMTransition) hooks taking a
+//! `&World`, [`GuardCtx`](crate::GuardCtx) and the [`FSMOverride`](crate::FSMOverride)
+//! component.
+//!
+//! ```
+//! use bevy_fsm::headless::{OverrideDecision, TransitionVerdict};
+//!
+//! // A whitelist without rules, and a transition it doesn't list
+//! let decision = OverrideDecision::of_list(true, false, false);
+//! assert_eq!(decision.verdict(|| true), TransitionVerdict::DeniedByOverride);
+//! ```
+
+use core::any::Any;
+use core::hash::Hash;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Result of a dry-run validation with [`validate`](crate::validate).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionVerdict {
+    /// The transition would be applied.
+    Allowed,
+    /// The entity is already in the requested state; a request would be a no-op.
+    Unchanged,
+    /// The current state requires a higher interrupt priority
+    /// (see [`FSMTransition::min_interrupt_priority`]).
+    ///
+    /// [`FSMTransition::min_interrupt_priority`]: crate::FSMTransition::min_interrupt_priority
+    DeniedByPriority,
+    /// The entity's `FSMOverride` denies the transition.
+    DeniedByOverride,
+    /// The `FSMTransition` rules deny the transition.
+    DeniedByRules,
+    /// The requested state already holds as many entities as its cap allows
+    /// (see [`FSMPlugin::with_state_cap`](crate::FSMPlugin::with_state_cap)).
+    DeniedByCap,
+    /// The entity does not exist or has no component of the FSM type.
+    NoFSM,
+    /// The FSM is frozen by [`FSMPaused`](crate::FSMPaused).
+    Paused,
+    /// The entity transitioned less than its [`FSMDebounce`](crate::FSMDebounce) window ago.
+    Debounced,
+    /// The entity already transitioned this frame (see
+    /// [`RequestChaining::OncePerFrame`](crate::RequestChaining::OncePerFrame)).
+    AlreadyTransitioned,
+}
+
+impl TransitionVerdict {
+    /// Returns true if a request would change the state.
+    pub fn is_allowed(self) -> bool {
+        self == Self::Allowed
+    }
+}
+
+/// Why the transition rules denied a transition: a message and/or a typed payload.
+///
+/// ```
+/// # use bevy_fsm::rules::DenyReason;
+/// struct MissingEnergy(u32);
+///
+/// let reason = DenyReason::new("Not enough energy (need 10)").with_payload(MissingEnergy(4));
+/// assert_eq!(reason.message(), Some("Not enough energy (need 10)"));
+/// assert_eq!(reason.payload::<MissingEnergy>().map(|missing| missing.0), Some(4));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DenyReason {
+    message: Option<Cow<'static, str>>,
+    payload: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl DenyReason {
+    /// Reason with a message.
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: Some(message.into()),
+            payload: None,
+        }
+    }
+
+    /// Attaches a typed payload, read back with [`Self::payload`].
+    #[must_use]
+    pub fn with_payload<T: Any + Send + Sync>(mut self, payload: T) -> Self {
+        self.payload = Some(Arc::new(payload));
+        self
+    }
+
+    /// The message, if any.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// The payload, if it is a `T`.
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.as_deref()?.downcast_ref()
+    }
+}
+
+impl core::fmt::Display for DenyReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.message().unwrap_or("denied by the transition rules"))
+    }
+}
+
+impl From<&'static str> for DenyReason {
+    fn from(message: &'static str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for DenyReason {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Result of a guard in a `#[transitions(...)]` table: `bool`, or `Result<(), DenyReason>`
+/// to tell why it denies.
+pub trait IntoTransitionResult {
+    /// The guard's result as a `Result`, with an empty reason for `false`.
+    fn into_transition_result(self) -> Result<(), DenyReason>;
+}
+
+impl IntoTransitionResult for bool {
+    fn into_transition_result(self) -> Result<(), DenyReason> {
+        if self {
+            Ok(())
+        } else {
+            Err(DenyReason::default())
+        }
+    }
+}
+
+impl IntoTransitionResult for Result<(), DenyReason> {
+    fn into_transition_result(self) -> Result<(), DenyReason> {
+        self
+    }
+}
+
+/// Bitset adjacency matrix of a transition table, built at compile time.
+///
+/// Bit `from * count + to` is set if `from -> to` is an edge, with states given by their
+/// [`FSMTransition::adjacency_index`](crate::FSMTransition::adjacency_index).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FSMAdjacency {
+    count: usize,
+    words: &'static [u64],
+}
+
+impl FSMAdjacency {
+    /// Matrix of `count` states stored in `words` (see [`Self::words`]).
+    ///
+    /// # Panics
+    /// If `words` has less than `count * count` bits.
+    pub const fn new(count: usize, words: &'static [u64]) -> Self {
+        assert!(
+            words.len() * 64 >= count * count,
+            "too few words for the matrix"
+        );
+        Self { count, words }
+    }
+
+    /// Bits of a matrix of `count` states with the `(from, to)` index pairs of `edges` set.
+    ///
+    /// `W` must be at least `(count * count).div_ceil(64)`.
+    pub const fn words<const W: usize>(count: usize, edges: &[(usize, usize)]) -> [u64; W] {
+        let mut words = [0; W];
+        let mut i = 0;
+        while i < edges.len() {
+            let (from, to) = edges[i];
+            assert!(from < count && to < count, "edge index out of range");
+            let bit = from * count + to;
+            words[bit / 64] |= 1 << (bit % 64);
+            i += 1;
+        }
+        words
+    }
+
+    /// Number of states.
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Whether `from -> to` is an edge. Out-of-range indices are not.
+    pub const fn contains(&self, from: usize, to: usize) -> bool {
+        if from >= self.count || to >= self.count {
+            return false;
+        }
+        let bit = from * self.count + to;
+        self.words[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    /// Indices of the states reachable from `from` in one transition.
+    pub fn targets(&self, from: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.count).filter(move |&to| self.contains(from, to))
+    }
+}
+
+/// Wildcard endpoint for [`FSMOverride`](crate::FSMOverride) entries, matching any state.
+///
+/// `(AnyState, LifeFSM::Dead)` lists every transition into `Dead`, `(LifeFSM::Stunned,
+/// AnyState)` every transition out of `Stunned`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnyState;
+
+/// One entry of an [`FSMOverride`](crate::FSMOverride) list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FSMEdge<S> {
+    /// Exactly `from -> to`.
+    Exact(S, S),
+    /// Every transition into the state, from any state.
+    Entering(S),
+    /// Every transition out of the state, to any state.
+    Exiting(S),
+}
+
+/// Conversion into an [`FSMEdge`], implemented for `(S, S)`, `(AnyState, S)`,
+/// `(S, AnyState)` and `FSMEdge<S>` itself.
+///
+/// A single array must use one of these forms; mix them by passing `FSMEdge` values or by
+/// chaining `and_allow`/`and_deny`.
+pub trait IntoFSMEdge<S> {
+    /// Converts `self` into an entry.
+    fn into_edge(self) -> FSMEdge<S>;
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> IntoFSMEdge<S> for (S, S) {
+    fn into_edge(self) -> FSMEdge<S> {
+        FSMEdge::Exact(self.0, self.1)
+    }
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> IntoFSMEdge<S> for (AnyState, S) {
+    fn into_edge(self) -> FSMEdge<S> {
+        FSMEdge::Entering(self.1)
+    }
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> IntoFSMEdge<S> for (S, AnyState) {
+    fn into_edge(self) -> FSMEdge<S> {
+        FSMEdge::Exiting(self.0)
+    }
+}
+
+impl<S> IntoFSMEdge<S> for FSMEdge<S> {
+    fn into_edge(self) -> FSMEdge<S> {
+        self
+    }
+}
+
+/// Outcome of evaluating an override for one transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrideDecision {
+    /// Accepted by config; `FSMTransition` rules are skipped.
+    Allow,
+    /// Denied by config.
+    Deny,
+    /// Not decided by config; `FSMTransition` rules apply.
+    Defer,
+}
+
+impl OverrideDecision {
+    /// How a whitelist (`allow_listed`) or blacklist decides a transition that is `listed` or
+    /// not: listed transitions are decided by the list, the others by the rules with
+    /// `call_rules` and the opposite of the list without.
+    ///
+    /// An empty blacklist decides like [`RuleType::All`](crate::RuleType::All).
+    pub fn of_list(allow_listed: bool, listed: bool, call_rules: bool) -> Self {
+        match (listed, call_rules) {
+            (true, _) if allow_listed => Self::Allow,
+            (true, _) => Self::Deny,
+            (false, true) => Self::Defer,
+            (false, false) if allow_listed => Self::Deny,
+            (false, false) => Self::Allow,
+        }
+    }
+
+    /// This decision, or the one of the next override layer if this one defers.
+    #[must_use]
+    pub fn or_else(self, next: impl FnOnce() -> Self) -> Self {
+        match self {
+            Self::Defer => next(),
+            decision => decision,
+        }
+    }
+
+    /// The verdict for this decision, consulting `rules` only if the overrides deferred.
+    pub fn verdict(self, rules: impl FnOnce() -> bool) -> TransitionVerdict {
+        match self {
+            Self::Allow => TransitionVerdict::Allowed,
+            Self::Deny => TransitionVerdict::DeniedByOverride,
+            Self::Defer if rules() => TransitionVerdict::Allowed,
+            Self::Defer => TransitionVerdict::DeniedByRules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacency_matrix_tests_edges() {
+        const WORDS: [u64; 3] = FSMAdjacency::words(12, &[(0, 1), (11, 11), (5, 0)]);
+        const MATRIX: FSMAdjacency = FSMAdjacency::new(12, &WORDS);
+
+        assert!(MATRIX.contains(0, 1));
+        assert!(!MATRIX.contains(1, 0));
+        // Bit 143, in the last word
+        assert!(MATRIX.contains(11, 11));
+        assert!(!MATRIX.contains(12, 0));
+        assert_eq!(MATRIX.targets(5).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn lists_decide_listed_transitions_and_defer_the_rest_with_rules() {
+        use OverrideDecision::*;
+
+        let decide = |allow_listed, call_rules| {
+            [true, false].map(|listed| OverrideDecision::of_list(allow_listed, listed, call_rules))
+        };
+        assert_eq!(decide(true, false), [Allow, Deny]);
+        assert_eq!(decide(true, true), [Allow, Defer]);
+        assert_eq!(decide(false, false), [Deny, Allow]);
+        assert_eq!(decide(false, true), [Deny, Defer]);
+        assert_eq!(
+            Defer.or_else(|| Deny).verdict(|| true),
+            TransitionVerdict::DeniedByOverride
+        );
+        assert_eq!(Defer.verdict(|| false), TransitionVerdict::DeniedByRules);
+    }
+}
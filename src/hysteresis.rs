@@ -0,0 +1,253 @@
+//! Delaying transitions until they have been requested continuously for a while.
+//!
+//! AI that picks its state from a distance threshold flickers between two states when the
+//! target hovers around the threshold. [`FSMHysteresis`] makes selected edges wait until they
+//! have been requested in consecutive frames, or for a continuous duration, before applying:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMHysteresis, FSMState, FSMTransition, StateChangeRequest};
+//! # use bevy_enum_event::EnumEvent;
+//! # use core::time::Duration;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum GuardFSM { Patrol, Chase }
+//! fn spawn_guard(mut commands: Commands) {
+//!     commands.spawn((
+//!         GuardFSM::Patrol,
+//!         FSMHysteresis::new()
+//!             // Start chasing once the player has been close for 5 frames...
+//!             .with_frames(GuardFSM::Patrol, GuardFSM::Chase, 5)
+//!             // ...and give up after losing them for a whole second
+//!             .with_duration(GuardFSM::Chase, GuardFSM::Patrol, Duration::from_secs(1)),
+//!     ));
+//! }
+//!
+//! fn sense(mut commands: Commands, guards: Query<(Entity, &Transform)>) {
+//!     for (guard, transform) in &guards {
+//!         # let close = transform.translation.length() < 10.0;
+//!         let next = if close { GuardFSM::Chase } else { GuardFSM::Patrol };
+//!         // Request every frame; only a sustained request changes the state
+//!         commands.trigger(StateChangeRequest::new(guard, next));
+//!     }
+//! }
+//! ```
+//!
+//! A request counts only once it passes validation. Skipping a frame, or requesting another
+//! state, starts the wait over. Held requests fire no events. Durations are measured with the
+//! [`FSMClock`](crate::FSMClock). `try_set_fsm_state` and `force_state` apply immediately.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::hash::Hash;
+use core::time::Duration;
+
+use crate::clock::{self, FSMTimeScale};
+use crate::FSMState;
+
+/// How long a transition must be requested before it applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dwell {
+    /// In this many consecutive frames.
+    Frames(u32),
+    /// Continuously for this long.
+    Duration(Duration),
+}
+
+/// The transition currently being waited for.
+#[derive(Debug, Clone, Copy)]
+struct Candidate<S> {
+    from: S,
+    to: S,
+    frames: u32,
+    since: Duration,
+    requested: bool,
+}
+
+/// Component delaying selected transitions of the FSM `S` until they are requested
+/// continuously.
+#[derive(Component, Debug, Clone)]
+pub struct FSMHysteresis<S: Copy + Eq + Hash + Send + Sync + 'static> {
+    edges: HashMap<(S, S), Dwell>,
+    candidate: Option<Candidate<S>>,
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> Default for FSMHysteresis<S> {
+    fn default() -> Self {
+        Self {
+            edges: HashMap::default(),
+            candidate: None,
+        }
+    }
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> FSMHysteresis<S> {
+    /// Hysteresis without any delayed edge.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `from -> to` only once it was requested in `frames` consecutive frames.
+    #[must_use]
+    pub fn with_frames(self, from: S, to: S, frames: u32) -> Self {
+        self.with_dwell(from, to, Dwell::Frames(frames))
+    }
+
+    /// Apply `from -> to` only once it was requested continuously for `duration`.
+    #[must_use]
+    pub fn with_duration(self, from: S, to: S, duration: Duration) -> Self {
+        self.with_dwell(from, to, Dwell::Duration(duration))
+    }
+
+    /// Delay `from -> to` by `dwell`, replacing any previous delay of the edge.
+    #[must_use]
+    pub fn with_dwell(mut self, from: S, to: S, dwell: Dwell) -> Self {
+        self.edges.insert((from, to), dwell);
+        self
+    }
+
+    /// Delay of `from -> to`, if any.
+    pub fn dwell(&self, from: S, to: S) -> Option<Dwell> {
+        self.edges.get(&(from, to)).copied()
+    }
+
+    /// The transition being waited for, if any.
+    pub fn pending(&self) -> Option<(S, S)> {
+        self.candidate
+            .map(|candidate| (candidate.from, candidate.to))
+    }
+}
+
+/// Counts a validated request for `cur -> next`, returning whether it may be applied now.
+pub(crate) fn settle<S: FSMState + Hash>(
+    world: &mut World,
+    entity: Entity,
+    cur: S,
+    next: S,
+) -> bool {
+    let now = clock::elapsed::<S>(world).unwrap_or_default();
+    let scale = world.get::<FSMTimeScale>(entity).copied();
+    let Some(mut hysteresis) = world.get_mut::<FSMHysteresis<S>>(entity) else {
+        return true;
+    };
+    let Some(dwell) = hysteresis.dwell(cur, next) else {
+        hysteresis.candidate = None;
+        return true;
+    };
+
+    let candidate = match &mut hysteresis.candidate {
+        Some(candidate) if candidate.from == cur && candidate.to == next => {
+            if !candidate.requested {
+                candidate.frames += 1;
+                candidate.requested = true;
+            }
+            *candidate
+        }
+        slot => *slot.insert(Candidate {
+            from: cur,
+            to: next,
+            frames: 1,
+            since: now,
+            requested: true,
+        }),
+    };
+    let ready = match dwell {
+        Dwell::Frames(frames) => candidate.frames >= frames,
+        Dwell::Duration(duration) => {
+            let passed = now.saturating_sub(candidate.since);
+            scale.map_or(passed, |scale| scale.apply(passed)) >= duration
+        }
+    };
+    if ready {
+        hysteresis.candidate = None;
+    }
+    ready
+}
+
+/// System dropping candidates that were not requested during the last frame.
+pub fn reset_unrequested_transitions<S: FSMState + Hash>(
+    mut hysteresis: Query<&mut FSMHysteresis<S>>,
+) {
+    for mut hysteresis in &mut hysteresis {
+        match &mut hysteresis.candidate {
+            Some(candidate) if candidate.requested => candidate.requested = false,
+            Some(_) => hysteresis.candidate = None,
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum GuardFSM {
+        Patrol,
+        Chase,
+    }
+
+    impl FSMState for GuardFSM {}
+
+    impl FSMTransition for GuardFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    /// Requests `next` for `guard` during one frame.
+    fn frame(app: &mut App, guard: Entity, next: Option<GuardFSM>) -> GuardFSM {
+        if let Some(next) = next {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(guard, next));
+        }
+        app.update();
+        *app.world().get::<GuardFSM>(guard).unwrap()
+    }
+
+    #[test]
+    fn transitions_wait_for_consecutive_requests() {
+        use GuardFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<GuardFSM>::default());
+        let guard = app
+            .world_mut()
+            .spawn((Patrol, FSMHysteresis::new().with_frames(Patrol, Chase, 3)))
+            .id();
+
+        assert_eq!(frame(&mut app, guard, Some(Chase)), Patrol);
+        assert_eq!(frame(&mut app, guard, Some(Chase)), Patrol);
+        // A frame without the request starts over
+        assert_eq!(frame(&mut app, guard, None), Patrol);
+        assert_eq!(frame(&mut app, guard, Some(Chase)), Patrol);
+        assert_eq!(frame(&mut app, guard, Some(Chase)), Patrol);
+        assert_eq!(frame(&mut app, guard, Some(Chase)), Chase);
+        // Undelayed edges apply at once
+        assert_eq!(frame(&mut app, guard, Some(Patrol)), Patrol);
+    }
+
+    #[test]
+    fn transitions_wait_for_a_continuous_duration() {
+        use GuardFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<GuardFSM>::default());
+        app.init_resource::<Time>();
+        let guard = app
+            .world_mut()
+            .spawn((
+                Chase,
+                FSMHysteresis::new().with_duration(Chase, Patrol, Duration::from_secs(1)),
+            ))
+            .id();
+
+        for _ in 0..4 {
+            assert_eq!(frame(&mut app, guard, Some(Patrol)), Chase);
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(300));
+        }
+        assert_eq!(frame(&mut app, guard, Some(Patrol)), Patrol);
+    }
+}
@@ -0,0 +1,199 @@
+//! Requesting transitions from button presses.
+//!
+//! Prototypes often want "press P → Paused" without writing a system for it.
+//! [`FSMInputPlugin`] maps buttons to states and requests the mapped state on every
+//! [`FSMInputTarget`] entity when the button is pressed:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::input::{FSMInputPlugin, FSMInputTarget};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum GameFSM { Playing, Paused }
+//! # let mut app = App::new();
+//! app.add_plugins((
+//!     FSMPlugin::<GameFSM>::default(),
+//!     FSMInputPlugin::<GameFSM>::default()
+//!         .with_binding(KeyCode::KeyP, GameFSM::Paused)
+//!         .with_binding(KeyCode::Escape, GameFSM::Playing),
+//! ));
+//!
+//! fn spawn_game(mut commands: Commands) {
+//!     commands.spawn((GameFSM::Playing, FSMInputTarget));
+//! }
+//! ```
+//!
+//! Bindings are read from the `ButtonInput<I>` resource, `KeyCode` by default; use
+//! `FSMInputPlugin::<S, MouseButton>` for mouse buttons (with Bevy's `mouse` feature). Requests
+//! are sent in `Update`, before [`FSMSystems`], and validated as usual. The
+//! bindings live in the [`FSMInputMap`] resource and can be changed at runtime.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::hash::Hash;
+
+use crate::{FSMState, FSMSystems, StateChangeRequest};
+
+/// Marks the entities receiving the requests of [`FSMInputPlugin`], e.g. the player.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct FSMInputTarget;
+
+/// Resource mapping buttons of type `I` to the `S` state requested when they are pressed.
+#[derive(Resource, Debug, Clone)]
+pub struct FSMInputMap<S, I = KeyCode>
+where
+    S: Copy + Send + Sync + 'static,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    bindings: HashMap<I, S>,
+}
+
+impl<S, I> Default for FSMInputMap<S, I>
+where
+    S: Copy + Send + Sync + 'static,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::default(),
+        }
+    }
+}
+
+impl<S, I> FSMInputMap<S, I>
+where
+    S: Copy + Send + Sync + 'static,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    /// Request `state` when `input` is pressed, replacing any previous binding of `input`.
+    pub fn bind(&mut self, input: I, state: S) {
+        self.bindings.insert(input, state);
+    }
+
+    /// Remove the binding of `input`, returning its state.
+    pub fn unbind(&mut self, input: I) -> Option<S> {
+        self.bindings.remove(&input)
+    }
+
+    /// The state requested when `input` is pressed, if any.
+    pub fn binding(&self, input: I) -> Option<S> {
+        self.bindings.get(&input).copied()
+    }
+}
+
+/// Plugin requesting `S` states on [`FSMInputTarget`] entities from presses of `I` buttons.
+pub struct FSMInputPlugin<S, I = KeyCode>
+where
+    S: Copy + Send + Sync + 'static,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    map: FSMInputMap<S, I>,
+}
+
+impl<S, I> Default for FSMInputPlugin<S, I>
+where
+    S: Copy + Send + Sync + 'static,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            map: FSMInputMap::default(),
+        }
+    }
+}
+
+impl<S, I> FSMInputPlugin<S, I>
+where
+    S: Copy + Send + Sync + 'static,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    /// Request `state` when `input` is pressed.
+    #[must_use]
+    pub fn with_binding(mut self, input: I, state: S) -> Self {
+        self.map.bind(input, state);
+        self
+    }
+}
+
+impl<S, I> Plugin for FSMInputPlugin<S, I>
+where
+    S: FSMState,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.map.clone())
+            .add_systems(Update, request_from_input::<S, I>.before(FSMSystems));
+    }
+}
+
+/// System requesting the states bound to the buttons pressed this frame.
+pub fn request_from_input<S, I>(
+    mut commands: Commands,
+    input: Option<Res<ButtonInput<I>>>,
+    map: Res<FSMInputMap<S, I>>,
+    targets: Query<Entity, With<FSMInputTarget>>,
+) where
+    S: FSMState,
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    let Some(input) = input else {
+        return;
+    };
+    for next in input
+        .get_just_pressed()
+        .filter_map(|&button| map.binding(button))
+    {
+        for target in &targets {
+            commands.trigger(StateChangeRequest::new(target, next));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum GameFSM {
+        Playing,
+        Paused,
+    }
+
+    impl FSMState for GameFSM {}
+
+    impl FSMTransition for GameFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn pressed_buttons_request_their_state_on_targets() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<GameFSM>::default(),
+            FSMInputPlugin::<GameFSM>::default().with_binding(KeyCode::KeyP, GameFSM::Paused),
+        ));
+        app.init_resource::<ButtonInput<KeyCode>>();
+        let player = app
+            .world_mut()
+            .spawn((GameFSM::Playing, FSMInputTarget))
+            .id();
+        let npc = app.world_mut().spawn(GameFSM::Playing).id();
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyO);
+        app.update();
+        assert_eq!(app.world().get::<GameFSM>(player), Some(&GameFSM::Playing));
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyP);
+        app.update();
+        assert_eq!(app.world().get::<GameFSM>(player), Some(&GameFSM::Paused));
+        assert_eq!(app.world().get::<GameFSM>(npc), Some(&GameFSM::Playing));
+    }
+}
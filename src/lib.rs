@@ -17,8 +17,7 @@
 //!
 //! ```rust
 //! use bevy::prelude::*;
-//! use bevy_fsm::{FSMState, FSMTransition, FSMPlugin, StateChangeRequest, Enter, Exit, Transition, fsm_observer};
-//! use bevy_enum_event::EnumEvent;
+//! use bevy_fsm::prelude::*;
 //!
 //! fn plugin(app: &mut App) {
 //!     // FSMPlugin automatically sets up the observer hierarchy on first use
@@ -144,15 +143,106 @@
 
 use bevy::prelude::*;
 use bevy::{
-    ecs::event::EntityEvent,
-    platform::collections::{HashMap, HashSet},
-    reflect::GetTypeRegistration,
+    ecs::{
+        event::EntityEvent,
+        lifecycle::HookContext,
+        schedule::{InternedScheduleLabel, InternedSystemSet, ScheduleLabel},
+        system::IntoObserverSystem,
+        world::DeferredWorld,
+    },
+    platform::collections::HashMap,
+    reflect::{GetTypeRegistration, Typed},
 };
 // Re-export EnumEvent from bevy_enum_event and FSM derives from bevy_fsm_macros
 // Note: FSMState and FSMTransition are both traits (below) and derive macros (from bevy_fsm_macros)
 pub use bevy_enum_event::EnumEvent;
 pub use bevy_fsm_macros::{FSMState, FSMTransition};
 use std::any::TypeId;
+use std::borrow::Cow;
+
+pub mod actions;
+#[cfg(feature = "animation")]
+pub mod animation;
+#[cfg(feature = "audio")]
+pub mod audio;
+#[cfg(feature = "auto-register")]
+pub mod auto_register;
+pub mod batched;
+#[cfg(feature = "behavior")]
+pub mod behavior;
+pub mod buffered;
+pub mod cleanup;
+pub mod clock;
+pub mod commands;
+pub mod compose;
+pub mod composite;
+pub mod console;
+pub mod coverage;
+pub mod debounce;
+#[cfg(feature = "debug-overlay")]
+pub mod debug_overlay;
+pub mod graph;
+pub mod guard;
+pub mod headless;
+pub mod hysteresis;
+#[cfg(feature = "input")]
+pub mod input;
+pub mod ordering;
+pub mod parallel;
+pub mod path;
+pub mod plugins;
+pub mod population;
+pub mod prelude;
+pub mod propagation;
+pub mod query;
+pub mod query_validation;
+pub mod redirect;
+pub mod reflect;
+pub mod replay;
+#[cfg(feature = "replicon")]
+pub mod replication;
+mod required;
+pub mod retry;
+pub mod rules;
+pub mod save;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod slot;
+pub mod stack;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod ticket;
+#[cfg(feature = "trace")]
+mod trace;
+pub mod typestate;
+#[cfg(feature = "visibility")]
+pub mod visibility;
+pub mod world;
+
+#[cfg(feature = "auto-register")]
+pub use auto_register::{FSMAutoPlugin, FSMPluginCheck};
+pub use buffered::ConflictPolicy;
+pub use cleanup::{FSMCleanup, FSMRemoved};
+pub use clock::{FSMClock, FSMTimeScale};
+pub use commands::{FSMCommandsExt, FSMEntityCommandsExt};
+pub use debounce::{DebounceMode, FSMDebounce};
+pub use hysteresis::{Dwell, FSMHysteresis};
+pub use ordering::FSMObserverOrder;
+pub use plugins::FSMPlugins;
+pub use propagation::ChildPropagation;
+pub use query::{FSMHistory, FSMTimer, FSM};
+pub use redirect::{FSMRedirect, FSMTypeRedirect};
+use reflect::{FSMMigrations, ReflectFSMState};
+pub use retry::{FSMRetry, RetryPolicy};
+use rules::OverrideDecision;
+pub use rules::{
+    AnyState, DenyReason, FSMEdge, FSMOverride, FSMTransition, GuardCtx, IntoFSMEdge, RuleType,
+    TransitionVerdict,
+};
+pub use slot::FSMSlot;
+pub use stack::FSMOverrideStack;
+pub use ticket::{TicketId, TicketResolved, TicketStatus, TransitionTicket};
+pub use world::{FSMEntityExt, FSMError, FSMQueryError, FSMWorldExt};
 
 /// Macro for registering FSM observers sorting them into the per-FSM hierarchy.
 ///
@@ -161,6 +251,9 @@ use std::any::TypeId;
 ///
 /// Uses the same naming convention as `global_observer!` for consistency.
 ///
+/// Append `order = n` to run the observer in a declared order among the other ordered
+/// observers of its event (see [`ordering`]).
+///
 /// # Example
 /// ```no_run
 /// # use bevy::prelude::*;
@@ -169,11 +262,23 @@ use std::any::TypeId;
 /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// # enum LifeFSM { Alive, Dying }
 /// # fn on_dying_observer(_: On<Enter<life_fsm::Dying>>) {}
+/// # fn log_dying_observer(_: On<Enter<life_fsm::Dying>>) {}
 /// # let mut app = App::new();
 /// fsm_observer!(app, LifeFSM, on_dying_observer);
+/// fsm_observer!(app, LifeFSM, log_dying_observer, order = 100);
 /// ```
 #[macro_export]
 macro_rules! fsm_observer {
+    ($app:expr, $fsm_type:ty, $system:expr, order = $order:expr) => {{
+        let mut world = $app.world_mut();
+        let entity = $crate::ordering::add_ordered_observer::<$fsm_type, _, _>(
+            &mut world,
+            stringify!($system),
+            $order,
+            $system,
+        );
+        world.entity_mut(entity)
+    }};
     ($app:expr, $fsm_type:ty, $system:expr) => {{
         let mut world = $app.world_mut();
         let entity = {
@@ -187,6 +292,269 @@ macro_rules! fsm_observer {
     }};
 }
 
+/// [`App`] extension registering FSM observers without [`fsm_observer!`].
+pub trait FSMAppExt {
+    /// Adds `observer` under the hierarchy group of `S`, like [`fsm_observer!`].
+    ///
+    /// Accepts anything `App::add_observer` accepts, including closures and generic
+    /// functions. The observer is named after its type: the function name for plain
+    /// functions, `{{closure}}` for closures.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMAppExt, FSMState, FSMTransition, Enter};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dying }
+    /// fn on_enter_dying(_: On<Enter<life_fsm::Dying>>) {}
+    ///
+    /// # let mut app = App::new();
+    /// app.add_fsm_observer::<LifeFSM, _, _, _>(on_enter_dying)
+    ///     .add_fsm_observer::<LifeFSM, _, _, _>(|enter: On<Enter<LifeFSM>>| {
+    ///         println!("{:?} entered {:?}", enter.event().entity, enter.event().state);
+    ///     });
+    /// ```
+    fn add_fsm_observer<S: Send + Sync + 'static, E: Event, B: Bundle, M>(
+        &mut self,
+        observer: impl IntoObserverSystem<E, B, M>,
+    ) -> &mut Self;
+
+    /// Adds `observer` under the hierarchy group of `S`, run in `order` among the other
+    /// ordered observers of its event, lowest first (see [`ordering`]).
+    ///
+    /// Named like [`add_fsm_observer`](Self::add_fsm_observer).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMAppExt, FSMState, FSMTransition, Enter};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dying }
+    /// fn drop_loot(_: On<Enter<life_fsm::Dying>>) {}
+    /// fn log_death(_: On<Enter<life_fsm::Dying>>) {}
+    ///
+    /// # let mut app = App::new();
+    /// app.add_ordered_fsm_observer::<LifeFSM, _, _>(100, log_death)
+    ///     .add_ordered_fsm_observer::<LifeFSM, _, _>(0, drop_loot);
+    /// ```
+    fn add_ordered_fsm_observer<S: Send + Sync + 'static, E, M>(
+        &mut self,
+        order: i32,
+        observer: impl IntoObserverSystem<E, (), M>,
+    ) -> &mut Self
+    where
+        E: Event + Clone,
+        for<'a> E::Trigger<'a>: Default;
+
+    /// Requests `to` on an entity's `B` FSM whenever the entity enters `from` on its `A` FSM.
+    ///
+    /// The request is validated like any other and ignored for entities without `B`. Entities
+    /// spawned in `from` request `to` with their initial Enter. The observer is added under the
+    /// hierarchy group of `A`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMAppExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dead }
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum WeaponFSM { Drawn, Holstered }
+    /// # let mut app = App::new();
+    /// // Dead characters put their weapon away
+    /// app.fsm_link(LifeFSM::Dead, WeaponFSM::Holstered);
+    /// ```
+    fn fsm_link<A: FSMState, B: FSMState>(&mut self, from: A, to: B) -> &mut Self;
+
+    /// Requests the state returned by `map` on the target of every `E` event.
+    ///
+    /// Replaces small glue observers translating domain events into requests. The request
+    /// is validated like any other and ignored for targets without `S`. The observer is added
+    /// under the hierarchy group of `S`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMAppExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dying }
+    /// #[derive(EntityEvent)]
+    /// struct Damage {
+    ///     entity: Entity,
+    ///     amount: f32,
+    /// }
+    ///
+    /// # let mut app = App::new();
+    /// app.fsm_on_event::<Damage, LifeFSM>(|_| LifeFSM::Dying);
+    /// ```
+    fn fsm_on_event<E: EntityEvent, S: FSMState>(
+        &mut self,
+        map: impl Fn(&E) -> S + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Maps variant names of `S` that no longer exist to current states.
+    ///
+    /// Consulted by the name-based APIs ([`reflect`], [`save::apply`], the dev console) when a
+    /// name does not match a variant, so saves made before a variant was renamed or removed
+    /// still load. Several migrations can be added; the first one returning a state wins.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMAppExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Stunned, Dead }
+    /// # let mut app = App::new();
+    /// app.fsm_migration::<LifeFSM>(|old_name| match old_name {
+    ///     // Renamed in 1.2
+    ///     "Fainted" => Some(LifeFSM::Stunned),
+    ///     // Removed in 1.3
+    ///     "Dying" => Some(LifeFSM::Dead),
+    ///     _ => None,
+    /// });
+    /// ```
+    fn fsm_migration<S: FSMState>(
+        &mut self,
+        migration: impl Fn(&str) -> Option<S> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Makes the `host` state of `O` a superstate hosting the FSM `I`.
+    ///
+    /// Entering `host` inserts `I` in its `initial` state, leaving it removes `I`, so the
+    /// inner machine's `Exit` fires before the outer one completes. See the
+    /// [`compose`] module for querying the composed state. The observers are
+    /// added under the hierarchy group of `O`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMAppExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum PlayerFSM { Exploring, InCombat }
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum CombatFSM { Idle, Attacking, Blocking }
+    /// # let mut app = App::new();
+    /// app.fsm_superstate(PlayerFSM::InCombat, CombatFSM::Idle);
+    /// ```
+    fn fsm_superstate<O: FSMState, I: FSMState>(&mut self, host: O, initial: I) -> &mut Self;
+}
+
+impl FSMAppExt for App {
+    fn add_fsm_observer<S: Send + Sync + 'static, E: Event, B: Bundle, M>(
+        &mut self,
+        observer: impl IntoObserverSystem<E, B, M>,
+    ) -> &mut Self {
+        let name = observer_name(&observer);
+        let world = self.world_mut();
+        let entity = world
+            .add_observer(observer)
+            .insert((Name::new(name), FSMObserverMarker::<S>::default()))
+            .id();
+        attach_observer_to_group::<S>(world, entity);
+        self
+    }
+
+    fn add_ordered_fsm_observer<S: Send + Sync + 'static, E, M>(
+        &mut self,
+        order: i32,
+        observer: impl IntoObserverSystem<E, (), M>,
+    ) -> &mut Self
+    where
+        E: Event + Clone,
+        for<'a> E::Trigger<'a>: Default,
+    {
+        let name = observer_name(&observer);
+        ordering::add_ordered_observer::<S, E, M>(self.world_mut(), name, order, observer);
+        self
+    }
+
+    fn fsm_link<A: FSMState, B: FSMState>(&mut self, from: A, to: B) -> &mut Self {
+        let name = format!(
+            "fsm_link<{}, {}>",
+            ShortName::of::<A>(),
+            ShortName::of::<B>()
+        );
+        let world = self.world_mut();
+        let entity = world
+            .add_observer(move |enter: On<Enter<A>>, mut commands: Commands| {
+                if enter.state == from && !propagation::bubbled(&enter) {
+                    commands.trigger(StateChangeRequest::new(enter.entity, to));
+                }
+            })
+            .insert((Name::new(name), FSMObserverMarker::<A>::default()))
+            .id();
+        attach_observer_to_group::<A>(world, entity);
+        self
+    }
+
+    fn fsm_on_event<E: EntityEvent, S: FSMState>(
+        &mut self,
+        map: impl Fn(&E) -> S + Send + Sync + 'static,
+    ) -> &mut Self {
+        let name = format!("fsm_on_event<{}>", ShortName::of::<E>());
+        let world = self.world_mut();
+        let entity = world
+            .add_observer(move |event: On<E>, mut commands: Commands| {
+                let next = map(event.event());
+                commands.trigger(StateChangeRequest::new(event.event_target(), next));
+            })
+            .insert((Name::new(name), FSMObserverMarker::<S>::default()))
+            .id();
+        attach_observer_to_group::<S>(world, entity);
+        self
+    }
+
+    fn fsm_migration<S: FSMState>(
+        &mut self,
+        migration: impl Fn(&str) -> Option<S> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<FSMMigrations<S>>()
+            .add(migration);
+        self
+    }
+
+    fn fsm_superstate<O: FSMState, I: FSMState>(&mut self, host: O, initial: I) -> &mut Self {
+        let name = format!(
+            "fsm_superstate<{}, {}>",
+            ShortName::of::<O>(),
+            ShortName::of::<I>()
+        );
+        let world = self.world_mut();
+        let enter = world
+            .add_observer(move |enter: On<Enter<O>>, mut commands: Commands| {
+                if enter.state == host && !propagation::bubbled(&enter) {
+                    commands.entity(enter.entity).try_insert(initial);
+                }
+            })
+            .insert((Name::new(name.clone()), FSMObserverMarker::<O>::default()))
+            .id();
+        let exit = world
+            .add_observer(move |exit: On<Exit<O>>, mut commands: Commands| {
+                if exit.state == host && !propagation::bubbled(&exit) {
+                    commands.entity(exit.entity).try_remove::<I>();
+                }
+            })
+            .insert((Name::new(name), FSMObserverMarker::<O>::default()))
+            .id();
+        attach_observer_to_group::<O>(world, enter);
+        attach_observer_to_group::<O>(world, exit);
+        self
+    }
+}
+
+/// Name given to observers registered without [`fsm_observer!`]: the short type name of the
+/// system, i.e. the function name for plain functions.
+pub(crate) fn observer_name<T>(_: &T) -> String {
+    ShortName::of::<T>().to_string()
+}
+
 /// Marker component to tag observers belonging to a specific FSM type.
 ///
 /// This is used internally by the `fsm_observer!` macro but needs to be public
@@ -226,10 +594,117 @@ impl<S: Send + Sync + 'static> Default for FSMObserverGroup<S> {
 }
 
 /// Event requesting a state change for an entity.
-#[derive(Event, Debug, Clone, Copy)]
+///
+/// Usually triggered as an event. In buffered mode (see [`FSMPlugin::buffered`]) it can
+/// also be written as a message.
+///
+/// # Several requests in one frame
+///
+/// Requests are validated when they are applied, never when they are sent. Several requests
+/// for the same entity in one command flush (or one buffer) are applied in send order, each
+/// validated against the state the previous one left, so `A -> B`, `B -> C`, `C -> D` sent
+/// together end in `D`. Requests sent while the entity is mid-transition, e.g. from its `Exit`
+/// or `Enter` observers, are validated against the state being entered once that transition
+/// has completed. [`RequestChaining::OncePerFrame`] allows one transition per entity and
+/// frame instead.
+#[derive(Event, Message, Debug, Clone, Copy)]
 pub struct StateChangeRequest<S: Copy + Send + Sync + 'static> {
     pub entity: Entity,
     pub next: S,
+    /// Interrupt priority, compared against [`FSMTransition::min_interrupt_priority`] of
+    /// the current state. Defaults to 0.
+    pub priority: u32,
+    /// Entity that asked for the transition (e.g. the attacker killing this entity), passed
+    /// on to the resulting [`Transition`] or [`TransitionDenied`] event. Defaults to `None`.
+    pub source: Option<Entity>,
+    /// How long the request is retried once denied (see [`retry`]). Defaults to
+    /// [`RetryPolicy::Never`].
+    pub retry: RetryPolicy,
+    /// Ticket tracking the outcome of the request, set by
+    /// [`FSMEntityCommandsExt::request_fsm_tracked`] (see [`ticket`]). Defaults to `None`.
+    pub ticket: Option<TicketId>,
+}
+
+impl<S: Copy + Send + Sync + 'static> StateChangeRequest<S> {
+    /// Request a change to `next` with the default priority (0).
+    pub fn new(entity: Entity, next: S) -> Self {
+        Self {
+            entity,
+            next,
+            priority: 0,
+            source: None,
+            retry: RetryPolicy::Never,
+            ticket: None,
+        }
+    }
+
+    /// Request a change to `next` for an entity set later, with
+    /// [`FSMEntityCommandsExt::request_fsm`] or [`with_entity`](Self::with_entity). Also
+    /// available as `StateChangeRequest::from(next)`.
+    ///
+    /// Until then it targets `Entity::PLACEHOLDER`; triggering it as is does nothing, and
+    /// panics in debug builds.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMEntityCommandsExt, FSMState, FSMTransition, StateChangeRequest};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dead }
+    /// fn kill(mut commands: Commands, victim: Entity, attacker: Entity) {
+    ///     commands.entity(victim).request_fsm(
+    ///         StateChangeRequest::to(LifeFSM::Dead)
+    ///             .with_priority(10)
+    ///             .with_source(attacker),
+    ///     );
+    ///     // Or, with the defaults
+    ///     commands.entity(attacker).request_fsm_state(LifeFSM::Alive);
+    /// }
+    /// ```
+    pub fn to(next: S) -> Self {
+        Self::new(Entity::PLACEHOLDER, next)
+    }
+
+    /// Target `entity` with this request.
+    #[must_use]
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = entity;
+        self
+    }
+
+    /// Set the interrupt priority of this request.
+    ///
+    /// A state with a minimum interrupt priority (see
+    /// [`FSMTransition::min_interrupt_priority`]) can only be left by requests with at least
+    /// that priority.
+    #[must_use]
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Record `source` as the entity that asked for this transition, e.g. for damage
+    /// attribution or to audit client requests.
+    #[must_use]
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Keep retrying this request every frame for `duration` if it is denied, applying it as
+    /// soon as it passes (see [`retry`]).
+    #[must_use]
+    pub fn retry_for(mut self, duration: core::time::Duration) -> Self {
+        self.retry = RetryPolicy::For(duration);
+        self
+    }
+}
+
+impl<S: Copy + Send + Sync + 'static> From<S> for StateChangeRequest<S> {
+    /// A request for `next` without a target entity, see [`StateChangeRequest::to`].
+    fn from(next: S) -> Self {
+        Self::to(next)
+    }
 }
 
 impl<S: Copy + Send + Sync + 'static> EntityEvent for StateChangeRequest<S> {
@@ -238,34 +713,93 @@ impl<S: Copy + Send + Sync + 'static> EntityEvent for StateChangeRequest<S> {
     }
 }
 
+/// When the initial `Enter` events of a newly added FSM component fire
+/// (see [`FSMPlugin::with_initial_enter`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum InitialEnter {
+    /// Right after the command or bundle inserting the component. Components inserted in the
+    /// same bundle are visible to the observers, components inserted by later commands are not.
+    #[default]
+    OnAdd,
+    /// In `PostUpdate` of the frame the component was added in, once everything queued for the
+    /// entity has been applied. Transitions applied before that skip the initial `Enter`.
+    Deferred,
+}
+
+/// Marks entities whose initial `Enter` events are deferred (see [`InitialEnter::Deferred`]).
+#[derive(Component)]
+struct InitialEnterPending<S: Send + Sync + 'static>(std::marker::PhantomData<S>);
+
+/// Inserted together with a restored state (see [`save::apply`]) so that no initial `Enter`
+/// fires for it. Removed again right after the insertion.
+#[doc(hidden)]
+#[derive(Component)]
+pub struct SkipInitialEnter<S: Send + Sync + 'static>(std::marker::PhantomData<S>);
+
+/// How several requests for the same entity within one frame are validated
+/// (see [`FSMPlugin::with_request_chaining`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RequestChaining {
+    /// Validate each request against the state left by the requests applied before it,
+    /// waiting for an in-flight transition to complete first.
+    #[default]
+    Chain,
+    /// Apply at most one transition per entity and frame; later requests are denied with
+    /// [`TransitionVerdict::AlreadyTransitioned`] until [`StateChanged`] is cleared.
+    OncePerFrame,
+}
+
+/// How much the `trace` and `debug-overlay` features report for an FSM type
+/// (see [`FSMPlugin::with_verbosity`]). Each level includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum FSMVerbosity {
+    /// Nothing: no log records and no overlay labels.
+    Off,
+    /// Denied requests and aborted transitions.
+    Denials,
+    /// Every request with its outcome, and the `fsm_transition` spans.
+    Decisions,
+    /// Also every fired `Exit`, `Transition` and `Enter` event.
+    #[default]
+    Events,
+}
+
 /// Event fired when an entity exits a state.
-#[derive(Event, Debug, Clone, Copy)]
+///
+/// Bubbles up the `ChildOf` hierarchy with [`FSMPlugin::with_event_bubbling`].
+#[derive(EntityEvent, Debug, Clone, Copy)]
+#[entity_event(propagate)]
 pub struct Exit<S: Copy + Send + Sync + 'static> {
     pub entity: Entity,
     pub state: S,
-}
-
-impl<S: Copy + Send + Sync + 'static> EntityEvent for Exit<S> {
-    fn event_target(&self) -> Entity {
-        self.entity
-    }
+    /// The state being entered next, or `None` if the FSM component was removed or the
+    /// entity despawned.
+    ///
+    /// Always `None` for the variant-specific events (`Exit<module::Variant>`), whose type
+    /// can't hold another variant; observe `Exit<S>` instead.
+    pub next: Option<S>,
 }
 
 /// Event fired when an entity enters a state.
-#[derive(Event, Debug, Clone, Copy)]
+///
+/// Bubbles up the `ChildOf` hierarchy with [`FSMPlugin::with_event_bubbling`].
+#[derive(EntityEvent, Debug, Clone, Copy)]
+#[entity_event(propagate)]
 pub struct Enter<S: Copy + Send + Sync + 'static> {
     pub entity: Entity,
     pub state: S,
-}
-
-impl<S: Copy + Send + Sync + 'static> EntityEvent for Enter<S> {
-    fn event_target(&self) -> Entity {
-        self.entity
-    }
+    /// The state that was left, or `None` when the FSM component was first added.
+    ///
+    /// Always `None` for the variant-specific events (`Enter<module::Variant>`), whose type
+    /// can't hold another variant; observe `Enter<S>` or read [`StateChanged`] instead.
+    pub previous: Option<S>,
 }
 
 /// Event fired for state transitions.
-#[derive(Event, Debug, Clone, Copy)]
+///
+/// Bubbles up the `ChildOf` hierarchy with [`FSMPlugin::with_event_bubbling`].
+#[derive(EntityEvent, Debug, Clone, Copy)]
+#[entity_event(propagate)]
 pub struct Transition<F, T>
 where
     F: Copy + Send + Sync + 'static,
@@ -274,68 +808,148 @@ where
     pub entity: Entity,
     pub from: F,
     pub to: T,
+    /// [`StateChangeRequest::source`] of the request that caused the transition.
+    pub source: Option<Entity>,
 }
 
-impl<F, T> EntityEvent for Transition<F, T>
-where
-    F: Copy + Send + Sync + 'static,
-    T: Copy + Send + Sync + 'static,
-{
-    fn event_target(&self) -> Entity {
-        self.entity
-    }
-}
-
-/// Trait for defining transition logic.
+/// Event fired once the entity is in its new state, for every applied transition and when the
+/// FSM component is first added (with `from: None`).
 ///
-/// Implement this trait on your FSM enum to define which transitions are valid.
+/// The one hook to observe for "any state change of this FSM", fired after the `Enter`
+/// events. Unlike [`Transition`] it also covers the initial state, and unlike the
+/// [`StateChanged`] component it is an event rather than a per-frame marker. In
+/// [`batched`] mode it is written as a message instead, the only notification of a
+/// transition.
 ///
-/// # Example
-/// ```rust
-/// use bevy_fsm::FSMTransition;
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{AnyTransition, FSMState, FSMTransition};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum DoorFSM { Open, Closed }
+/// # let mut app = App::new();
+/// app.add_observer(|change: On<AnyTransition<DoorFSM>>| {
+///     println!("{} {:?} -> {:?}", change.entity, change.from, change.to);
+/// });
+/// ```
+#[derive(EntityEvent, Message, Debug, Clone, Copy)]
+pub struct AnyTransition<S: Copy + Send + Sync + 'static> {
+    pub entity: Entity,
+    /// The state that was left, or `None` when the FSM component was first added.
+    pub from: Option<S>,
+    pub to: S,
+    /// [`StateChangeRequest::source`] of the request that caused the transition.
+    pub source: Option<Entity>,
+}
+
+/// Phase of a transition after which the entity disappeared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionPhase {
+    /// An `Exit` observer despawned the entity or removed its FSM component.
+    Exit,
+    /// A `Transition` observer despawned the entity or removed its FSM component.
+    Transition,
+}
+
+/// Event fired when an accepted transition cannot complete because one of its own observers
+/// despawned the entity or removed its FSM component.
 ///
-/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-/// enum LifeFSM { Alive, Dying, Dead }
+/// The remaining events are skipped and the new state is never inserted. The entity may no
+/// longer exist when this fires, so only global observers see it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct TransitionAborted<S: Copy + Send + Sync + 'static> {
+    pub entity: Entity,
+    pub from: S,
+    pub to: S,
+    pub phase: TransitionPhase,
+}
+
+/// Event fired when a [`StateChangeRequest`] is rejected.
 ///
-/// impl FSMTransition for LifeFSM {
-///     fn can_transition(from: Self, to: Self) -> bool {
-///         matches!((from, to),
-///             (LifeFSM::Alive, LifeFSM::Dying) |
-///             (LifeFSM::Dying, LifeFSM::Dead)) || from == to
-///     }
-/// }
-/// ```
-pub trait FSMTransition {
-    /// Returns true if transition from `from` to `to` is allowed.
-    fn can_transition(from: Self, to: Self) -> bool
-    where
-        Self: Sized;
+/// `reason` is the [`TransitionVerdict`] that denied it. Requests to the current state,
+/// for entities without an FSM, or queued by a buffered [`FSMPaused`] are not reported.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct TransitionDenied<S: Copy + Send + Sync + 'static> {
+    pub entity: Entity,
+    pub from: S,
+    pub to: S,
+    pub reason: TransitionVerdict,
+    /// [`StateChangeRequest::source`] of the denied request.
+    pub source: Option<Entity>,
+    /// Why the rules denied it, from [`FSMTransition::try_transition_ctx`]. Only set for
+    /// [`TransitionVerdict::DeniedByRules`].
+    pub details: Option<DenyReason>,
+}
 
-    /// Optional context-aware validation with world access.
-    ///
-    /// Defaults to calling `can_transition`.
-    fn can_transition_ctx(world: &World, entity: Entity, from: Self, to: Self) -> bool
-    where
-        Self: Sized,
-    {
-        let _ = (world, entity);
-        Self::can_transition(from, to)
+/// Error returned by the `FromStr` impl generated by `#[derive(FSMState)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FSMParseError {
+    /// Name of the FSM type.
+    pub fsm: &'static str,
+    /// The string that matched no variant.
+    pub name: String,
+}
+
+impl core::fmt::Display for FSMParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "FSM type '{}' has no variant '{}'", self.fsm, self.name)
     }
 }
 
+impl std::error::Error for FSMParseError {}
+
 /// Core FSM trait implemented automatically by `#[derive(FSMState)]`.
 ///
 /// This trait provides the infrastructure for variant-specific event generation
 /// and state transition management.
+///
+/// The derive also accepts generic enums. `EnumEvent` doesn't, so `#[fsm(module = ...)]`
+/// emits the variant types instead, carrying the enum's parameters. `Reflect` can't be derived
+/// for const-generic enums, so [`FSMPlugin`] is out and the core observers are added by hand:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{apply_state_request, on_fsm_added, Enter, FSMState, FSMTransition, StateChangeRequest};
+/// #[derive(Component, FSMTransition, FSMState, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// #[fsm(module = "slot_fsm")]
+/// enum SlotFSM<const N: usize> {
+///     Empty,
+///     Loaded,
+/// }
+///
+/// #[derive(Resource, Default)]
+/// struct Loaded(bool);
+///
+/// let mut world = World::new();
+/// world.init_resource::<Loaded>();
+/// world.add_observer(on_fsm_added::<SlotFSM<2>>);
+/// world.add_observer(apply_state_request::<SlotFSM<2>>);
+/// world.add_observer(|_: On<Enter<slot_fsm::Loaded<2>>>, mut loaded: ResMut<Loaded>| {
+///     loaded.0 = true;
+/// });
+///
+/// let slot = world.spawn(SlotFSM::<2>::Empty).id();
+/// world.trigger(StateChangeRequest::new(slot, SlotFSM::<2>::Loaded));
+/// world.flush();
+/// assert_eq!(world.get(slot), Some(&SlotFSM::<2>::Loaded));
+/// assert!(world.resource::<Loaded>().0);
+/// ```
 pub trait FSMState: Component + Copy + Eq + Send + Sync + 'static + FSMTransition {
     /// Validate transition (delegated to `FSMTransition` impl).
+    ///
+    /// Called instead of [`Self::can_transition_ctx`] by
+    /// [`FSMPlugin::with_query_validation`] for rules that don't read the world.
     fn can_transition(from: Self, to: Self) -> bool {
         <Self as FSMTransition>::can_transition(from, to)
     }
 
-    /// Context-aware validation with world access.
+    /// Context-aware validation with world access, called when no override decides the
+    /// transition.
+    ///
+    /// Defaults to [`FSMTransition::try_transition_guarded`] with the [`GuardCtx`] of the
+    /// entity.
     fn can_transition_ctx(world: &World, entity: Entity, from: Self, to: Self) -> bool {
-        <Self as FSMTransition>::can_transition_ctx(world, entity, from, to)
+        let ctx = GuardCtx::of::<Self>(world, entity);
+        <Self as FSMTransition>::try_transition_guarded(&ctx, from, to).is_ok()
     }
 
     /// Fire variant-specific enter event (generated by derive macro).
@@ -353,322 +967,243 @@ pub trait FSMState: Component + Copy + Eq + Send + Sync + 'static + FSMTransitio
         _entity: Entity,
         _from: Self,
         _to: Self,
+        _source: Option<Entity>,
     ) {
     }
+
+    /// Add the `on_enter`/`on_exit` actions declared on the variants (generated by derive
+    /// macro, see [`actions`]).
+    #[inline]
+    fn add_actions(_world: &mut World) {}
 }
 
-/// Configuration mode for FSM transition validation set in the [`FSMOverride`] component.
+/// Resource applying an [`FSMOverride`] to every entity of the FSM type `S` that has no
+/// `FSMOverride<S>` of its own.
 ///
-/// The mode determines priority behavior - config wins over `FSMTransition` rules:
-/// - **Whitelist**: Transitions ON the list are **immediately accepted** (override rules)
-/// - **Blacklist**: Transitions ON the list are **immediately denied** (override rules)
-/// - Transitions NOT decided by the config can still use `FSMTransition` rules (if `call_rules: true`)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
-pub enum RuleType {
-    /// No config restrictions - defer to `FSMTransition` rules.
-    ///
-    /// The `transitions` set is ignored. If `call_rules: true`, `FSMTransition` validation
-    /// applies. If `call_rules: false`, all transitions are allowed.
-    All,
-
-    /// Deny all transitions (immutable state).
-    ///
-    /// The `transitions` set is ignored. All transitions are hard-denied.
-    /// `FSMTransition` validation is never reached.
-    None,
-
-    /// Whitelist mode - listed transitions are prioritized and accepted.
-    ///
-    /// **Priority behavior:**
-    /// - If transition IS in set → **ACCEPT** (whitelist wins, `FSMTransition` NOT checked)
-    /// - If transition is NOT in set:
-    ///   - With `call_rules: true` → Check `FSMTransition` (can accept or deny)
-    ///   - With `call_rules: false` → **DENY** (default)
-    ///
-    /// Use this to explicitly allow specific transitions regardless of `FSMTransition` rules.
-    Whitelist,
+/// Use it for game-wide rule changes (difficulty modes, tutorials) without touching every
+/// entity. It dereferences to the wrapped override, so the runtime mutation methods apply:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{AnyState, FSMOverride, FSMState, FSMTransition, FSMTypeRules};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum EnemyFSM { Idle, Fleeing, Dead }
+/// fn easy_mode(mut commands: Commands) {
+///     // Enemies never flee on easy
+///     commands.insert_resource(FSMTypeRules::new(
+///         FSMOverride::blacklist([(AnyState, EnemyFSM::Fleeing)]).with_rules(),
+///     ));
+/// }
+///
+/// fn allow_fleeing(mut rules: ResMut<FSMTypeRules<EnemyFSM>>) {
+///     rules.clear();
+/// }
+/// ```
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct FSMTypeRules<S: Copy + Eq + core::hash::Hash + Send + Sync + 'static>(
+    pub FSMOverride<S>,
+);
 
-    /// Blacklist mode - listed transitions are prioritized and denied.
-    ///
-    /// **Priority behavior:**
-    /// - If transition IS in set → **DENY** (blacklist wins, `FSMTransition` NOT checked)
-    /// - If transition is NOT in set:
-    ///   - With `call_rules: true` → Check `FSMTransition` (can accept or deny)
-    ///   - With `call_rules: false` → **ACCEPT** (default)
-    ///
-    /// Use this to explicitly forbid specific transitions regardless of `FSMTransition` rules.
-    Blacklist,
+impl<S: Copy + Eq + core::hash::Hash + Send + Sync + 'static> FSMTypeRules<S> {
+    /// Type-wide rules from `config`.
+    #[must_use]
+    pub fn new(config: FSMOverride<S>) -> Self {
+        Self(config)
+    }
 }
 
-/// Component for optional per-entity state machine configuration.
-///
-/// Attach this component alongside your FSM enum to constrain transitions
-/// for that specific entity.
-///
-/// # Priority Model: Config Wins, Rules Fill Gaps
+/// Marker component that freezes the FSM `S` on an entity.
 ///
-/// `FSMOverride` has **priority** over `FSMTransition` rules:
+/// While present, every state change request for `S` is denied, regardless of
+/// `FSMOverride` or `FSMTransition` rules. Useful for cutscenes and game pause without
+/// rewriting overrides.
 ///
-/// ```text
-/// Transition Request
-///       ↓
-/// Is it ON whitelist?  → YES → ACCEPT (config wins)
-///       ↓ NO
-/// call_rules enabled?  → YES → Check FSMTransition
-///       ↓ NO
-///   DENY (not whitelisted)
-/// ```
+/// With [`FSMPaused::buffered`], denied requests are queued instead and re-issued in order
+/// when the component is removed. Inserting an `FSMPaused` again keeps the requests queued so
+/// far, and despawning the entity drops them. [`FSMTimer`], [`FSMDebounce`] windows and
+/// stepped paths ([`FSMPath`](path::FSMPath)) don't advance while paused.
 ///
-/// ```text
-/// Transition Request
-///       ↓
-/// Is it ON blacklist?  → YES → DENY (config wins)
-///       ↓ NO
-/// call_rules enabled?  → YES → Check FSMTransition
-///       ↓ NO
-///   ACCEPT (not blacklisted)
-/// ```
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{FSMPaused, FSMState, FSMTransition};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum LifeFSM { Alive, Dying }
+/// fn start_cutscene(mut commands: Commands, player: Entity) {
+///     commands.entity(player).insert(FSMPaused::<LifeFSM>::buffered());
+/// }
 ///
-/// # Validation Flow
-///
-/// 1. **`FSMOverride` (if present):**
-///    - **Whitelist mode:**
-///      - ON list: ACCEPT immediately
-///      - NOT on list: Check `call_rules` → if true check `FSMTransition`, else DENY
-///    - **Blacklist mode:**
-///      - ON list: DENY immediately
-///      - NOT on list: Check `call_rules` → if true check `FSMTransition`, else ACCEPT
-///    - **All mode:** Check `call_rules` → if true check `FSMTransition`, else ACCEPT all
-///    - **None mode:** DENY all (immutable)
-///
-/// 2. **No `FSMOverride`:**
-///    - Falls back to `FSMTransition::can_transition_ctx` only
-///
-/// # Examples
-///
-/// ```rust
-/// use bevy_fsm::{FSMOverride, RuleType};
-/// # #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-/// # enum MyState { A, B, C }
-///
-/// // Example 1: Whitelist A->C (overrides FSMTransition)
-/// let config = FSMOverride::whitelist([
-///     (MyState::A, MyState::C),  // Force allow
-/// ]);
-/// // A->C: ACCEPT (ON whitelist, config wins)
-/// // A->B: DENY (NOT on whitelist, call_rules=false so denied)
-/// // B->C: DENY (NOT on whitelist)
-///
-/// // Example 2: Whitelist + FSMTransition for unlisted transitions
-/// let config = FSMOverride::whitelist([
-///     (MyState::A, MyState::C),  // Force allow (overrides FSMTransition)
-/// ]).with_rules();
-/// // A->C: ACCEPT (ON whitelist, config wins, FSMTransition NOT checked)
-/// // A->B: Check FSMTransition (NOT on whitelist, but call_rules=true)
-/// // B->C: Check FSMTransition (NOT on whitelist, but call_rules=true)
-///
-/// // Example 3: Blacklist A->C (force deny)
-/// let config = FSMOverride::blacklist([
-///     (MyState::A, MyState::C),  // Force deny
-/// ]);
-/// // A->C: DENY (ON blacklist, config wins)
-/// // A->B: ACCEPT (NOT on blacklist, call_rules=false so allowed)
-/// // B->C: ACCEPT (NOT on blacklist)
-///
-/// // Example 4: Blacklist + FSMTransition for non-blacklisted
-/// let config = FSMOverride::blacklist([
-///     (MyState::A, MyState::C),  // Force deny
-/// ]).with_rules();
-/// // A->C: DENY (ON blacklist, config wins, FSMTransition NOT checked)
-/// // A->B: Check FSMTransition (NOT on blacklist, but call_rules=true)
-/// // B->C: Check FSMTransition (NOT on blacklist, but call_rules=true)
-///
-/// // Example 5: Allow all (bypass FSMTransition)
-/// let config = FSMOverride::<MyState>::allow_all();
-/// // All transitions: ACCEPT (no restrictions)
-///
-/// // Example 6: Allow all but enforce FSMTransition
-/// let config = FSMOverride::<MyState>::allow_all().with_rules();
-/// // All transitions: Check FSMTransition
-///
-/// // Example 7: Immutable state
-/// let config = FSMOverride::<MyState>::deny_all();
-/// // All transitions: DENY
+/// fn end_cutscene(mut commands: Commands, player: Entity) {
+///     // Requests received during the cutscene are applied now
+///     commands.entity(player).remove::<FSMPaused<LifeFSM>>();
+/// }
 /// ```
-///
-/// # Use Cases
-///
-/// - **Force allow specific transitions**: Use `whitelist([...])` to allow transitions
-///   that `FSMTransition` would normally forbid - whitelist wins
-/// - **Force deny specific transitions**: Use `blacklist([...])` to prevent transitions
-///   that `FSMTransition` would normally allow - blacklist wins
-/// - **Whitelist + fallback to rules**: Use `whitelist([...]).with_rules()` to allow
-///   specific transitions unconditionally while checking `FSMTransition` for others
-/// - **Blacklist + fallback to rules**: Use `blacklist([...]).with_rules()` to deny
-///   specific transitions unconditionally while checking `FSMTransition` for others
-/// - **Immutable states**: Use `deny_all()` for entities that should never change state
-#[derive(Component, Reflect, Debug)]
-#[reflect(Component)]
-pub struct FSMOverride<S: Copy + Eq + core::hash::Hash + Send + Sync + 'static> {
-    /// Transition filtering mode.
-    pub mode: RuleType,
-    /// Transitions set (interpretation depends on mode).
-    transitions: HashSet<(S, S)>,
-    /// Whether to check `FSMTransition` for transitions NOT decided by the config.
-    ///
-    /// - **Whitelist mode**: If `true`, transitions NOT on whitelist check `FSMTransition`.
-    ///   If `false`, they are denied.
-    /// - **Blacklist mode**: If `true`, transitions NOT on blacklist check `FSMTransition`.
-    ///   If `false`, they are allowed.
-    /// - **All mode**: If `true`, check `FSMTransition`. If `false`, allow everything.
-    /// - **None mode**: Ignored (all transitions denied).
-    ///
-    /// **Note**: Transitions ON whitelist/blacklist are decided by config and do NOT
-    /// check `FSMTransition` regardless of this flag (config has priority).
-    pub call_rules: bool,
+#[derive(Component, Debug, Clone)]
+#[component(on_replace = Self::hand_over)]
+pub struct FSMPaused<S: Copy + Send + Sync + 'static> {
+    buffer: bool,
+    queued: Vec<StateChangeRequest<S>>,
 }
 
-impl<S> Default for FSMOverride<S>
-where
-    S: Copy + Eq + core::hash::Hash + Send + Sync + 'static,
-{
+impl<S: Copy + Send + Sync + 'static> Default for FSMPaused<S> {
     fn default() -> Self {
         Self {
-            mode: RuleType::All,
-            transitions: HashSet::new(),
-            call_rules: false,
+            buffer: false,
+            queued: Vec::new(),
         }
     }
 }
 
-impl<S> FSMOverride<S>
-where
-    S: Copy + Eq + core::hash::Hash + Send + Sync + 'static,
-{
-    /// Allow all transitions (validate only via `FSMTransition` trait).
-    #[must_use]
-    pub fn allow_all() -> Self {
-        Self {
-            mode: RuleType::All,
-            transitions: HashSet::new(),
-            call_rules: false,
-        }
+impl<S: Copy + Send + Sync + 'static> FSMPaused<S> {
+    /// Pause the FSM, dropping all requests received while paused.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Deny all transitions (immutable state).
-    #[must_use]
-    pub fn deny_all() -> Self {
+    /// Pause the FSM, replaying requests received while paused once it is removed.
+    pub fn buffered() -> Self {
         Self {
-            mode: RuleType::None,
-            transitions: HashSet::new(),
-            call_rules: false,
+            buffer: true,
+            queued: Vec::new(),
         }
     }
 
-    /// Allow only whitelisted transitions.
-    pub fn whitelist<I>(edges: I) -> Self
-    where
-        I: IntoIterator<Item = (S, S)>,
-    {
-        Self {
-            mode: RuleType::Whitelist,
-            transitions: edges.into_iter().collect(),
-            call_rules: false,
-        }
+    /// Requests queued while paused (always empty unless buffered).
+    pub fn queued(&self) -> &[StateChangeRequest<S>] {
+        &self.queued
     }
 
-    /// Allow all except blacklisted transitions.
-    pub fn blacklist<I>(edges: I) -> Self
-    where
-        I: IntoIterator<Item = (S, S)>,
-    {
-        Self {
-            mode: RuleType::Blacklist,
-            transitions: edges.into_iter().collect(),
-            call_rules: false,
+    /// Queues `request` if buffered, returning whether it did.
+    fn queue(&mut self, request: StateChangeRequest<S>) -> bool {
+        if self.buffer {
+            self.queued.push(request);
         }
+        self.buffer
     }
 
-    /// Enable `FSMTransition` validation for transitions NOT decided by config.
-    ///
-    /// By default, `FSMOverride` is the sole authority - whitelisted/blacklisted transitions
-    /// are decided by config alone, others have default behavior. Calling `with_rules()`
-    /// applies `FSMTransition` validation to the "gap" transitions not explicitly listed.
-    ///
-    /// **Behavior by mode:**
-    /// - **Whitelist**: Listed transitions still ACCEPT (config priority). Unlisted
-    ///   transitions now check `FSMTransition` instead of auto-denying.
-    /// - **Blacklist**: Listed transitions still DENY (config priority). Unlisted
-    ///   transitions now check `FSMTransition` instead of auto-allowing.
-    /// - **All**: No whitelist/blacklist, so all transitions check `FSMTransition`.
-    /// - **None**: No effect (all transitions denied).
-    ///
-    /// # Examples
-    /// ```rust
-    /// # use bevy_fsm::FSMOverride;
-    /// # #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-    /// # enum MyState { A, B, C }
-    ///
-    /// // Whitelist without with_rules: whitelist is sole authority
-    /// let config = FSMOverride::whitelist([(MyState::A, MyState::C)]);
-    /// // A->C: ACCEPT (whitelisted)
-    /// // A->B: DENY (not whitelisted, no rules check)
-    ///
-    /// // Whitelist with with_rules: whitelist wins, rules fill gaps
-    /// let config = FSMOverride::whitelist([(MyState::A, MyState::C)]).with_rules();
-    /// // A->C: ACCEPT (whitelisted, config priority)
-    /// // A->B: Check FSMTransition (not whitelisted, rules enabled)
-    ///
-    /// // Blacklist without with_rules: blacklist is sole authority
-    /// let config = FSMOverride::blacklist([(MyState::A, MyState::C)]);
-    /// // A->C: DENY (blacklisted)
-    /// // A->B: ACCEPT (not blacklisted, no rules check)
-    ///
-    /// // Blacklist with with_rules: blacklist wins, rules fill gaps
-    /// let config = FSMOverride::blacklist([(MyState::A, MyState::C)]).with_rules();
-    /// // A->C: DENY (blacklisted, config priority)
-    /// // A->B: Check FSMTransition (not blacklisted, rules enabled)
-    /// ```
-    #[must_use]
-    pub fn with_rules(mut self) -> Self {
-        self.call_rules = true;
-        self
-    }
-
-    /// Add transitions to the set.
-    ///
-    /// For whitelist mode: adds allowed transitions.
-    /// For blacklist mode: adds denied transitions.
-    /// For All/None modes: has no effect.
-    #[must_use]
-    pub fn and_allow<I>(mut self, edges: I) -> Self
-    where
-        I: IntoIterator<Item = (S, S)>,
-    {
-        self.transitions.extend(edges);
-        self
+    /// Hands the queued requests to the `FSMPaused` replacing this one, or re-issues them
+    /// once the component is gone.
+    fn hand_over(mut world: DeferredWorld, ctx: HookContext) {
+        let Some(mut paused) = world.get_mut::<Self>(ctx.entity) else {
+            return;
+        };
+        let queued = core::mem::take(&mut paused.queued);
+        if queued.is_empty() {
+            return;
+        }
+        // The hook runs before a replacement is written, so check once it is applied
+        world.commands().queue(move |world: &mut World| {
+            // Despawned: the cleanup cancels the tickets of the queued requests
+            let Ok(mut entity) = world.get_entity_mut(ctx.entity) else {
+                return;
+            };
+            if let Some(mut paused) = entity.get_mut::<Self>() {
+                paused.queued.splice(0..0, queued);
+                return;
+            }
+            for request in queued {
+                world.trigger(request);
+            }
+        });
     }
+}
 
-    /// Add denied transitions (for blacklist mode).
-    ///
-    /// Alias for `and_allow()` when using blacklist mode for semantic clarity.
-    #[must_use]
-    pub fn and_deny<I>(mut self, edges: I) -> Self
-    where
-        I: IntoIterator<Item = (S, S)>,
-    {
-        self.transitions.extend(edges);
-        self
-    }
+/// The state an entity is moving into, present while its transition is being applied.
+///
+/// Inserted once a request is accepted and removed after the Enter events have fired, so it
+/// is visible to the transition's own Exit, Transition and Enter observers. During that window
+/// the `S` component still holds the old state until the insert phase; [`effective_state`]
+/// returns the state requests would be validated against.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{Exit, FSMState, FSMTransition, TransitioningTo};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum LifeFSM { Alive, Dying, Dead }
+/// fn on_exit_alive(trigger: On<Exit<LifeFSM>>, q: Query<&TransitioningTo<LifeFSM>>) {
+///     if let Ok(TransitioningTo(next)) = q.get(trigger.event().entity) {
+///         println!("leaving {:?} for {next:?}", trigger.event().state);
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[component(storage = "SparseSet")]
+pub struct TransitioningTo<S: Copy + Send + Sync + 'static>(pub S);
 
-    /// Check if a transition is allowed by this config.
-    pub fn is_transition_allowed(&self, from: S, to: S) -> bool {
-        match self.mode {
-            RuleType::All => true,
-            RuleType::None => false,
-            RuleType::Whitelist => self.transitions.contains(&(from, to)),
-            RuleType::Blacklist => !self.transitions.contains(&(from, to)),
-        }
+/// Requests held back while a transition is in progress, replayed once it completes.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+struct PendingTransition<S: Copy + Send + Sync + 'static> {
+    queued: Vec<QueuedChange<S>>,
+}
+
+/// A state change held back by [`PendingTransition`].
+enum QueuedChange<S: Copy + Send + Sync + 'static> {
+    /// Validated when replayed
+    Request(StateChangeRequest<S>),
+    /// From [`force_state`], applied without validation
+    Forced(S),
+}
+
+/// The state an entity is in or, mid-transition, the state it is moving into.
+///
+/// Returns `None` if the entity does not exist or has no `S` component.
+pub fn effective_state<S: FSMState>(world: &World, entity: Entity) -> Option<S> {
+    let entity_ref = world.get_entity(entity).ok()?;
+    let current = *entity_ref.get::<S>()?;
+    Some(
+        entity_ref
+            .get::<TransitioningTo<S>>()
+            .map_or(current, |t| t.0),
+    )
+}
+
+/// Marker inserted alongside the new state on every transition, for frame-based systems.
+///
+/// Transitions are applied through commands, so `Changed<S>` is reported to each system
+/// exactly once, on its first run after the transition: in the same frame for systems
+/// running after the command flush that applied it, in the next frame for systems that
+/// already ran. `StateChanged<S>` is aligned to frames instead: [`FSMPlugin`] removes it at
+/// the start of the next frame (in `First`, via [`clear_state_changed`]), so it is present
+/// for the rest of the frame the transition happened in. In buffered mode, order readers
+/// `.after(FSMSystems)` to see every transition.
+///
+/// If an entity transitions several times in one frame, `from` is the state left by the
+/// last transition.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{FSMState, FSMTransition, StateChanged};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum DoorFSM { Open, Closed }
+/// fn log_doors(q: Query<(&DoorFSM, &StateChanged<DoorFSM>)>) {
+///     for (state, changed) in &q {
+///         println!("door {:?} -> {:?}", changed.from, state);
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[component(storage = "SparseSet")]
+pub struct StateChanged<S: Copy + Send + Sync + 'static> {
+    /// The state the entity left.
+    pub from: S,
+}
+
+/// Removes the [`StateChanged`] markers of the previous frame.
+///
+/// Registered in `First` by [`FSMPlugin`].
+pub fn clear_state_changed<S: FSMState>(
+    mut commands: Commands,
+    q_changed: Query<Entity, With<StateChanged<S>>>,
+) {
+    for entity in &q_changed {
+        commands.entity(entity).remove::<StateChanged<S>>();
     }
 }
 
@@ -679,14 +1214,16 @@ where
 /// # Important Timing Considerations
 ///
 /// **WARNING**: Enter events fire **in the same frame** as entity spawn when the FSM
-/// component is added during `spawn()`. This means:
+/// component is added during `spawn()`, right after the spawning bundle is inserted. This means:
 ///
-/// - The entity is **not yet fully initialized** - other components may not exist yet
+/// - Components spawned in the same bundle (e.g. `(S, FSMOverride<S>)`) are visible
+/// - Components inserted by later commands (`spawn(S).insert(..)`) do **not exist yet**
 /// - Child entities may not be spawned
 ///
 /// **Best practices**:
-/// - Avoid accessing other components in initial Enter event handlers
-/// - For initialization logic, consider using a separate system that runs after spawn
+/// - Spawn the components initial Enter observers read in the same bundle as the FSM
+/// - Otherwise, defer the initial Enter with
+///   [`FSMPlugin::with_initial_enter(InitialEnter::Deferred)`](FSMPlugin::with_initial_enter)
 /// - Do not despawn entities in Enter events that may fire on spawn
 /// - Do not insert components in Enter events that may fire on spawn
 ///
@@ -710,7 +1247,7 @@ where
 ///     // SAFE: Queue command for later execution
 ///     commands.entity(trigger.entity).insert(HealthBar::default());
 ///
-///     // UNSAFE: Don't query other components - they may not exist yet!
+///     // UNSAFE: Only components of the spawning bundle exist yet!
 ///     // let health = query.get(trigger.entity).unwrap(); // May panic!
 /// }
 /// ```
@@ -726,16 +1263,149 @@ where
 /// app.world_mut().add_observer(on_fsm_added::<YourFSM>);
 /// ```
 #[allow(clippy::needless_pass_by_value)]
-pub fn on_fsm_added<S: FSMState>(trigger: On<Add, S>, mut commands: Commands, q_state: Query<&S>) {
-    let entity = trigger.entity;
+pub fn on_fsm_added<S: FSMState>(
+    trigger: On<Add, S>,
+    mut commands: Commands,
+    q_state: Query<&S, Without<SkipInitialEnter<S>>>,
+) {
+    if let Ok(&state) = q_state.get(trigger.entity) {
+        enter_initial_state(&mut commands, trigger.entity, state, true);
+    }
+}
+
+/// [`on_fsm_added`] for plugins created with [`FSMPlugin::minimal`].
+#[allow(clippy::needless_pass_by_value)]
+fn on_fsm_added_minimal<S: FSMState>(
+    trigger: On<Add, S>,
+    mut commands: Commands,
+    q_state: Query<&S, Without<SkipInitialEnter<S>>>,
+) {
+    if let Ok(&state) = q_state.get(trigger.entity) {
+        enter_initial_state(&mut commands, trigger.entity, state, false);
+    }
+}
 
-    let Ok(&state) = q_state.get(entity) else {
+/// [`on_fsm_added`] for [`InitialEnter::Deferred`]: marks the entity for
+/// [`fire_deferred_enters`].
+#[allow(clippy::needless_pass_by_value)]
+fn on_fsm_added_deferred<S: FSMState>(
+    trigger: On<Add, S>,
+    mut commands: Commands,
+    q_skip: Query<(), With<SkipInitialEnter<S>>>,
+) {
+    if q_skip.contains(trigger.entity) {
         return;
-    };
+    }
+    commands
+        .entity(trigger.entity)
+        .insert(InitialEnterPending::<S>(std::marker::PhantomData));
+}
+
+/// Fires the initial Enter events deferred by [`InitialEnter::Deferred`].
+///
+/// Registered in `PostUpdate` by [`FSMPlugin`].
+fn fire_deferred_enters<S: FSMState>(
+    mut commands: Commands,
+    q_pending: Query<(Entity, &S), With<InitialEnterPending<S>>>,
+    core: Res<FSMCoreObservers<S>>,
+) {
+    for (entity, &state) in &q_pending {
+        commands.entity(entity).remove::<InitialEnterPending<S>>();
+        enter_initial_state(&mut commands, entity, state, core.variant_events);
+    }
+}
 
+pub(crate) fn enter_initial_state<S: FSMState>(
+    commands: &mut Commands,
+    entity: Entity,
+    state: S,
+    variant_events: bool,
+) {
     // Fire enter events for initial state
-    commands.trigger(Enter::<S> { entity, state });
-    S::trigger_enter_variant(&mut commands, entity, state);
+    propagation::queue_event::<S, _>(
+        commands,
+        Enter::<S> {
+            entity,
+            state,
+            previous: None,
+        },
+    );
+    if variant_events {
+        S::trigger_enter_variant(commands, entity, state);
+    }
+    commands.trigger(AnyTransition::<S> {
+        entity,
+        from: None,
+        to: state,
+        source: None,
+    });
+}
+
+/// Observer that triggers exit events when an FSM component is removed or its entity is
+/// despawned.
+///
+/// The counterpart of [`on_fsm_added`]: cleanup observers on `Exit` also run when an FSM goes
+/// away, with the last state the entity was in. If an observer removes the component in the
+/// middle of a transition whose Exit events already fired, they are not repeated.
+///
+/// **Note**: This is automatically registered when using `FSMPlugin`, unless
+/// [`FSMPlugin::ignore_fsm_removal`] is set. On despawn the entity is gone by the time the
+/// events are observed, so observers must not expect to access it.
+///
+/// For manual registration:
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{FSMState, FSMTransition, on_fsm_removed};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum YourFSM { StateA }
+/// # let mut app = App::new();
+/// app.world_mut().add_observer(on_fsm_removed::<YourFSM>);
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+pub fn on_fsm_removed<S: FSMState>(
+    trigger: On<Remove, S>,
+    mut commands: Commands,
+    q_state: Query<(&S, Option<&TransitioningTo<S>>)>,
+) {
+    exit_removed_state(&mut commands, trigger.entity, &q_state, true);
+}
+
+/// [`on_fsm_removed`] for plugins created with [`FSMPlugin::minimal`].
+#[allow(clippy::needless_pass_by_value)]
+fn on_fsm_removed_minimal<S: FSMState>(
+    trigger: On<Remove, S>,
+    mut commands: Commands,
+    q_state: Query<(&S, Option<&TransitioningTo<S>>)>,
+) {
+    exit_removed_state(&mut commands, trigger.entity, &q_state, false);
+}
+
+fn exit_removed_state<S: FSMState>(
+    commands: &mut Commands,
+    entity: Entity,
+    q_state: &Query<(&S, Option<&TransitioningTo<S>>)>,
+    variant_events: bool,
+) {
+    let Ok((&state, in_flight)) = q_state.get(entity) else {
+        return;
+    };
+    // Removed by an Exit/Transition observer: Exit already fired for this state
+    if in_flight.is_some_and(|target| target.0 != state) {
+        return;
+    }
+
+    propagation::queue_event::<S, _>(
+        commands,
+        Exit::<S> {
+            entity,
+            state,
+            next: None,
+        },
+    );
+    if variant_events {
+        S::trigger_exit_variant(commands, entity, state);
+    }
 }
 
 /// Observer that applies state change requests.
@@ -751,103 +1421,407 @@ pub fn on_fsm_added<S: FSMState>(trigger: On<Add, S>, mut commands: Commands, q_
 /// app.world_mut().add_observer(apply_state_request::<YourFSM>);
 /// ```
 ///
-/// Gracefully handles entities that may have been despawned or had their FSM
-/// component removed by using a query to check component existence.
+/// Validation runs when the request's command is applied, against the entity's state at
+/// that point. Requests for entities that were despawned or lost their FSM component are
+/// ignored. Requests issued while the entity is mid-transition (e.g. from an `Exit` or
+/// `Enter` observer) are applied after the current transition completes, so every Exit is
+/// matched by the preceding Enter.
 #[allow(clippy::needless_pass_by_value)]
 pub fn apply_state_request<S: FSMState + core::hash::Hash>(
     trigger: On<StateChangeRequest<S>>,
     mut commands: Commands,
-    world: &World,
-    q_state: Query<&S>,
 ) {
-    let entity = trigger.event().entity;
-
-    // Query fails gracefully if entity was despawned or component removed
-    let current = q_state.get(entity).ok().copied();
+    let request = *trigger.event();
+    // Validate when the command runs, against the state at that point
+    commands.queue(move |world: &mut World| {
+        apply_request(world, request);
+    });
+}
 
-    if let Some(cur) = current {
-        let next = trigger.event().next;
-        if cur == next {
-            return;
+/// Validates and applies a single request immediately, flushing the resulting commands.
+///
+/// Requests for an entity that is in the middle of a transition are held back and replayed,
+/// in order, once it completes. Returns `true` if the transition was applied.
+pub(crate) fn apply_request<S: FSMState + core::hash::Hash>(
+    world: &mut World,
+    request: StateChangeRequest<S>,
+) -> bool {
+    let StateChangeRequest {
+        entity,
+        next,
+        priority,
+        source,
+        ..
+    } = request;
+    if let Some(mut pending) = world.get_mut::<PendingTransition<S>>(entity) {
+        pending.queued.push(QueuedChange::Request(request));
+        #[cfg(feature = "trace")]
+        trace::queued(world, entity, next);
+        return false;
+    }
+    // Entity may have been despawned or lost its component
+    let Some(&cur) = world.get::<S>(entity) else {
+        assert_targeted(&request);
+        ticket::resolve(
+            world,
+            &request,
+            TicketStatus::Denied(TransitionVerdict::NoFSM),
+        );
+        return false;
+    };
+    let next = redirect::redirect(world, entity, next);
+    if cur == next {
+        ticket::resolve(
+            world,
+            &request,
+            TicketStatus::Denied(TransitionVerdict::Unchanged),
+        );
+        return false;
+    }
+    let verdict = decide_transition(world, entity, cur, next, priority);
+    #[cfg(feature = "trace")]
+    trace::decision(world, entity, cur, next, verdict);
+    match verdict {
+        TransitionVerdict::Allowed => {
+            if !hysteresis::settle(world, entity, cur, next) {
+                ticket::resolve(world, &request, TicketStatus::Superseded);
+                return false;
+            }
         }
-
-        // Validation flow with priority model:
-        // FSMOverride (if present) has priority - it can force accept or force deny
-        // FSMTransition rules only apply to transitions NOT decided by FSMOverride
-        if let Some(cfg) = world.get::<FSMOverride<S>>(entity) {
-            let in_set = cfg.transitions.contains(&(cur, next));
-
-            match cfg.mode {
-                RuleType::All => {
-                    // All mode: no config restrictions, optionally check rules
-                    if cfg.call_rules
-                        && !<S as FSMState>::can_transition_ctx(world, entity, cur, next)
-                    {
-                        return;
-                    }
-                }
-                RuleType::None => {
-                    // None mode: deny everything
-                    return;
-                }
-                RuleType::Whitelist => {
-                    if in_set {
-                        // ON whitelist: ACCEPT immediately (whitelist wins)
-                        // Don't check FSMTransition - whitelist has priority
-                    } else {
-                        // NOT on whitelist: check rules if enabled, otherwise deny
-                        if cfg.call_rules {
-                            if !<S as FSMState>::can_transition_ctx(world, entity, cur, next) {
-                                return;
-                            }
-                        } else {
-                            // Not on whitelist and no rules checking: deny
-                            return;
-                        }
-                    }
-                }
-                RuleType::Blacklist => {
-                    if in_set {
-                        // ON blacklist: DENY immediately (blacklist wins)
-                        return;
-                    }
-                    // NOT on blacklist: check rules if enabled
-                    if cfg.call_rules
-                        && !<S as FSMState>::can_transition_ctx(world, entity, cur, next)
-                    {
-                        return;
-                    }
-                }
+        TransitionVerdict::Paused => {
+            let queued = world
+                .get_mut::<FSMPaused<S>>(entity)
+                .is_some_and(|mut paused| paused.queue(request));
+            if !queued {
+                ticket::resolve(world, &request, TicketStatus::Denied(verdict));
             }
-        } else {
-            // No FSMOverride - fall back to type-level FSMTransition validation
-            if !<S as FSMState>::can_transition_ctx(world, entity, cur, next) {
-                return;
+            return false;
+        }
+        TransitionVerdict::Debounced if debounce::hold(world, request) => {
+            return false;
+        }
+        reason if retry::park(world, request, reason) => {
+            return false;
+        }
+        reason => {
+            let details = rules_details(world, entity, cur, next, reason);
+            world.trigger(TransitionDenied {
+                entity,
+                from: cur,
+                to: next,
+                reason,
+                source,
+                details,
+            });
+            ticket::resolve(world, &request, TicketStatus::Denied(reason));
+            world.flush();
+            return false;
+        }
+    }
+    fire_transition(world, entity, cur, next, source);
+    ticket::resolve(world, &request, TicketStatus::Applied);
+    true
+}
+
+/// Catches, in debug builds, a request built with [`StateChangeRequest::to`] that never got
+/// an entity.
+pub(crate) fn assert_targeted<S: Copy + Send + Sync + 'static>(request: &StateChangeRequest<S>) {
+    debug_assert!(
+        request.entity != Entity::PLACEHOLDER,
+        "StateChangeRequest<{}> was triggered without a target entity; send it with \
+         `request_fsm` or set one with `with_entity`",
+        ShortName::of::<S>()
+    );
+}
+
+/// The [`DenyReason`] of the rules, for a [`TransitionVerdict::DeniedByRules`] verdict.
+///
+/// Validation only keeps the verdict, so the rules are asked again when reporting the denial.
+pub(crate) fn rules_details<S: FSMState>(
+    world: &World,
+    entity: Entity,
+    cur: S,
+    next: S,
+    verdict: TransitionVerdict,
+) -> Option<DenyReason> {
+    if verdict != TransitionVerdict::DeniedByRules {
+        return None;
+    }
+    let ctx = GuardCtx::of::<S>(world, entity);
+    <S as FSMTransition>::try_transition_guarded(&ctx, cur, next).err()
+}
+
+/// Runs the exact `FSMOverride` + `FSMTransition` decision tree without applying anything.
+///
+/// Useful for UIs that grey out actions leading to denied transitions. Checks a request with
+/// the default priority; use [`validate_with_priority`] for prioritized requests.
+///
+/// While the entity is mid-transition (e.g. when called from an `Exit` observer), the request
+/// is checked against the state being entered, as that is where it would be applied.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{validate, FSMState, FSMTransition};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum LifeFSM { Alive, Dying }
+/// fn can_kill(world: &World, entity: Entity) -> bool {
+///     validate(world, entity, LifeFSM::Dying).is_allowed()
+/// }
+/// ```
+pub fn validate<S: FSMState + core::hash::Hash>(
+    world: &World,
+    entity: Entity,
+    next: S,
+) -> TransitionVerdict {
+    validate_with_priority(world, entity, next, 0)
+}
+
+/// Like [`validate`], for a request carrying an interrupt `priority`.
+pub fn validate_with_priority<S: FSMState + core::hash::Hash>(
+    world: &World,
+    entity: Entity,
+    next: S,
+    priority: u32,
+) -> TransitionVerdict {
+    // Mid-transition, a request would be applied against the in-flight target
+    let Some(cur) = effective_state::<S>(world, entity) else {
+        return TransitionVerdict::NoFSM;
+    };
+    if cur == next {
+        return TransitionVerdict::Unchanged;
+    }
+    decide_transition(world, entity, cur, next, priority)
+}
+
+/// Runs the full validation flow (`FSMPaused`, interrupt priority, state caps,
+/// `FSMOverrideStack`, `FSMOverride` or `FSMTypeRules`, then `FSMTransition`) for one
+/// transition, keeping track of which layer denied it.
+fn decide_transition<S: FSMState + core::hash::Hash>(
+    world: &World,
+    entity: Entity,
+    cur: S,
+    next: S,
+    priority: u32,
+) -> TransitionVerdict {
+    // A paused FSM denies everything, before any other layer is consulted
+    if world.get::<FSMPaused<S>>(entity).is_some() {
+        return TransitionVerdict::Paused;
+    }
+
+    // Rate limiting applies to every request, whatever its priority
+    if debounce::is_debouncing::<S>(world, entity) {
+        return TransitionVerdict::Debounced;
+    }
+
+    // With one transition per frame, the StateChanged marker shows it has been used up
+    if world
+        .get_resource::<FSMCoreObservers<S>>()
+        .is_some_and(|core| core.chaining == RequestChaining::OncePerFrame)
+        && world.get::<StateChanged<S>>(entity).is_some()
+    {
+        return TransitionVerdict::AlreadyTransitioned;
+    }
+
+    // Interrupt priority gates leaving the current state before normal validation
+    if priority < <S as FSMTransition>::min_interrupt_priority(cur) {
+        return TransitionVerdict::DeniedByPriority;
+    }
+
+    // A full state can't be entered, whatever the overrides say
+    if population::is_full(world, next) {
+        return TransitionVerdict::DeniedByCap;
+    }
+
+    // Validation flow with priority model:
+    // FSMOverrideStack, then FSMOverride (if present) have priority - they can force accept
+    // or force deny. FSMTransition rules only apply to transitions NOT decided by them
+    let stacked = world
+        .get::<FSMOverrideStack<S>>(entity)
+        .map_or(OverrideDecision::Defer, |stack| stack.decision(cur, next));
+    let decision = stacked.or_else(|| match world.get::<FSMOverride<S>>(entity) {
+        Some(cfg) => cfg.decision(cur, next),
+        // Without a per-entity override, the type-wide rules stand in for it
+        None => world
+            .get_resource::<FSMTypeRules<S>>()
+            .map_or(OverrideDecision::Defer, |rules| rules.decision(cur, next)),
+    });
+    // No override decided - fall back to type-level FSMTransition validation
+    decision.verdict(|| <S as FSMState>::can_transition_ctx(world, entity, cur, next))
+}
+
+/// Runs the full Exit -> Transition -> insert -> Enter sequence for an accepted transition,
+/// flushing commands after each phase.
+///
+/// After each phase the entity is checked: if an observer despawned it or removed its `S`
+/// component, the remaining phases are skipped and [`TransitionAborted`] is triggered
+/// instead. [`TransitioningTo`] marks the entity for the duration; requests received
+/// meanwhile are held back and replayed at the end.
+fn fire_transition<S: FSMState + core::hash::Hash>(
+    world: &mut World,
+    entity: Entity,
+    cur: S,
+    next: S,
+    source: Option<Entity>,
+) {
+    debounce::record::<S>(world, entity);
+    world.entity_mut(entity).insert((
+        TransitioningTo(next),
+        PendingTransition::<S> { queued: Vec::new() },
+    ));
+    if propagation::propagates::<S>(world, ChildPropagation::BeforeParent) {
+        propagation::propagate(world, entity, next);
+    }
+    run_transition(world, entity, cur, next, source);
+    if propagation::propagates::<S>(world, ChildPropagation::AfterParent)
+        && world.get::<S>(entity) == Some(&next)
+    {
+        propagation::propagate(world, entity, next);
+    }
+
+    // Replay requests issued during the transition against the new state
+    let queued = world
+        .get_entity_mut(entity)
+        .ok()
+        .and_then(|mut e| e.take::<(TransitioningTo<S>, PendingTransition<S>)>())
+        .map(|(_, pending)| pending.queued)
+        .unwrap_or_default();
+    for change in queued {
+        match change {
+            QueuedChange::Request(request) => {
+                apply_request(world, request);
             }
+            QueuedChange::Forced(next) => force_state(world, entity, next),
         }
+    }
+}
 
-        // Fire exit
-        commands.trigger(Exit::<S> { entity, state: cur });
-        S::trigger_exit_variant(&mut commands, entity, cur);
+/// Inserts the new state of an accepted transition, marking it changed and resetting the
+/// [`FSMTimer`] and recording `cur` in the [`FSMHistory`] of the entity.
+pub(crate) fn insert_state<S: FSMState>(world: &mut World, entity: Entity, cur: S, next: S) {
+    world
+        .entity_mut(entity)
+        .insert((next, StateChanged::<S> { from: cur }))
+        .remove::<InitialEnterPending<S>>();
+    query::record(world, entity, cur);
+}
 
-        // Fire transition
-        commands.trigger(Transition::<S, S> {
+fn run_transition<S: FSMState>(
+    world: &mut World,
+    entity: Entity,
+    cur: S,
+    next: S,
+    source: Option<Entity>,
+) {
+    let alive = |world: &World| world.get_entity(entity).is_ok_and(|e| e.contains::<S>());
+    let abort = |world: &mut World, phase| {
+        #[cfg(feature = "trace")]
+        trace::aborted::<S>(world, entity, phase);
+        world.trigger(TransitionAborted::<S> {
             entity,
             from: cur,
             to: next,
+            phase,
         });
-        S::trigger_transition_variant(&mut commands, entity, cur, next);
+        world.flush();
+    };
+    if !alive(world) {
+        return;
+    }
+    #[cfg(feature = "trace")]
+    let _span = trace::span(world, entity, cur, next);
+    let variant_events = world
+        .get_resource::<FSMCoreObservers<S>>()
+        .is_none_or(|core| core.variant_events);
+
+    // Fire exit
+    #[cfg(feature = "trace")]
+    trace::event(world, entity, "Exit", cur);
+    propagation::trigger_event::<S, _>(
+        world,
+        Exit::<S> {
+            entity,
+            state: cur,
+            next: Some(next),
+        },
+    );
+    if variant_events {
+        S::trigger_exit_variant(&mut world.commands(), entity, cur);
+    }
+    world.flush();
+    if !alive(world) {
+        return abort(world, TransitionPhase::Exit);
+    }
+
+    // Fire transition
+    #[cfg(feature = "trace")]
+    trace::event(world, entity, "Transition", next);
+    propagation::trigger_event::<S, _>(
+        world,
+        Transition::<S, S> {
+            entity,
+            from: cur,
+            to: next,
+            source,
+        },
+    );
+    if variant_events {
+        S::trigger_transition_variant(&mut world.commands(), entity, cur, next, source);
+    }
+    world.flush();
+    if !alive(world) {
+        return abort(world, TransitionPhase::Transition);
+    }
 
-        // Apply new state
-        commands.entity(entity).insert(next);
+    insert_state(world, entity, cur, next);
 
-        // Fire enter
-        commands.trigger(Enter::<S> {
+    // Fire enter
+    #[cfg(feature = "trace")]
+    trace::event(world, entity, "Enter", next);
+    propagation::trigger_event::<S, _>(
+        world,
+        Enter::<S> {
             entity,
             state: next,
-        });
-        S::trigger_enter_variant(&mut commands, entity, next);
+            previous: Some(cur),
+        },
+    );
+    if variant_events {
+        S::trigger_enter_variant(&mut world.commands(), entity, next);
+    }
+    world.flush();
+    world.trigger(AnyTransition::<S> {
+        entity,
+        from: Some(cur),
+        to: next,
+        source,
+    });
+    world.flush();
+}
+
+/// Applies a transition without any validation, firing the regular events.
+///
+/// This bypasses `FSMOverride` and `FSMTransition` entirely and is intended for tooling
+/// (scripting, debugging) that must be able to put an entity into any state. Does nothing
+/// if the entity has no `S` component or is already in `next`. Pending commands are
+/// flushed before returning.
+///
+/// Called while the entity is mid-transition, e.g. from one of its `Enter` observers, the
+/// change is queued behind the requests already held back and applied, still without
+/// validation, once the transition completes.
+pub fn force_state<S: FSMState + core::hash::Hash>(world: &mut World, entity: Entity, next: S) {
+    if let Some(mut pending) = world.get_mut::<PendingTransition<S>>(entity) {
+        pending.queued.push(QueuedChange::Forced(next));
+        return;
+    }
+    let Some(&cur) = world.get::<S>(entity) else {
+        return;
+    };
+    if cur == next {
+        return;
     }
+    fire_transition(world, entity, cur, next, None);
 }
 
 /// Generic plugin for FSM types that automatically sets up core observers.
@@ -855,6 +1829,12 @@ pub fn apply_state_request<S: FSMState + core::hash::Hash>(
 /// This plugin automatically registers:
 /// - `apply_state_request` - Handles state transition requests
 /// - `on_fsm_added` - Fires Enter events when FSM component is first added
+/// - `on_fsm_removed` - Fires Exit events when FSM component is removed or despawned
+/// - `on_fsm_cleanup` - Cancels pending work of removed FSMs and fires [`FSMCleanup`] (see [`cleanup`])
+/// - `clear_state_changed` - Removes last frame's [`StateChanged`] markers (in `First`)
+/// - `fire_deferred_enters` - Fires initial Enter events with [`InitialEnter::Deferred`] (in `PostUpdate`)
+/// - `advance_fsm_paths` - Advances [`path::FSMPath`] components one step per frame (in `Update`)
+/// - `tick_fsm_timers` - Advances [`FSMTimer`] components (in `Update`)
 ///
 /// # Timing Warning
 ///
@@ -877,6 +1857,43 @@ pub fn apply_state_request<S: FSMState + core::hash::Hash>(
 pub struct FSMPlugin<S: FSMState + core::hash::Hash + Component> {
     /// If true, skip registering the `on_fsm_added` observer
     ignore_fsm_addition: bool,
+    /// If true, skip registering the `on_fsm_removed` observer
+    ignore_fsm_removal: bool,
+    /// If false, only the generic events are fired
+    variant_events: bool,
+    /// How state change requests are applied
+    processing: RequestProcessing,
+    /// Schedule running the processing system (buffered modes only)
+    schedule: Option<InternedScheduleLabel>,
+    /// Extra system set for the processing system (buffered modes only)
+    set: Option<InternedSystemSet>,
+    /// How competing requests for one entity are resolved (buffered modes only)
+    conflict_policy: ConflictPolicy,
+    /// Animations played when entering states
+    #[cfg(feature = "animation")]
+    animations: HashMap<S, animation::FSMAnimation>,
+    /// Marker components requiring `S`, registered on build
+    required: Vec<required::Registration>,
+    /// Maximum number of entities per state
+    caps: HashMap<S, usize>,
+    /// Whether and when transitions are propagated to children
+    child_propagation: Option<ChildPropagation>,
+    /// Whether Enter/Exit/Transition events bubble up to ancestors
+    event_bubbling: bool,
+    /// Whether batched requests are validated on the compute task pool
+    parallel_validation: bool,
+    /// Whether immediate requests are validated from a query where possible
+    query_validation: bool,
+    /// How several requests for one entity within a frame are validated
+    chaining: RequestChaining,
+    /// When the initial Enter events fire
+    initial_enter: InitialEnter,
+    /// Clock of the timing features, if not the `FSMClock` resource
+    clock: Option<FSMClock>,
+    /// Display name, if not the short type name
+    name: Option<Cow<'static, str>>,
+    /// How much the debug features report
+    verbosity: FSMVerbosity,
     _phantom: std::marker::PhantomData<S>,
 }
 
@@ -884,6 +1901,25 @@ impl<S: FSMState + core::hash::Hash + Component> Default for FSMPlugin<S> {
     fn default() -> Self {
         Self {
             ignore_fsm_addition: false,
+            ignore_fsm_removal: false,
+            variant_events: true,
+            processing: RequestProcessing::Immediate,
+            schedule: None,
+            set: None,
+            conflict_policy: ConflictPolicy::ApplyAll,
+            #[cfg(feature = "animation")]
+            animations: HashMap::default(),
+            required: Vec::new(),
+            caps: HashMap::default(),
+            child_propagation: None,
+            event_bubbling: false,
+            parallel_validation: false,
+            query_validation: false,
+            chaining: RequestChaining::Chain,
+            initial_enter: InitialEnter::OnAdd,
+            clock: None,
+            name: None,
+            verbosity: FSMVerbosity::Events,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -904,272 +1940,1867 @@ impl<S: FSMState + core::hash::Hash + Component> FSMPlugin<S> {
         self.ignore_fsm_addition = true;
         self
     }
-}
 
-impl<S: FSMState + core::hash::Hash + Component + Reflect + GetTypeRegistration> Plugin
-    for FSMPlugin<S>
-{
-    fn build(&self, app: &mut App) {
-        // Register the FSM type for reflection
-        app.register_type::<S>();
-        {
-            let world = app.world_mut();
-            let group_entity = ensure_fsm_group::<S>(world);
+    /// Make the marker component `M` require `S`, starting in `state`.
+    ///
+    /// Spawning `M` without an explicit `S` inserts `S` in `state` (and fires its initial Enter
+    /// events as usual). The state is kept per app, so other apps in the same process may pick
+    /// a different one for the same marker.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum MoveFSM { Idle, Run }
+    /// #[derive(Component)]
+    /// struct Player;
+    ///
+    /// # let mut app = App::new();
+    /// app.add_plugins(FSMPlugin::<MoveFSM>::default().require_on::<Player>(MoveFSM::Idle));
+    /// // `commands.spawn(Player)` now also inserts `MoveFSM::Idle`
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// On build, if `M` already requires `S` in this app.
+    #[must_use]
+    pub fn require_on<M: Component>(mut self, state: S) -> Self {
+        self.required
+            .push(Box::new(move |app| required::register::<M, S>(app, state)));
+        self
+    }
 
-            // Register core observers under the group entity
-            let apply_entity = {
-                let mut observer = world.add_observer(apply_state_request::<S>);
-                observer.insert(Name::new("apply_state_request"));
-                observer.insert(FSMObserverMarker::<S>::default());
-                observer.id()
-            };
-            world.entity_mut(group_entity).add_child(apply_entity);
-
-            if !self.ignore_fsm_addition {
-                let added_entity = {
-                    let mut observer = world.add_observer(on_fsm_added::<S>);
-                    observer.insert(Name::new("on_fsm_added"));
-                    observer.insert(FSMObserverMarker::<S>::default());
-                    observer.id()
-                };
-                world.entity_mut(group_entity).add_child(added_entity);
-            }
+    /// Skip registering the `on_fsm_removed` observer.
+    ///
+    /// Use this if you don't want Exit events when the FSM component is removed or its
+    /// entity is despawned.
+    #[must_use]
+    pub fn ignore_fsm_removal(mut self) -> Self {
+        self.ignore_fsm_removal = true;
+        self
+    }
+
+    /// Create an `FSMPlugin` that only fires the generic `Enter<S>`, `Exit<S>` and
+    /// `Transition<S, S>` events.
+    ///
+    /// Skips the variant-specific `Enter<module::Variant>`, `Exit<module::Variant>` and
+    /// `Transition<module::From, module::To>` triggers, saving their dispatch on every
+    /// transition. Combine with `#[fsm(no_variant_events)]` on the enum to not generate them
+    /// at all.
+    #[must_use]
+    pub fn minimal() -> Self {
+        Self {
+            variant_events: false,
+            ..Self::default()
         }
     }
-}
 
-/// Tracks the root observer entity and per-type observer groups.
-#[derive(Resource)]
-struct FSMObserverHierarchy {
-    root: Entity,
-    groups: HashMap<TypeId, Entity>,
-}
+    /// Create an `FSMPlugin` that processes requests with a system instead of an observer.
+    ///
+    /// [`StateChangeRequest`]s are registered as a message and applied in send order by
+    /// [`buffered::process_buffered_requests`] in `Update`. This gives explicit ordering
+    /// relative to gameplay systems and cheaper bulk processing. Triggered requests are
+    /// forwarded into the buffer, so existing code keeps working. See [`buffered`] for details.
+    #[must_use]
+    pub fn buffered() -> Self {
+        Self {
+            processing: RequestProcessing::Buffered,
+            ..Self::default()
+        }
+    }
 
-/// Marker component for the root `FSMObservers` entity.
-#[derive(Component)]
-struct FSMObserversRoot;
+    /// Create an `FSMPlugin` that applies buffered requests in one batch, for FSMs with many
+    /// transitions per frame.
+    ///
+    /// Transitions don't trigger `Exit`, `Transition` or `Enter` events; they are written as
+    /// [`AnyTransition`] messages instead. See [`batched`] for details.
+    #[must_use]
+    pub fn batched() -> Self {
+        Self {
+            processing: RequestProcessing::Batched,
+            ..Self::default()
+        }
+    }
 
-/// Ensures the root `FSMObservers` entity exists and returns its [`Entity`] id.
-fn ensure_fsm_hierarchy(world: &mut World) -> Entity {
-    if let Some(hierarchy) = world.get_resource::<FSMObserverHierarchy>() {
-        return hierarchy.root;
+    /// Validate [`batched`](Self::batched) requests in parallel on the `ComputeTaskPool`.
+    ///
+    /// Worth it when many entities transition per frame or `can_transition_ctx` is
+    /// expensive. Validation sees the world as it was before the batch; see [`batched`] for
+    /// the details. Has no effect in the other modes.
+    #[must_use]
+    pub fn with_parallel_validation(mut self) -> Self {
+        self.parallel_validation = true;
+        self
     }
 
-    let root = world
-        .spawn((Name::new("FSMObservers"), FSMObserversRoot))
+    /// Validate requests with a query in the observer where no World-reading layer applies.
+    ///
+    /// Requests for entities without overrides, pausing, debouncing, hysteresis or redirects,
+    /// of FSM types whose rules don't read the world ([`FSMTransition::READS_WORLD`]), skip
+    /// the World-reading validation; the others go through it as usual. See
+    /// [`query_validation`] for the details. Has no effect in the buffered modes.
+    #[must_use]
+    pub fn with_query_validation(mut self) -> Self {
+        self.query_validation = true;
+        self
+    }
+
+    /// Choose how several requests for the same entity within one frame are validated.
+    ///
+    /// Requests chain by default (see [`StateChangeRequest`]); use
+    /// [`RequestChaining::OncePerFrame`] to let only the first valid one through.
+    #[must_use]
+    pub fn with_request_chaining(mut self, chaining: RequestChaining) -> Self {
+        self.chaining = chaining;
+        self
+    }
+
+    /// Choose when the initial Enter events of a newly added FSM component fire.
+    ///
+    /// They fire right after the spawning bundle by default; use [`InitialEnter::Deferred`]
+    /// when components their observers read are inserted by separate commands, e.g.
+    /// `commands.spawn(S::Idle).insert(FSMOverride::whitelist(..))`.
+    #[must_use]
+    pub fn with_initial_enter(mut self, initial_enter: InitialEnter) -> Self {
+        self.initial_enter = initial_enter;
+        self
+    }
+
+    /// Measure the timing features of `S` ([`FSMTimer`], [`FSMDebounce`], [`FSMHysteresis`])
+    /// with `clock` instead of the [`FSMClock`] resource.
+    ///
+    /// Use [`FSMClock::Virtual`] for pause-aware behavior and [`FSMClock::Real`] for wall-clock
+    /// behavior, e.g. for menus that keep running while the game is paused. Inserts an
+    /// [`FSMTypeClock<S>`](clock::FSMTypeClock) resource.
+    #[must_use]
+    pub fn with_clock(mut self, clock: FSMClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Name `S` in the observer hierarchy, the `trace` records and the debug overlay.
+    ///
+    /// Defaults to the short type name, which is ambiguous when two crates both define e.g.
+    /// a `GameState`: `FSMPlugin::<menu::GameState>::default().with_name("menu::GameState")`.
+    /// See [`fsm_name`].
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Choose how much the `trace` and `debug-overlay` features report for `S`.
+    ///
+    /// Use [`FSMVerbosity::Off`] to silence a chatty FSM, e.g. per-frame animation states,
+    /// while tracing the others.
+    #[must_use]
+    pub fn with_verbosity(mut self, verbosity: FSMVerbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Apply requests in a deterministic order for fixed-timestep simulations.
+    ///
+    /// Instead of applying each [`StateChangeRequest`] as soon as it is triggered, requests
+    /// are buffered and applied in `FixedUpdate`, sorted by entity index. Requests for the
+    /// same entity keep the order they were sent in. See [`buffered`] for details.
+    #[must_use]
+    pub fn deterministic(mut self) -> Self {
+        self.processing = RequestProcessing::Deterministic;
+        self
+    }
+
+    /// Run request processing in the given schedule.
+    ///
+    /// Defaults to `Update` for [`buffered`](Self::buffered) and `FixedUpdate` for
+    /// [`deterministic`](Self::deterministic). Switches an immediate plugin to buffered mode,
+    /// since observers can't be scheduled.
+    #[must_use]
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = Some(schedule.intern());
+        self.use_system_processing();
+        self
+    }
+
+    /// Add the request processing system to a system set, in addition to [`FSMSystems`].
+    ///
+    /// Switches an immediate plugin to buffered mode, since observers can't be ordered.
+    #[must_use]
+    pub fn in_set(mut self, set: impl SystemSet) -> Self {
+        self.set = Some(set.intern());
+        self.use_system_processing();
+        self
+    }
+
+    /// Resolve competing requests for the same entity within one processing run.
+    ///
+    /// By default every request is applied in order, so the last valid one silently wins.
+    /// A policy other than [`ConflictPolicy::ApplyAll`] keeps a single request per entity.
+    /// Switches an immediate plugin to buffered mode, since conflicts can only be detected
+    /// across a buffer.
+    #[must_use]
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = policy;
+        self.use_system_processing();
+        self
+    }
+
+    /// Play (and loop) the animation graph node `node` whenever `state` is entered.
+    ///
+    /// See [`animation`] for how the [`AnimationPlayer`] is located.
+    #[cfg(feature = "animation")]
+    #[must_use]
+    pub fn with_animation(
+        self,
+        state: S,
+        node: bevy::animation::graph::AnimationNodeIndex,
+    ) -> Self {
+        self.with_crossfade(state, node, core::time::Duration::ZERO)
+    }
+
+    /// Like [`with_animation`](Self::with_animation), fading in over `crossfade` when the
+    /// player has an `AnimationTransitions` component.
+    #[cfg(feature = "animation")]
+    #[must_use]
+    pub fn with_crossfade(
+        mut self,
+        state: S,
+        node: bevy::animation::graph::AnimationNodeIndex,
+        crossfade: core::time::Duration,
+    ) -> Self {
+        self.animations.insert(
+            state,
+            animation::FSMAnimation {
+                node,
+                crossfade,
+                repeat: true,
+            },
+        );
+        self
+    }
+
+    /// Allow at most `cap` entities in `state` at once.
+    ///
+    /// Transitions into the state while it is full are denied with
+    /// [`TransitionVerdict::DeniedByCap`], before `FSMOverride` and the rules are consulted.
+    /// Adds [`FSMPopulationPlugin`](population::FSMPopulationPlugin) to count the entities;
+    /// see [`population`] for details.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum EnemyFSM { Idle, Aggro }
+    /// # let mut app = App::new();
+    /// // Only 5 enemies may attack the player at once
+    /// app.add_plugins(FSMPlugin::<EnemyFSM>::default().with_state_cap(EnemyFSM::Aggro, 5));
+    /// ```
+    #[must_use]
+    pub fn with_state_cap(mut self, state: S, cap: usize) -> Self {
+        self.caps.insert(state, cap);
+        self
+    }
+
+    /// Request every applied transition on the entity's direct children carrying `S`,
+    /// before or after the entity's own events.
+    ///
+    /// See [`propagation`] for details.
+    #[must_use]
+    pub fn with_child_propagation(mut self, order: ChildPropagation) -> Self {
+        self.child_propagation = Some(order);
+        self
+    }
+
+    /// Let the `Enter`, `Exit` and `Transition` events of `S` bubble up the `ChildOf`
+    /// hierarchy, so observers on ancestors react to their descendants' transitions.
+    ///
+    /// See [`propagation`] for details.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{Enter, FSMEntityCommandsExt, FSMPlugin, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum WeaponFSM { Intact, Broken }
+    /// # let mut app = App::new();
+    /// app.add_plugins(FSMPlugin::<WeaponFSM>::default().with_event_bubbling());
+    ///
+    /// fn spawn_knight(mut commands: Commands) {
+    ///     commands
+    ///         .spawn(Name::new("Knight"))
+    ///         .with_child(WeaponFSM::Intact)
+    ///         .observe_fsm(|enter: On<Enter<WeaponFSM>>| {
+    ///             if enter.state == WeaponFSM::Broken {
+    ///                 println!("{} broke", enter.original_event_target());
+    ///             }
+    ///         });
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_event_bubbling(mut self) -> Self {
+        self.event_bubbling = true;
+        self
+    }
+
+    fn use_system_processing(&mut self) {
+        if self.processing == RequestProcessing::Immediate {
+            self.processing = RequestProcessing::Buffered;
+        }
+    }
+}
+
+/// Remembers which core observers an [`FSMPlugin`] registered, so they can be restored
+/// after [`despawn_fsm_group`].
+#[derive(Resource)]
+struct FSMCoreObservers<S> {
+    buffered: bool,
+    /// Whether requests are validated from a query where possible
+    query_validation: bool,
+    on_added: bool,
+    on_removed: bool,
+    /// Whether variant-specific events are fired (see [`FSMPlugin::minimal`])
+    variant_events: bool,
+    /// How several requests for one entity within a frame are validated
+    chaining: RequestChaining,
+    /// When the initial Enter events fire
+    initial_enter: InitialEnter,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S: FSMState + core::hash::Hash> FSMCoreObservers<S> {
+    /// Registers the core observers under the group entity.
+    fn register(&self, world: &mut World) {
+        let group_entity = ensure_fsm_group::<S>(world);
+
+        let apply_entity = {
+            let mut observer = if self.buffered {
+                let mut observer = world.add_observer(buffered::buffer_state_request::<S>);
+                observer.insert(Name::new("buffer_state_request"));
+                observer
+            } else if self.query_validation {
+                let mut observer =
+                    world.add_observer(query_validation::apply_state_request_fast::<S>);
+                observer.insert(Name::new("apply_state_request_fast"));
+                observer
+            } else {
+                let mut observer = world.add_observer(apply_state_request::<S>);
+                observer.insert(Name::new("apply_state_request"));
+                observer
+            };
+            observer.insert(FSMObserverMarker::<S>::default());
+            observer.id()
+        };
+        world.entity_mut(group_entity).add_child(apply_entity);
+
+        if self.on_added {
+            let added_entity = {
+                let mut observer = match (self.initial_enter, self.variant_events) {
+                    (InitialEnter::Deferred, _) => world.add_observer(on_fsm_added_deferred::<S>),
+                    (InitialEnter::OnAdd, true) => world.add_observer(on_fsm_added::<S>),
+                    (InitialEnter::OnAdd, false) => world.add_observer(on_fsm_added_minimal::<S>),
+                };
+                observer.insert(Name::new("on_fsm_added"));
+                observer.insert(FSMObserverMarker::<S>::default());
+                observer.id()
+            };
+            world.entity_mut(group_entity).add_child(added_entity);
+        }
+
+        if self.on_removed {
+            let removed_entity = {
+                let mut observer = if self.variant_events {
+                    world.add_observer(on_fsm_removed::<S>)
+                } else {
+                    world.add_observer(on_fsm_removed_minimal::<S>)
+                };
+                observer.insert(Name::new("on_fsm_removed"));
+                observer.insert(FSMObserverMarker::<S>::default());
+                observer.id()
+            };
+            world.entity_mut(group_entity).add_child(removed_entity);
+        }
+
+        let cleanup_entity = world
+            .add_observer(cleanup::on_fsm_cleanup::<S>)
+            .insert((
+                Name::new("on_fsm_cleanup"),
+                FSMObserverMarker::<S>::default(),
+            ))
+            .id();
+        world.entity_mut(group_entity).add_child(cleanup_entity);
+
+        if self.variant_events {
+            S::add_actions(world);
+        }
+        #[cfg(feature = "animation")]
+        animation::add_observer::<S>(world);
+    }
+}
+
+/// System set containing the request processing systems of all buffered FSM plugins.
+///
+/// Order gameplay systems against it to read states before or after transitions apply:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::FSMSystems;
+/// # fn read_states() {}
+/// # let mut app = App::new();
+/// app.add_systems(Update, read_states.after(FSMSystems));
+/// ```
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FSMSystems;
+
+/// How an [`FSMPlugin`] applies state change requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequestProcessing {
+    /// Observer applying each request as it is triggered
+    Immediate,
+    /// System applying buffered requests in send order
+    Buffered,
+    /// System applying buffered requests sorted by entity in `FixedUpdate`
+    Deterministic,
+    /// System applying buffered requests without transition events
+    Batched,
+}
+
+impl<S> Plugin for FSMPlugin<S>
+where
+    S: FSMState
+        + core::hash::Hash
+        + Component
+        + Reflect
+        + FromReflect
+        + Typed
+        + GetTypeRegistration,
+{
+    fn build(&self, app: &mut App) {
+        // `add_plugins` already rejects a second `FSMPlugin<S>`, but building one by hand
+        // would register the core observers twice and fire every event twice
+        assert!(
+            !fsm_plugin_added::<S>(app.world()),
+            "FSMPlugin::<{}> was already added; each FSM type takes a single FSMPlugin",
+            ShortName::of::<S>()
+        );
+        set_fsm_identity::<S>(app.world_mut(), self.name.clone(), self.verbosity);
+        // Register the FSM type for reflection, including the type-erased FSM accessors
+        app.register_type::<S>();
+        app.register_type_data::<S, ReflectFSMState>();
+        #[cfg(feature = "trace")]
+        app.insert_resource(trace::StateNames::<S>::new());
+        let core = FSMCoreObservers::<S> {
+            buffered: self.processing != RequestProcessing::Immediate,
+            query_validation: self.query_validation,
+            on_added: !self.ignore_fsm_addition,
+            on_removed: !self.ignore_fsm_removal,
+            variant_events: self.variant_events,
+            chaining: self.chaining,
+            initial_enter: self.initial_enter,
+            _phantom: std::marker::PhantomData,
+        };
+        #[cfg(feature = "animation")]
+        animation::register(app, &self.animations);
+        core.register(app.world_mut());
+        app.insert_resource(core);
+        if let Some(clock) = self.clock {
+            app.insert_resource(clock::FSMTypeClock::<S>::new(clock));
+        }
+        app.add_systems(
+            First,
+            (
+                clear_state_changed::<S>,
+                hysteresis::reset_unrequested_transitions::<S>,
+            ),
+        );
+        if self.initial_enter == InitialEnter::Deferred && !self.ignore_fsm_addition {
+            app.add_systems(PostUpdate, fire_deferred_enters::<S>);
+        }
+        for register in &self.required {
+            register(app);
+        }
+        app.add_systems(
+            Update,
+            (
+                path::advance_fsm_paths::<S>,
+                debounce::release_debounced_requests::<S>,
+                retry::retry_parked_requests::<S>,
+                query::tick_fsm_timers::<S>,
+            )
+                .before(FSMSystems),
+        );
+        if let Some(order) = self.child_propagation {
+            app.insert_resource(propagation::PropagateToChildren::<S>::new(order));
+        }
+        if self.event_bubbling {
+            app.insert_resource(propagation::BubbleEvents::<S>::default());
+        }
+        if !self.caps.is_empty() {
+            if !app.is_plugin_added::<population::FSMPopulationPlugin<S>>() {
+                app.add_plugins(population::FSMPopulationPlugin::<S>::default());
+            }
+            let mut caps = population::FSMStateCaps::<S>::default();
+            for (&state, &cap) in &self.caps {
+                caps.set(state, cap);
+            }
+            app.insert_resource(caps);
+        }
+
+        let (default_schedule, sorted) = match self.processing {
+            RequestProcessing::Immediate => return,
+            RequestProcessing::Buffered => (Update.intern(), false),
+            RequestProcessing::Deterministic => (FixedUpdate.intern(), true),
+            RequestProcessing::Batched => (Update.intern(), false),
+        };
+        let policy = self.conflict_policy;
+        let batched = self.processing == RequestProcessing::Batched;
+        let parallel = self.parallel_validation;
+        let system = (move |world: &mut World| {
+            if batched {
+                batched::process_batch::<S>(world, policy, parallel);
+            } else {
+                buffered::process_requests::<S>(world, sorted, policy);
+            }
+        })
+        .in_set(FSMSystems);
+        let system = match self.set {
+            Some(set) => system.in_set(set),
+            None => system,
+        };
+        app.add_message::<StateChangeRequest<S>>();
+        if batched {
+            app.add_message::<AnyTransition<S>>();
+        }
+        app.add_systems(self.schedule.unwrap_or(default_schedule), system);
+    }
+}
+
+/// Tracks the root observer entity and per-type observer groups.
+#[derive(Resource)]
+pub(crate) struct FSMObserverHierarchy {
+    root: Entity,
+    groups: HashMap<TypeId, FSMGroupEntry>,
+}
+
+/// An observer group and the type path of its FSM type.
+#[derive(Clone, Copy)]
+struct FSMGroupEntry {
+    entity: Entity,
+    type_path: &'static str,
+}
+
+impl FSMObserverHierarchy {
+    /// Groups of the FSM types other than `type_id` whose short type name is `short_name`.
+    fn namesakes<'a>(
+        &'a self,
+        type_id: TypeId,
+        short_name: &'a str,
+    ) -> impl Iterator<Item = FSMGroupEntry> + 'a {
+        self.groups
+            .iter()
+            .filter(move |(&id, entry)| {
+                id != type_id && ShortName(entry.type_path).to_string() == short_name
+            })
+            .map(|(_, &entry)| entry)
+    }
+}
+
+/// Marker component for the root `FSMObservers` entity.
+#[derive(Component)]
+struct FSMObserversRoot;
+
+/// Ensures the root `FSMObservers` entity exists and returns its [`Entity`] id.
+fn ensure_fsm_hierarchy(world: &mut World) -> Entity {
+    if let Some(hierarchy) = world.get_resource::<FSMObserverHierarchy>() {
+        return hierarchy.root;
+    }
+
+    let root = world
+        .spawn((Name::new("FSMObservers"), FSMObserversRoot))
         .id();
 
-    world.insert_resource(FSMObserverHierarchy {
-        root,
-        groups: HashMap::default(),
-    });
+    world.insert_resource(FSMObserverHierarchy {
+        root,
+        groups: HashMap::default(),
+    });
+
+    root
+}
+
+/// Ensures an observer group exists for the FSM type and returns its entity id.
+fn ensure_fsm_group<S>(world: &mut World) -> Entity
+where
+    S: Send + Sync + 'static,
+{
+    let root = ensure_fsm_hierarchy(world);
+    let type_id = TypeId::of::<S>();
+
+    if let Some(group) = fsm_group_of(world, type_id) {
+        return group;
+    }
+
+    let group = world.spawn(FSMObserverGroup::<S>::default()).id();
+
+    world.entity_mut(root).add_child(group);
+
+    let entry = FSMGroupEntry {
+        entity: group,
+        type_path: core::any::type_name::<S>(),
+    };
+    world
+        .resource_mut::<FSMObserverHierarchy>()
+        .groups
+        .insert(type_id, entry);
+
+    let name = fsm_name::<S>(world).into_owned();
+    world.entity_mut(group).insert(Name::new(name));
+    qualify_namesakes::<S>(world);
+
+    group
+}
+
+/// Renames the groups of other FSM types with the short type name of `S` by their type path,
+/// unless they were given a name.
+fn qualify_namesakes<S>(world: &mut World)
+where
+    S: Send + Sync + 'static,
+{
+    let short_name = ShortName::of::<S>().to_string();
+    let namesakes: Vec<_> = world
+        .resource::<FSMObserverHierarchy>()
+        .namesakes(TypeId::of::<S>(), &short_name)
+        .collect();
+    for namesake in namesakes {
+        let unnamed = world
+            .get::<Name>(namesake.entity)
+            .is_some_and(|name| name.as_str() == short_name);
+        if unnamed {
+            world
+                .entity_mut(namesake.entity)
+                .insert(Name::new(namesake.type_path));
+        }
+    }
+}
+
+/// Attaches an observer entity to the hierarchy for the FSM type `S`.
+pub fn attach_observer_to_group<S>(world: &mut World, observer: Entity)
+where
+    S: Send + Sync + 'static,
+{
+    let group_entity = ensure_fsm_group::<S>(world);
+    world.entity_mut(group_entity).add_child(observer);
+}
+
+/// Returns whether an [`FSMPlugin`] has been added for `S`.
+///
+/// Like `app.is_plugin_added::<FSMPlugin<S>>()`, but only needs the world, e.g. for plugins
+/// that add `FSMPlugin<S>` only if no other plugin did. An observer group alone (see
+/// [`fsm_group`]) doesn't count, since [`fsm_observer!`] creates one without the plugin.
+pub fn fsm_plugin_added<S>(world: &World) -> bool
+where
+    S: Send + Sync + 'static,
+{
+    world.contains_resource::<FSMCoreObservers<S>>()
+}
+
+/// Display name and verbosity of the FSM type `S`, given to its [`FSMPlugin`].
+#[derive(Resource)]
+pub(crate) struct FSMIdentity<S> {
+    name: Option<Cow<'static, str>>,
+    pub(crate) verbosity: FSMVerbosity,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S: 'static> FSMIdentity<S> {
+    /// The configured name of `S`, or its short type name if no other FSM type in the
+    /// `hierarchy` shares it, or else its type path.
+    pub(crate) fn name<'w>(
+        identity: Option<&'w Self>,
+        hierarchy: Option<&FSMObserverHierarchy>,
+    ) -> Cow<'w, str> {
+        if let Some(name) = identity.and_then(|identity| identity.name.as_deref()) {
+            return Cow::Borrowed(name);
+        }
+        // Short name keeps generic arguments readable, e.g. `FSMSlot<WeaponFSM, 1>`
+        let short_name = ShortName::of::<S>().to_string();
+        let ambiguous = hierarchy.is_some_and(|hierarchy| {
+            hierarchy
+                .namesakes(TypeId::of::<S>(), &short_name)
+                .next()
+                .is_some()
+        });
+        if ambiguous {
+            Cow::Borrowed(core::any::type_name::<S>())
+        } else {
+            Cow::Owned(short_name)
+        }
+    }
+}
+
+/// Stores the name and verbosity of `S` and renames its observer group, if
+/// [`fsm_observer!`] already created one.
+fn set_fsm_identity<S>(world: &mut World, name: Option<Cow<'static, str>>, verbosity: FSMVerbosity)
+where
+    S: Send + Sync + 'static,
+{
+    world.insert_resource(FSMIdentity::<S> {
+        name,
+        verbosity,
+        _phantom: std::marker::PhantomData,
+    });
+    if let Some(group) = fsm_group::<S>(world) {
+        let name = fsm_name::<S>(world).into_owned();
+        world.entity_mut(group).insert(Name::new(name));
+    }
+}
+
+/// Returns the display name of the FSM type `S`: the one given to
+/// [`FSMPlugin::with_name`], or the short type name. If the observer hierarchy holds another
+/// FSM type with the same short type name, e.g. `menu::GameState` and `world::GameState`,
+/// both are named by their full type path instead.
+///
+/// Names its observer group, `trace` records and debug overlay labels.
+pub fn fsm_name<S>(world: &World) -> Cow<'_, str>
+where
+    S: Send + Sync + 'static,
+{
+    FSMIdentity::<S>::name(
+        world.get_resource::<FSMIdentity<S>>(),
+        world.get_resource::<FSMObserverHierarchy>(),
+    )
+}
+
+/// Returns how much the debug features report for `S` (see [`FSMPlugin::with_verbosity`]),
+/// e.g. for debug tools of your own.
+pub fn fsm_verbosity<S>(world: &World) -> FSMVerbosity
+where
+    S: Send + Sync + 'static,
+{
+    world
+        .get_resource::<FSMIdentity<S>>()
+        .map_or_else(FSMVerbosity::default, |identity| identity.verbosity)
+}
+
+/// Returns the root `FSMObservers` entity, if any FSM has been set up yet.
+pub fn fsm_root(world: &World) -> Option<Entity> {
+    world
+        .get_resource::<FSMObserverHierarchy>()
+        .map(|hierarchy| hierarchy.root)
+}
+
+/// Returns the observer group entity for the FSM type `S`, if it has been created.
+pub fn fsm_group<S>(world: &World) -> Option<Entity>
+where
+    S: Send + Sync + 'static,
+{
+    fsm_group_of(world, TypeId::of::<S>())
+}
+
+/// Returns the observer group entity for the FSM type with the given [`TypeId`], if it has
+/// been created.
+///
+/// Type-erased version of [`fsm_group`], for tools that only know the FSM type at runtime,
+/// e.g. from [`ReflectFSMState`] type data.
+pub fn fsm_group_of(world: &World, type_id: TypeId) -> Option<Entity> {
+    world
+        .get_resource::<FSMObserverHierarchy>()?
+        .groups
+        .get(&type_id)
+        .map(|entry| entry.entity)
+}
+
+/// Lists every FSM observer group as `(group entity, name)`, sorted by name.
+pub fn fsm_groups(world: &World) -> Vec<(Entity, String)> {
+    let Some(hierarchy) = world.get_resource::<FSMObserverHierarchy>() else {
+        return Vec::new();
+    };
+    let mut groups: Vec<_> = hierarchy
+        .groups
+        .values()
+        .map(|entry| (entry.entity, entity_name(world, entry.entity)))
+        .collect();
+    groups.sort_by(|a, b| a.1.cmp(&b.1));
+    groups
+}
+
+/// Lists the observers registered under the group for `S` as `(observer entity, name)`.
+///
+/// Observers are returned in registration order. Unnamed observers are listed with an
+/// empty name.
+pub fn fsm_observers<S>(world: &World) -> Vec<(Entity, String)>
+where
+    S: Send + Sync + 'static,
+{
+    let Some(children) = fsm_group::<S>(world).and_then(|group| world.get::<Children>(group))
+    else {
+        return Vec::new();
+    };
+    children
+        .iter()
+        .map(|observer| (observer, entity_name(world, observer)))
+        .collect()
+}
+
+/// Despawns every observer named `name` in the group for `S`.
+///
+/// Returns the number of observers removed. Names are the ones assigned by
+/// [`fsm_observer!`] and [`FSMAppExt::add_fsm_observer`] (the function name) or by
+/// [`FSMPlugin`] for core observers.
+pub fn remove_fsm_observer<S>(world: &mut World, name: &str) -> usize
+where
+    S: Send + Sync + 'static,
+{
+    let matching: Vec<Entity> = fsm_observers::<S>(world)
+        .into_iter()
+        .filter(|(_, observer_name)| observer_name == name)
+        .map(|(observer, _)| observer)
+        .collect();
+    for &observer in &matching {
+        world.despawn(observer);
+    }
+    matching.len()
+}
+
+/// Replaces every observer named `name` in the group for `S` with `observer`, keeping the
+/// name and the place in the group.
+///
+/// Meant for hot patches during development and for A/B tests of a behavior: the old
+/// observer entities are despawned and a single replacement takes the place of the first
+/// one. If that one was ordered (see [`FSMObserverOrder`]), the replacement runs in the same
+/// order. If none matched, the replacement is added like with [`FSMAppExt::add_fsm_observer`].
+/// Returns the replacement's entity.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::{replace_fsm_observer, Enter, FSMState, FSMTransition};
+/// # use bevy_enum_event::EnumEvent;
+/// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// # enum LifeFSM { Alive, Dying }
+/// fn slow_death(_: On<Enter<life_fsm::Dying>>) {}
+///
+/// # let mut app = App::new();
+/// replace_fsm_observer::<LifeFSM, _, _>(app.world_mut(), "on_enter_dying", slow_death);
+/// ```
+pub fn replace_fsm_observer<S, E, M>(
+    world: &mut World,
+    name: &str,
+    observer: impl IntoObserverSystem<E, (), M>,
+) -> Entity
+where
+    S: Send + Sync + 'static,
+    E: Event + Clone,
+    for<'a> E::Trigger<'a>: Default,
+{
+    let matching: Vec<Entity> = fsm_observers::<S>(world)
+        .into_iter()
+        .filter(|(_, observer_name)| observer_name == name)
+        .map(|(observer, _)| observer)
+        .collect();
+    let group = ensure_fsm_group::<S>(world);
+    let index = matching.first().and_then(|&first| {
+        world
+            .get::<Children>(group)
+            .and_then(|children| children.iter().position(|child| child == first))
+    });
+    let place = matching
+        .first()
+        .and_then(|&first| ordering::ordered_place::<E>(world, first));
+    for &old in &matching {
+        world.despawn(old);
+    }
+    let replacement = match place {
+        Some(place) => {
+            ordering::add_ordered_observer_at::<S, E, M>(world, name.to_string(), place, observer)
+        }
+        None => world
+            .add_observer(observer)
+            .insert((
+                Name::new(name.to_string()),
+                FSMObserverMarker::<S>::default(),
+            ))
+            .id(),
+    };
+    match index {
+        Some(index) => {
+            world
+                .entity_mut(group)
+                .insert_children(index, &[replacement]);
+        }
+        None => attach_observer_to_group::<S>(world, replacement),
+    }
+    replacement
+}
+
+/// Marks an FSM observer switched off with [`set_fsm_observer_enabled`].
+///
+/// Holds the detached [`Observer`] until the observer is switched on again.
+#[derive(Component)]
+pub struct FSMObserverDisabled {
+    observer: Option<Observer>,
+}
+
+/// Switches every observer named `name` in the group for `S` off or on, e.g. from a debug
+/// menu or a feature toggle.
+///
+/// A disabled observer stays in the hierarchy, marked with [`FSMObserverDisabled`], but is
+/// detached from its events until it is enabled again. Entity-scoped observers can't be
+/// switched. Returns the number of observers named `name`, like [`remove_fsm_observer`].
+pub fn set_fsm_observer_enabled<S>(world: &mut World, name: &str, enabled: bool) -> usize
+where
+    S: Send + Sync + 'static,
+{
+    let matching: Vec<Entity> = fsm_observers::<S>(world)
+        .into_iter()
+        .filter(|(_, observer_name)| observer_name == name)
+        .map(|(observer, _)| observer)
+        .collect();
+    for &observer in &matching {
+        let mut observer = world.entity_mut(observer);
+        if enabled {
+            if let Some(detached) = observer
+                .take::<FSMObserverDisabled>()
+                .and_then(|disabled| disabled.observer)
+            {
+                observer.insert(detached);
+            }
+        } else if !observer.contains::<FSMObserverDisabled>() {
+            // Removing the `Observer` unregisters it, inserting it again registers it anew
+            let detached = observer.take::<Observer>();
+            observer.insert(FSMObserverDisabled { observer: detached });
+        }
+    }
+    world.flush();
+    matching.len()
+}
+
+/// Despawns the observer group for `S` together with all of its observers.
+///
+/// This includes the core observers added by [`FSMPlugin`], so requests for `S` are
+/// ignored until they are restored with [`restore_fsm_core_observers`]. Observers added
+/// afterwards (e.g. via [`fsm_observer!`]) create a fresh group. Returns `false` if no
+/// group existed.
+pub fn despawn_fsm_group<S>(world: &mut World) -> bool
+where
+    S: Send + Sync + 'static,
+{
+    let Some(group) = fsm_group::<S>(world) else {
+        return false;
+    };
+    world
+        .resource_mut::<FSMObserverHierarchy>()
+        .groups
+        .remove(&TypeId::of::<S>());
+    world.despawn(group);
+    true
+}
+
+/// Re-registers the core observers [`FSMPlugin`] added for `S`, e.g. after
+/// [`despawn_fsm_group`], along with the entry and exit actions of `S` and the observer
+/// playing its animations.
+///
+/// Returns `false` if no `FSMPlugin<S>` was added to the app or the core observers are
+/// still registered.
+pub fn restore_fsm_core_observers<S>(world: &mut World) -> bool
+where
+    S: FSMState + core::hash::Hash,
+{
+    if !world.contains_resource::<FSMCoreObservers<S>>() {
+        return false;
+    }
+    let registered = fsm_observers::<S>(world).iter().any(|(_, name)| {
+        matches!(
+            name.as_str(),
+            "apply_state_request" | "apply_state_request_fast" | "buffer_state_request"
+        )
+    });
+    if registered {
+        return false;
+    }
+    world.resource_scope(|world, core: Mut<FSMCoreObservers<S>>| core.register(world));
+    true
+}
+
+fn entity_name(world: &World, entity: Entity) -> String {
+    world
+        .get::<Name>(entity)
+        .map(|name| name.as_str().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    enum TestState {
+        A,
+        B,
+        C,
+    }
+
+    impl FSMState for TestState {}
+
+    impl FSMTransition for TestState {
+        fn can_transition(from: Self, to: Self) -> bool {
+            !(matches!(from, TestState::A) && matches!(to, TestState::C))
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct EventLog {
+        enters: Vec<TestState>,
+        exits: Vec<TestState>,
+        transitions: Vec<(TestState, TestState)>,
+    }
+
+    fn on_enter(trigger: On<Enter<TestState>>, mut log: ResMut<EventLog>) {
+        log.enters.push(trigger.event().state);
+    }
+
+    fn on_exit(trigger: On<Exit<TestState>>, mut log: ResMut<EventLog>) {
+        log.exits.push(trigger.event().state);
+    }
+
+    fn on_transition(trigger: On<Transition<TestState, TestState>>, mut log: ResMut<EventLog>) {
+        let event = trigger.event();
+        log.transitions.push((event.from, event.to));
+    }
+
+    #[test]
+    fn transitions_apply_and_fire_events() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<EventLog>();
+        app.world_mut()
+            .add_observer(apply_state_request::<TestState>);
+        app.world_mut().add_observer(on_enter);
+        app.world_mut().add_observer(on_exit);
+
+        let e = app.world_mut().spawn(TestState::A).id();
+
+        app.world_mut()
+            .commands()
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
+
+        app.update();
+
+        assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
+        let log = app.world().resource::<EventLog>();
+        assert_eq!(log.exits, vec![TestState::A]);
+        assert_eq!(log.enters, vec![TestState::B]);
+    }
+
+    #[test]
+    fn guard_blocks_invalid_transitions() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<EventLog>();
+        app.world_mut()
+            .add_observer(apply_state_request::<TestState>);
+
+        let e = app.world_mut().spawn(TestState::A).id();
+
+        app.world_mut()
+            .commands()
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
+
+        app.update();
+
+        assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::A);
+    }
+
+    #[test]
+    fn generic_transition_events_fire() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<EventLog>();
+        app.world_mut()
+            .add_observer(apply_state_request::<TestState>);
+        app.world_mut().add_observer(on_transition);
+
+        let e = app.world_mut().spawn(TestState::A).id();
+
+        // Transition A -> B
+        app.world_mut()
+            .commands()
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
+        app.update();
+
+        let log = app.world().resource::<EventLog>();
+        assert_eq!(log.transitions, vec![(TestState::A, TestState::B)]);
+    }
+
+    #[test]
+    fn on_fsm_added_fires_initial_enter_events() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<EventLog>();
+        app.world_mut()
+            .add_observer(apply_state_request::<TestState>);
+        app.world_mut().add_observer(on_fsm_added::<TestState>);
+        app.world_mut().add_observer(on_enter);
+
+        let _e = app.world_mut().spawn(TestState::A).id();
+        app.update();
+
+        let log = app.world().resource::<EventLog>();
+        assert_eq!(log.enters, vec![TestState::A]);
+    }
+
+    #[test]
+    fn minimal_plugin_skips_variant_events() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static VARIANT_EVENTS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Resource, Default)]
+        struct Entered(usize);
+
+        #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum LampFSM {
+            Off,
+            On,
+        }
+
+        impl FSMTransition for LampFSM {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+        }
+
+        impl FSMState for LampFSM {
+            fn trigger_enter_variant(_: &mut Commands, _: Entity, _: Self) {
+                VARIANT_EVENTS.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn trigger_exit_variant(_: &mut Commands, _: Entity, _: Self) {
+                VARIANT_EVENTS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LampFSM>::minimal());
+        app.init_resource::<Entered>();
+        app.add_observer(|_: On<Enter<LampFSM>>, mut entered: ResMut<Entered>| entered.0 += 1);
+        let lamp = app.world_mut().spawn(LampFSM::Off).id();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(lamp, LampFSM::On));
+        app.world_mut().flush();
+        app.world_mut().despawn(lamp);
+
+        assert_eq!(app.world().resource::<Entered>().0, 2);
+        assert_eq!(VARIANT_EVENTS.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn any_transition_covers_initial_add_and_every_transition() {
+        #[derive(Resource, Default)]
+        struct Changes(Vec<(Option<TestState>, TestState)>);
+
+        let mut world = World::new();
+        world.init_resource::<Changes>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(on_fsm_added::<TestState>);
+        world.add_observer(
+            |change: On<AnyTransition<TestState>>, mut changes: ResMut<Changes>| {
+                changes.0.push((change.event().from, change.event().to));
+            },
+        );
+
+        let e = world.spawn(TestState::A).id();
+        world.flush();
+        world.trigger(StateChangeRequest::new(e, TestState::B));
+        world.flush();
+        force_state(&mut world, e, TestState::C);
+
+        use TestState::*;
+        assert_eq!(
+            world.resource::<Changes>().0,
+            vec![(None, A), (Some(A), B), (Some(B), C)]
+        );
+    }
+
+    #[test]
+    fn force_state_mid_transition_is_queued_behind_held_requests() {
+        let mut world = World::new();
+        world.init_resource::<EventLog>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(on_enter);
+        world.add_observer(|enter: On<Enter<TestState>>, mut commands: Commands| {
+            if enter.state == TestState::B {
+                let entity = enter.entity;
+                commands.trigger(StateChangeRequest::new(entity, TestState::A));
+                // A -> C is denied by the rules, forcing skips them
+                commands.queue(move |world: &mut World| force_state(world, entity, TestState::C));
+            }
+        });
+
+        let e = world.spawn(TestState::A).id();
+        world.trigger(StateChangeRequest::new(e, TestState::B));
+        world.flush();
 
-    root
-}
+        use TestState::*;
+        assert_eq!(world.resource::<EventLog>().enters, vec![B, A, C]);
+        assert_eq!(world.get::<TestState>(e), Some(&C));
+        assert!(!world.entity(e).contains::<TransitioningTo<TestState>>());
+    }
 
-/// Ensures an observer group exists for the FSM type and returns its entity id.
-fn ensure_fsm_group<S>(world: &mut World) -> Entity
-where
-    S: Send + Sync + 'static,
-{
-    let root = ensure_fsm_hierarchy(world);
-    let type_id = TypeId::of::<S>();
+    #[test]
+    fn enter_and_exit_carry_previous_and_next_state() {
+        #[derive(Resource, Default)]
+        struct Edges(Vec<(&'static str, TestState, Option<TestState>)>);
+
+        let mut world = World::new();
+        world.init_resource::<Edges>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(on_fsm_added::<TestState>);
+        world.add_observer(on_fsm_removed::<TestState>);
+        world.add_observer(|enter: On<Enter<TestState>>, mut edges: ResMut<Edges>| {
+            let Enter {
+                state, previous, ..
+            } = *enter.event();
+            edges.0.push(("enter", state, previous));
+        });
+        world.add_observer(|exit: On<Exit<TestState>>, mut edges: ResMut<Edges>| {
+            let Exit { state, next, .. } = *exit.event();
+            edges.0.push(("exit", state, next));
+        });
 
-    if let Some(group) = {
-        let hierarchy = world.resource::<FSMObserverHierarchy>();
-        hierarchy.groups.get(&type_id).copied()
-    } {
-        return group;
+        let e = world.spawn(TestState::A).id();
+        world.flush();
+        world.trigger(StateChangeRequest::new(e, TestState::B));
+        world.flush();
+        world.despawn(e);
+        world.flush();
+
+        use TestState::*;
+        assert_eq!(
+            world.resource::<Edges>().0,
+            vec![
+                ("enter", A, None),
+                ("exit", A, Some(B)),
+                ("enter", B, Some(A)),
+                ("exit", B, None),
+            ]
+        );
     }
 
-    let type_name = std::any::type_name::<S>()
-        .split("::")
-        .last()
-        .unwrap_or("UnknownFSM")
-        .to_string();
+    #[test]
+    fn guards_see_time_in_state_and_changes_since_the_transition() {
+        use core::time::Duration;
 
-    let group = world
-        .spawn((Name::new(type_name), FSMObserverGroup::<S>::default()))
-        .id();
+        #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum StanceFSM {
+            Guarding,
+            Attacking,
+        }
 
-    world.entity_mut(root).add_child(group);
+        #[derive(Component)]
+        struct Aim(f32);
 
-    world
-        .resource_mut::<FSMObserverHierarchy>()
-        .groups
-        .insert(type_id, group);
+        impl FSMState for StanceFSM {}
 
-    group
-}
+        impl FSMTransition for StanceFSM {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
 
-/// Attaches an observer entity to the hierarchy for the FSM type `S`.
-pub fn attach_observer_to_group<S>(world: &mut World, observer: Entity)
-where
-    S: Send + Sync + 'static,
-{
-    let group_entity = ensure_fsm_group::<S>(world);
-    world.entity_mut(group_entity).add_child(observer);
-}
+            // Hold the guard for a second, unless the player re-aims
+            fn try_transition_guarded(
+                ctx: &GuardCtx<'_>,
+                from: Self,
+                _: Self,
+            ) -> Result<(), DenyReason> {
+                let held = ctx.time_in_state >= Some(Duration::from_secs(1));
+                if from == StanceFSM::Guarding && !held && !ctx.changed_since_transition::<Aim>() {
+                    return Err(DenyReason::new("Still guarding"));
+                }
+                Ok(())
+            }
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut world = World::new();
+        world.add_observer(apply_state_request::<StanceFSM>);
+        let timer = |secs| FSMTimer::<StanceFSM>::with_elapsed(Duration::from_secs_f32(secs));
+        let fresh = world
+            .spawn((StanceFSM::Guarding, timer(0.5), Aim(0.0)))
+            .id();
+        let veteran = world
+            .spawn((StanceFSM::Guarding, timer(2.0), Aim(0.0)))
+            .id();
+        let reaiming = world
+            .spawn((StanceFSM::Guarding, timer(0.5), Aim(0.0)))
+            .id();
+        let ctx = GuardCtx::of::<StanceFSM>(&world, fresh);
+        assert_eq!(ctx.time_in_state, Some(Duration::from_millis(500)));
+        assert!(ctx.last_transition_tick.is_some());
+
+        world.increment_change_tick();
+        world.get_mut::<Aim>(reaiming).unwrap().0 = 90.0;
+        for entity in [fresh, veteran, reaiming] {
+            world.trigger(StateChangeRequest::new(entity, StanceFSM::Attacking));
+            world.flush();
+        }
 
-    #[derive(Component, Clone, Copy, Debug, Hash, PartialEq, Eq)]
-    enum TestState {
-        A,
-        B,
-        C,
+        assert_eq!(world.get::<StanceFSM>(fresh), Some(&StanceFSM::Guarding));
+        assert_eq!(world.get::<StanceFSM>(veteran), Some(&StanceFSM::Attacking));
+        assert_eq!(
+            world.get::<StanceFSM>(reaiming),
+            Some(&StanceFSM::Attacking)
+        );
     }
 
-    impl FSMState for TestState {}
+    #[test]
+    fn rules_report_deny_reasons() {
+        #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum MageFSM {
+            Idle,
+            Casting,
+        }
 
-    impl FSMTransition for TestState {
-        fn can_transition(from: Self, to: Self) -> bool {
-            !(matches!(from, TestState::A) && matches!(to, TestState::C))
+        #[derive(Component)]
+        struct Energy(u32);
+
+        impl FSMState for MageFSM {}
+
+        impl FSMTransition for MageFSM {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+
+            fn try_transition_ctx(
+                world: &World,
+                entity: Entity,
+                _: Self,
+                to: Self,
+            ) -> Result<(), DenyReason> {
+                let energy = world.get::<Energy>(entity).map_or(0, |energy| energy.0);
+                if to == MageFSM::Casting && energy < 10 {
+                    return Err(DenyReason::new("Not enough energy (need 10)").with_payload(energy));
+                }
+                Ok(())
+            }
         }
-    }
 
-    #[derive(Resource, Default)]
-    struct EventLog {
-        enters: Vec<TestState>,
-        exits: Vec<TestState>,
-        transitions: Vec<(TestState, TestState)>,
-    }
+        #[derive(Resource, Default)]
+        struct Denials(Vec<(TransitionVerdict, Option<String>, Option<u32>)>);
+
+        let mut world = World::new();
+        world.init_resource::<Denials>();
+        world.add_observer(apply_state_request::<MageFSM>);
+        world.add_observer(
+            |denied: On<TransitionDenied<MageFSM>>, mut denials: ResMut<Denials>| {
+                let details = denied.details.as_ref();
+                denials.0.push((
+                    denied.reason,
+                    details.and_then(DenyReason::message).map(str::to_string),
+                    details.and_then(|details| details.payload::<u32>().copied()),
+                ));
+            },
+        );
 
-    fn on_enter(trigger: On<Enter<TestState>>, mut log: ResMut<EventLog>) {
-        log.enters.push(trigger.event().state);
-    }
+        let apprentice = world.spawn((MageFSM::Idle, Energy(4))).id();
+        let archmage = world.spawn((MageFSM::Idle, Energy(50))).id();
+        for mage in [apprentice, archmage] {
+            world.trigger(StateChangeRequest::new(mage, MageFSM::Casting));
+            world.flush();
+        }
 
-    fn on_exit(trigger: On<Exit<TestState>>, mut log: ResMut<EventLog>) {
-        log.exits.push(trigger.event().state);
+        assert_eq!(
+            world.resource::<Denials>().0,
+            vec![(
+                TransitionVerdict::DeniedByRules,
+                Some("Not enough energy (need 10)".to_string()),
+                Some(4)
+            )]
+        );
+        assert_eq!(world.get::<MageFSM>(archmage), Some(&MageFSM::Casting));
     }
 
-    fn on_transition(trigger: On<Transition<TestState, TestState>>, mut log: ResMut<EventLog>) {
-        let event = trigger.event();
-        log.transitions.push((event.from, event.to));
+    #[test]
+    fn request_source_is_passed_to_transition_and_denial_events() {
+        #[derive(Resource, Default)]
+        struct Sources(Vec<Option<Entity>>);
+
+        let mut world = World::new();
+        world.init_resource::<Sources>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(
+            |transition: On<Transition<TestState, TestState>>, mut sources: ResMut<Sources>| {
+                sources.0.push(transition.event().source);
+            },
+        );
+        world.add_observer(
+            |denied: On<TransitionDenied<TestState>>, mut sources: ResMut<Sources>| {
+                sources.0.push(denied.event().source);
+            },
+        );
+
+        let attacker = world.spawn_empty().id();
+        let e = world.spawn(TestState::A).id();
+        // A -> C is not allowed
+        world.trigger(StateChangeRequest::new(e, TestState::C).with_source(attacker));
+        world.flush();
+        world.trigger(StateChangeRequest::new(e, TestState::B).with_source(attacker));
+        world.flush();
+        world.trigger(StateChangeRequest::new(e, TestState::C));
+        world.flush();
+
+        assert_eq!(
+            world.resource::<Sources>().0,
+            vec![Some(attacker), Some(attacker), None]
+        );
     }
 
+    // Additional tests without variant-specific events (to avoid proc macro issues in test modules)
     #[test]
-    fn transitions_apply_and_fire_events() {
+    fn fsm_plugin_registers_core_observers_with_teststate() {
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
         app.init_resource::<EventLog>();
+        // Note: TestState doesn't have FSMState derive, so can't use FSMPlugin
+        // This tests the manual approach still works
         app.world_mut()
             .add_observer(apply_state_request::<TestState>);
+        app.world_mut().add_observer(on_fsm_added::<TestState>);
         app.world_mut().add_observer(on_enter);
-        app.world_mut().add_observer(on_exit);
 
         let e = app.world_mut().spawn(TestState::A).id();
+        app.update();
 
+        // Verify on_fsm_added was registered
+        let log = app.world().resource::<EventLog>();
+        assert_eq!(log.enters, vec![TestState::A]);
+
+        // Verify apply_state_request was registered
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
-
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
 
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
-        let log = app.world().resource::<EventLog>();
-        assert_eq!(log.exits, vec![TestState::A]);
-        assert_eq!(log.enters, vec![TestState::B]);
     }
 
     #[test]
-    fn guard_blocks_invalid_transitions() {
+    fn plugin_detection_ignores_groups_created_without_the_plugin() {
         let mut app = App::new();
-        app.add_plugins(MinimalPlugins);
-        app.init_resource::<EventLog>();
-        app.world_mut()
-            .add_observer(apply_state_request::<TestState>);
+        app.add_fsm_observer::<TestState, _, _, _>(on_enter);
+        assert!(fsm_group::<TestState>(app.world()).is_some());
+        assert!(!fsm_plugin_added::<TestState>(app.world()));
 
-        let e = app.world_mut().spawn(TestState::A).id();
+        app.add_plugins(FSMPlugin::<TestState>::default());
+        assert!(fsm_plugin_added::<TestState>(app.world()));
+    }
 
-        app.world_mut()
-            .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+    #[test]
+    fn validation_calls_the_fsm_state_hook() {
+        #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum GateFSM {
+            Shut,
+            Raised,
+        }
 
-        app.update();
+        impl FSMTransition for GateFSM {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+        }
 
-        assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::A);
+        // Only gates with a name may be raised
+        impl FSMState for GateFSM {
+            fn can_transition_ctx(world: &World, entity: Entity, _: Self, _: Self) -> bool {
+                world.get::<Name>(entity).is_some()
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<GateFSM>::default());
+        let plain = app.world_mut().spawn(GateFSM::Shut).id();
+        let named = app
+            .world_mut()
+            .spawn((GateFSM::Shut, Name::new("portcullis")))
+            .id();
+        app.world_mut().flush();
+        assert_eq!(
+            validate::<GateFSM>(app.world(), plain, GateFSM::Raised),
+            TransitionVerdict::DeniedByRules
+        );
+        assert!(validate::<GateFSM>(app.world(), named, GateFSM::Raised).is_allowed());
     }
 
     #[test]
-    fn generic_transition_events_fire() {
+    #[should_panic(expected = "FSMPlugin::<TestState> was already added")]
+    fn building_the_plugin_twice_panics() {
         let mut app = App::new();
-        app.add_plugins(MinimalPlugins);
-        app.init_resource::<EventLog>();
+        app.add_plugins(FSMPlugin::<TestState>::default());
+        FSMPlugin::<TestState>::minimal().build(&mut app);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "StateChangeRequest<TestState> was triggered without a target entity"
+    )]
+    fn untargeted_requests_panic_in_debug_builds() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<TestState>::default());
         app.world_mut()
-            .add_observer(apply_state_request::<TestState>);
-        app.world_mut().add_observer(on_transition);
+            .trigger(StateChangeRequest::from(TestState::B));
+        app.world_mut().flush();
+    }
 
-        let e = app.world_mut().spawn(TestState::A).id();
+    #[test]
+    fn linked_fsms_follow_each_other() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<TestState>::default(),
+            FSMPlugin::<PluginTestState>::default(),
+        ));
+        app.fsm_link(TestState::B, PluginTestState::Active)
+            .fsm_link(PluginTestState::Active, TestState::C);
+        let linked = app
+            .world_mut()
+            .spawn((TestState::A, PluginTestState::Initial))
+            .id();
+        let unlinked = app.world_mut().spawn(TestState::A).id();
 
-        // Transition A -> B
-        app.world_mut()
-            .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+        for entity in [linked, unlinked] {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(entity, TestState::B));
+        }
         app.update();
 
-        let log = app.world().resource::<EventLog>();
-        assert_eq!(log.transitions, vec![(TestState::A, TestState::B)]);
+        // B -> Active, which in turn requests C
+        assert_eq!(
+            app.world().get::<PluginTestState>(linked),
+            Some(&PluginTestState::Active)
+        );
+        assert_eq!(app.world().get::<TestState>(linked), Some(&TestState::C));
+        assert_eq!(app.world().get::<TestState>(unlinked), Some(&TestState::B));
+        assert!(fsm_observers::<TestState>(app.world())
+            .iter()
+            .any(|(_, name)| name == "fsm_link<TestState, PluginTestState>"));
+    }
+
+    #[derive(EntityEvent)]
+    struct Poke {
+        entity: Entity,
+        hard: bool,
     }
 
     #[test]
-    fn on_fsm_added_fires_initial_enter_events() {
+    fn mapped_events_request_transitions_on_their_target() {
         let mut app = App::new();
-        app.add_plugins(MinimalPlugins);
-        app.init_resource::<EventLog>();
-        app.world_mut()
-            .add_observer(apply_state_request::<TestState>);
-        app.world_mut().add_observer(on_fsm_added::<TestState>);
-        app.world_mut().add_observer(on_enter);
+        app.add_plugins(FSMPlugin::<TestState>::default());
+        app.fsm_on_event::<Poke, TestState>(|poke| {
+            if poke.hard {
+                TestState::C
+            } else {
+                TestState::B
+            }
+        });
+        let soft = app.world_mut().spawn(TestState::A).id();
+        let hard = app.world_mut().spawn(TestState::A).id();
+        let rock = app.world_mut().spawn_empty().id();
 
-        let _e = app.world_mut().spawn(TestState::A).id();
+        app.world_mut().trigger(Poke {
+            entity: soft,
+            hard: false,
+        });
+        app.world_mut().trigger(Poke {
+            entity: hard,
+            hard: true,
+        });
+        app.world_mut().trigger(Poke {
+            entity: rock,
+            hard: true,
+        });
         app.update();
 
-        let log = app.world().resource::<EventLog>();
-        assert_eq!(log.enters, vec![TestState::A]);
+        assert_eq!(app.world().get::<TestState>(soft), Some(&TestState::B));
+        // A -> C is not allowed
+        assert_eq!(app.world().get::<TestState>(hard), Some(&TestState::A));
+        assert_eq!(app.world().get::<TestState>(rock), None);
+    }
+
+    #[test]
+    fn plugin_names_the_fsm_and_sets_its_verbosity() {
+        let mut app = App::new();
+        // Group created under the short type name before the plugin is added
+        fsm_observer!(app, PluginTestState, on_plugin_enter);
+        assert_eq!(fsm_name::<PluginTestState>(app.world()), "PluginTestState");
+        assert_eq!(
+            fsm_verbosity::<PluginTestState>(app.world()),
+            FSMVerbosity::Events
+        );
+
+        app.add_plugins(
+            FSMPlugin::<PluginTestState>::default()
+                .with_name("tests::PluginTestState")
+                .with_verbosity(FSMVerbosity::Denials),
+        );
+        let world = app.world();
+        let group = fsm_group::<PluginTestState>(world).unwrap();
+        assert_eq!(
+            fsm_groups(world),
+            vec![(group, "tests::PluginTestState".to_string())]
+        );
+        assert_eq!(
+            fsm_verbosity::<PluginTestState>(world),
+            FSMVerbosity::Denials
+        );
+    }
+
+    mod menu {
+        use bevy::prelude::*;
+
+        #[derive(Component, Reflect, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+        pub enum GameState {
+            Title,
+        }
+
+        impl crate::FSMState for GameState {}
+
+        impl crate::FSMTransition for GameState {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+        }
+    }
+
+    mod overworld {
+        use bevy::prelude::*;
+
+        #[derive(Component, Reflect, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+        pub enum GameState {
+            Exploring,
+        }
+
+        impl crate::FSMState for GameState {}
+
+        impl crate::FSMTransition for GameState {
+            fn can_transition(_: Self, _: Self) -> bool {
+                true
+            }
+        }
+    }
+
+    #[test]
+    fn same_named_fsm_types_get_distinct_groups() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<menu::GameState>::default());
+        assert_eq!(fsm_name::<menu::GameState>(app.world()), "GameState");
+        app.add_plugins(FSMPlugin::<overworld::GameState>::default());
+
+        let world = app.world();
+        let names: Vec<_> = fsm_groups(world)
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "bevy_fsm::tests::menu::GameState",
+                "bevy_fsm::tests::overworld::GameState"
+            ]
+        );
+        assert_eq!(
+            fsm_name::<menu::GameState>(world),
+            "bevy_fsm::tests::menu::GameState"
+        );
+        let group = fsm_group_of(world, TypeId::of::<overworld::GameState>());
+        assert!(group.is_some());
+        assert_eq!(group, fsm_group::<overworld::GameState>(world));
+    }
+
+    #[test]
+    fn hierarchy_inspection_lists_groups_and_observers() {
+        let mut app = App::new();
+        assert_eq!(fsm_root(app.world()), None);
+        app.add_plugins(FSMPlugin::<PluginTestState>::default());
+        fsm_observer!(app, PluginTestState, on_plugin_enter);
+
+        let world = app.world();
+        let root = fsm_root(world).unwrap();
+        let group = fsm_group::<PluginTestState>(world).unwrap();
+        assert_eq!(world.get::<ChildOf>(group).unwrap().parent(), root);
+        assert_eq!(fsm_group::<TestState>(world), None);
+        assert_eq!(
+            fsm_groups(world),
+            vec![(group, "PluginTestState".to_string())]
+        );
+
+        let names: Vec<_> = fsm_observers::<PluginTestState>(world)
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "apply_state_request",
+                "on_fsm_added",
+                "on_fsm_removed",
+                "on_fsm_cleanup",
+                "on_plugin_enter"
+            ]
+        );
+    }
+
+    #[test]
+    fn app_extension_adds_functions_and_closures_to_the_group() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<PluginTestState>::default());
+        app.init_resource::<PluginEventLog>();
+        app.add_fsm_observer::<PluginTestState, _, _, _>(on_plugin_enter)
+            .add_fsm_observer::<PluginTestState, _, _, _>(
+                |exit: On<Exit<PluginTestState>>, mut log: ResMut<PluginEventLog>| {
+                    log.enters.push(exit.event().state);
+                },
+            );
+
+        let names: Vec<_> = fsm_observers::<PluginTestState>(app.world())
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(&names[4..], ["on_plugin_enter", "{{closure}}"]);
+
+        let e = app.world_mut().spawn(PluginTestState::Initial).id();
+        app.world_mut().flush();
+        app.world_mut()
+            .resource_mut::<PluginEventLog>()
+            .enters
+            .clear();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, PluginTestState::Active));
+        app.world_mut().flush();
+        // The closure logs the exited state, the function the entered one
+        assert_eq!(
+            app.world().resource::<PluginEventLog>().enters,
+            vec![PluginTestState::Initial, PluginTestState::Active]
+        );
+    }
+
+    #[test]
+    fn observers_can_be_removed_and_restored() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<PluginTestState>::default());
+        app.add_plugins(FSMPlugin::<LampFSM>::default());
+        app.init_resource::<PluginEventLog>();
+        app.init_resource::<LampEnters>();
+        fsm_observer!(app, PluginTestState, on_plugin_enter);
+
+        assert_eq!(
+            remove_fsm_observer::<PluginTestState>(app.world_mut(), "on_plugin_enter"),
+            1
+        );
+        let e = app.world_mut().spawn(PluginTestState::Initial).id();
+        app.world_mut().flush();
+        assert!(app.world().resource::<PluginEventLog>().enters.is_empty());
+
+        // Without its group the FSM ignores requests...
+        assert!(despawn_fsm_group::<PluginTestState>(app.world_mut()));
+        assert_eq!(fsm_group::<PluginTestState>(app.world()), None);
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, PluginTestState::Active));
+        app.world_mut().flush();
+        assert_eq!(
+            *app.world().get::<PluginTestState>(e).unwrap(),
+            PluginTestState::Initial
+        );
+
+        // ...until the core observers are restored
+        assert!(restore_fsm_core_observers::<PluginTestState>(
+            app.world_mut()
+        ));
+        assert!(!restore_fsm_core_observers::<PluginTestState>(
+            app.world_mut()
+        ));
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, PluginTestState::Active));
+        app.world_mut().flush();
+        assert_eq!(
+            *app.world().get::<PluginTestState>(e).unwrap(),
+            PluginTestState::Active
+        );
+
+        // Entry actions are restored with the core observers
+        let lamp = app.world_mut().spawn(LampFSM::Off).id();
+        app.world_mut().flush();
+        assert_eq!(app.world().resource::<LampEnters>().0, 1);
+        assert!(despawn_fsm_group::<LampFSM>(app.world_mut()));
+        assert!(restore_fsm_core_observers::<LampFSM>(app.world_mut()));
+        app.world_mut()
+            .trigger(StateChangeRequest::new(lamp, LampFSM::On));
+        app.world_mut().flush();
+        assert_eq!(*app.world().get::<LampFSM>(lamp).unwrap(), LampFSM::On);
+        assert_eq!(app.world().resource::<LampEnters>().0, 2);
     }
 
-    // Additional tests without variant-specific events (to avoid proc macro issues in test modules)
     #[test]
-    fn fsm_plugin_registers_core_observers_with_teststate() {
+    fn observers_can_be_replaced_in_place() {
         let mut app = App::new();
-        app.add_plugins(MinimalPlugins);
-        app.init_resource::<EventLog>();
-        // Note: TestState doesn't have FSMState derive, so can't use FSMPlugin
-        // This tests the manual approach still works
-        app.world_mut()
-            .add_observer(apply_state_request::<TestState>);
-        app.world_mut().add_observer(on_fsm_added::<TestState>);
-        app.world_mut().add_observer(on_enter);
+        app.add_plugins(FSMPlugin::<PluginTestState>::default());
+        app.init_resource::<PluginEventLog>();
+        fn on_plugin_exit(_: On<Exit<PluginTestState>>) {}
+        fsm_observer!(app, PluginTestState, on_plugin_enter);
+        fsm_observer!(app, PluginTestState, on_plugin_exit);
+        let names = |app: &App| -> Vec<String> {
+            fsm_observers::<PluginTestState>(app.world())
+                .into_iter()
+                .map(|(_, name)| name)
+                .collect()
+        };
+        let before = names(&app);
+
+        replace_fsm_observer::<PluginTestState, _, _>(
+            app.world_mut(),
+            "on_plugin_enter",
+            |enter: On<Enter<PluginTestState>>, mut log: ResMut<PluginEventLog>| {
+                // Log every state twice to tell the replacement apart
+                log.enters.extend([enter.state, enter.state]);
+            },
+        );
+        assert_eq!(names(&app), before);
 
-        let e = app.world_mut().spawn(TestState::A).id();
-        app.update();
+        app.world_mut().spawn(PluginTestState::Initial);
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().resource::<PluginEventLog>().enters,
+            vec![PluginTestState::Initial; 2]
+        );
+    }
 
-        // Verify on_fsm_added was registered
-        let log = app.world().resource::<EventLog>();
-        assert_eq!(log.enters, vec![TestState::A]);
+    #[test]
+    fn observers_can_be_disabled_and_enabled() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<PluginTestState>::default());
+        app.init_resource::<PluginEventLog>();
+        fsm_observer!(app, PluginTestState, on_plugin_enter);
+        let toggle = |app: &mut App, enabled| {
+            set_fsm_observer_enabled::<PluginTestState>(app.world_mut(), "on_plugin_enter", enabled)
+        };
 
-        // Verify apply_state_request was registered
-        app.world_mut()
-            .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
-        app.update();
+        assert_eq!(toggle(&mut app, false), 1);
+        // Disabling twice keeps the detached observer
+        assert_eq!(toggle(&mut app, false), 1);
+        let e = app.world_mut().spawn(PluginTestState::Initial).id();
+        app.world_mut().flush();
+        assert!(app.world().resource::<PluginEventLog>().enters.is_empty());
+        assert_eq!(fsm_observers::<PluginTestState>(app.world()).len(), 5);
 
-        assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
+        assert_eq!(toggle(&mut app, true), 1);
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, PluginTestState::Active));
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().resource::<PluginEventLog>().enters,
+            vec![PluginTestState::Active]
+        );
     }
 
     #[test]
@@ -1235,10 +3866,7 @@ mod tests {
 
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         println!("✓ StateChangeRequest triggered: A -> B");
 
         app.update();
@@ -1416,10 +4044,7 @@ mod tests {
         // Whitelisted transition should succeed
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
 
@@ -1429,10 +4054,7 @@ mod tests {
         // A->C is whitelisted, should succeed even though FSMTransition would block it
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1463,10 +4085,7 @@ mod tests {
         // A->C: ON whitelist, should ACCEPT (config wins, FSMTransition NOT checked)
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1480,10 +4099,7 @@ mod tests {
         // A->B: NOT on whitelist, check FSMTransition (allows it)
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1494,10 +4110,7 @@ mod tests {
         // B->C: NOT on whitelist, check FSMTransition (allows it)
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1524,10 +4137,7 @@ mod tests {
         // Non-blacklisted transition should succeed (even if FSMTransition would block it)
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
 
@@ -1537,10 +4147,7 @@ mod tests {
         // Blacklisted transition should fail
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1549,6 +4156,476 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_reports_verdict_without_applying() {
+        let mut world = World::new();
+        let plain = world.spawn(TestState::A).id();
+        let locked = world
+            .spawn((TestState::A, FSMOverride::<TestState>::deny_all()))
+            .id();
+        let empty = world.spawn_empty().id();
+
+        assert_eq!(
+            validate(&world, plain, TestState::B),
+            TransitionVerdict::Allowed
+        );
+        assert_eq!(
+            validate(&world, plain, TestState::C),
+            TransitionVerdict::DeniedByRules
+        );
+        assert_eq!(
+            validate(&world, plain, TestState::A),
+            TransitionVerdict::Unchanged
+        );
+        assert_eq!(
+            validate(&world, locked, TestState::B),
+            TransitionVerdict::DeniedByOverride
+        );
+        assert_eq!(
+            validate(&world, empty, TestState::B),
+            TransitionVerdict::NoFSM
+        );
+        assert_eq!(*world.get::<TestState>(plain).unwrap(), TestState::A);
+    }
+
+    #[derive(Component, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    enum CombatState {
+        Idle,
+        Casting,
+        Dead,
+    }
+
+    impl FSMState for CombatState {}
+
+    impl FSMTransition for CombatState {
+        fn can_transition(_from: Self, _to: Self) -> bool {
+            true
+        }
+
+        fn min_interrupt_priority(state: Self) -> u32 {
+            match state {
+                CombatState::Casting => 5,
+                _ => 0,
+            }
+        }
+    }
+
+    #[test]
+    fn interrupt_priority_gates_leaving_a_state() {
+        let mut world = World::new();
+        world.add_observer(apply_state_request::<CombatState>);
+        let e = world.spawn(CombatState::Casting).id();
+
+        world.trigger(StateChangeRequest::new(e, CombatState::Idle).with_priority(4));
+        world.flush();
+        assert_eq!(*world.get::<CombatState>(e).unwrap(), CombatState::Casting);
+        assert_eq!(
+            validate(&world, e, CombatState::Idle),
+            TransitionVerdict::DeniedByPriority
+        );
+        assert_eq!(
+            validate_with_priority(&world, e, CombatState::Dead, 5),
+            TransitionVerdict::Allowed
+        );
+
+        world.trigger(StateChangeRequest::new(e, CombatState::Dead).with_priority(10));
+        world.flush();
+        assert_eq!(*world.get::<CombatState>(e).unwrap(), CombatState::Dead);
+    }
+
+    #[test]
+    fn paused_fsm_denies_or_buffers_requests() {
+        let mut world = World::new();
+        world.add_observer(apply_state_request::<TestState>);
+        let dropped = world
+            .spawn((TestState::A, FSMOverride::<TestState>::allow_all()))
+            .insert(FSMPaused::<TestState>::new())
+            .id();
+        let buffered = world
+            .spawn((TestState::A, FSMPaused::<TestState>::buffered()))
+            .id();
+
+        for entity in [dropped, buffered] {
+            world.trigger(StateChangeRequest::new(entity, TestState::B));
+        }
+        world.flush();
+        assert_eq!(*world.get::<TestState>(dropped).unwrap(), TestState::A);
+        assert_eq!(*world.get::<TestState>(buffered).unwrap(), TestState::A);
+        assert_eq!(
+            world
+                .get::<FSMPaused<TestState>>(buffered)
+                .unwrap()
+                .queued()[0]
+                .next,
+            TestState::B
+        );
+        assert_eq!(
+            validate(&world, dropped, TestState::B),
+            TransitionVerdict::Paused
+        );
+
+        world.entity_mut(dropped).remove::<FSMPaused<TestState>>();
+        world.entity_mut(buffered).remove::<FSMPaused<TestState>>();
+        world.flush();
+        assert_eq!(*world.get::<TestState>(dropped).unwrap(), TestState::A);
+        assert_eq!(*world.get::<TestState>(buffered).unwrap(), TestState::B);
+    }
+
+    #[test]
+    fn pausing_again_keeps_queued_requests_and_despawn_drops_them() {
+        #[derive(Resource, Default)]
+        struct Entered(Vec<Entity>);
+
+        let mut world = World::new();
+        world.init_resource::<Entered>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(
+            |enter: On<Enter<TestState>>, mut entered: ResMut<Entered>| {
+                entered.0.push(enter.entity);
+            },
+        );
+        let paused = || FSMPaused::<TestState>::buffered();
+        let repaused = world.spawn((TestState::A, paused())).id();
+        let despawned = world.spawn((TestState::A, paused())).id();
+        let ticket = world
+            .commands()
+            .entity(repaused)
+            .request_fsm_tracked(StateChangeRequest::to(TestState::B));
+        world.trigger(StateChangeRequest::new(despawned, TestState::B));
+        world.flush();
+
+        world.entity_mut(repaused).insert(paused());
+        world.flush();
+        assert_eq!(
+            world
+                .get::<FSMPaused<TestState>>(repaused)
+                .unwrap()
+                .queued()
+                .len(),
+            1
+        );
+
+        world.despawn(despawned);
+        world.entity_mut(repaused).remove::<FSMPaused<TestState>>();
+        world.flush();
+        assert_eq!(*world.get::<TestState>(repaused).unwrap(), TestState::B);
+        assert_eq!(ticket.status(), TicketStatus::Applied);
+        assert_eq!(world.resource::<Entered>().0, vec![repaused]);
+    }
+
+    #[test]
+    fn despawn_during_transition_aborts_remaining_phases() {
+        #[derive(Resource, Default)]
+        struct Log(Vec<String>);
+
+        #[derive(Resource)]
+        struct DespawnIn(TransitionPhase);
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(
+            |t: On<Exit<TestState>>,
+             mut log: ResMut<Log>,
+             at: Res<DespawnIn>,
+             mut commands: Commands| {
+                log.0.push(format!("exit {:?}", t.event().state));
+                if at.0 == TransitionPhase::Exit {
+                    commands.entity(t.event().entity).despawn();
+                }
+            },
+        );
+        world.add_observer(
+            |t: On<Transition<TestState, TestState>>,
+             at: Res<DespawnIn>,
+             mut commands: Commands| {
+                if at.0 == TransitionPhase::Transition {
+                    commands.entity(t.event().entity).despawn();
+                }
+            },
+        );
+        world.add_observer(|t: On<Enter<TestState>>, mut log: ResMut<Log>| {
+            log.0.push(format!("enter {:?}", t.event().state));
+        });
+        world.add_observer(
+            |t: On<TransitionAborted<TestState>>, mut log: ResMut<Log>| {
+                log.0.push(format!("aborted {:?}", t.event().phase));
+            },
+        );
+
+        for phase in [TransitionPhase::Exit, TransitionPhase::Transition] {
+            world.insert_resource(DespawnIn(phase));
+            let entity = world.spawn(TestState::A).id();
+            world.flush();
+            world.resource_mut::<Log>().0.clear();
+
+            world.trigger(StateChangeRequest::new(entity, TestState::B));
+            world.flush();
+
+            assert!(world.get_entity(entity).is_err());
+            assert_eq!(
+                world.resource::<Log>().0,
+                vec!["exit A".to_string(), format!("aborted {phase:?}")]
+            );
+        }
+    }
+
+    #[test]
+    fn nested_requests_apply_after_the_current_transition() {
+        let mut world = World::new();
+        world.init_resource::<EventLog>();
+        world.add_observer(apply_state_request::<TestState>);
+        world.add_observer(on_enter);
+        world.add_observer(on_exit);
+        // A -> C is invalid, B -> C is not: the nested request must see B
+        world.add_observer(|t: On<Exit<TestState>>, mut commands: Commands| {
+            if t.event().state == TestState::A {
+                commands.trigger(StateChangeRequest::new(t.event().entity, TestState::C));
+            }
+        });
+        world.add_observer(|t: On<Exit<TestState>>, world: &World| {
+            let entity = t.event().entity;
+            if t.event().state == TestState::A {
+                assert_eq!(
+                    world.get::<TransitioningTo<TestState>>(entity),
+                    Some(&TransitioningTo(TestState::B))
+                );
+                assert_eq!(
+                    effective_state::<TestState>(world, entity),
+                    Some(TestState::B)
+                );
+                assert!(validate(world, entity, TestState::C).is_allowed());
+            }
+        });
+        let entity = world.spawn(TestState::A).id();
+
+        world.trigger(StateChangeRequest::new(entity, TestState::B));
+        world.flush();
+
+        assert_eq!(*world.get::<TestState>(entity).unwrap(), TestState::C);
+        assert!(world.get::<TransitioningTo<TestState>>(entity).is_none());
+        let log = world.resource::<EventLog>();
+        assert_eq!(log.exits, vec![TestState::A, TestState::B]);
+        assert_eq!(log.enters, vec![TestState::B, TestState::C]);
+    }
+
+    #[test]
+    fn triple_chained_requests_in_one_frame_apply_in_order() {
+        use TestState::*;
+
+        // A -> C is invalid, so validating against the state at the start of the frame
+        // would deny the second request
+        for (plugin, events) in [
+            (FSMPlugin::<TestState>::default(), true),
+            (FSMPlugin::<TestState>::buffered(), true),
+            (FSMPlugin::<TestState>::batched(), false),
+        ] {
+            let mut app = App::new();
+            app.add_plugins(plugin);
+            app.init_resource::<EventLog>();
+            app.add_observer(on_enter);
+            let entity = app.world_mut().spawn(A).id();
+            app.world_mut().flush();
+            app.world_mut().resource_mut::<EventLog>().enters.clear();
+
+            let mut commands = app.world_mut().commands();
+            for next in [B, C, A] {
+                commands.trigger(StateChangeRequest::new(entity, next));
+            }
+            app.update();
+
+            assert_eq!(*app.world().get::<TestState>(entity).unwrap(), A);
+            if events {
+                assert_eq!(app.world().resource::<EventLog>().enters, vec![B, C, A]);
+            }
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct OverrideSeen(Vec<(Entity, bool)>);
+
+    /// App recording whether initial Enter observers see the entity's `FSMOverride`.
+    fn override_visibility_app(initial_enter: InitialEnter) -> App {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<TestState>::default().with_initial_enter(initial_enter));
+        app.init_resource::<OverrideSeen>();
+        app.add_observer(
+            |enter: On<Enter<TestState>>,
+             q_override: Query<Has<FSMOverride<TestState>>>,
+             mut seen: ResMut<OverrideSeen>| {
+                let has_override = q_override.get(enter.entity).unwrap_or_default();
+                seen.0.push((enter.entity, has_override));
+            },
+        );
+        app
+    }
+
+    #[test]
+    fn initial_enter_sees_the_spawning_bundle_or_is_deferred() {
+        let whitelist = || FSMOverride::whitelist([(TestState::A, TestState::B)]);
+
+        let mut app = override_visibility_app(InitialEnter::OnAdd);
+        let bundle = app.world_mut().spawn((TestState::A, whitelist())).id();
+        let mut commands = app.world_mut().commands();
+        let separate = commands.spawn(TestState::A).insert(whitelist()).id();
+        app.update();
+        assert_eq!(
+            app.world().resource::<OverrideSeen>().0,
+            vec![(bundle, true), (separate, false)]
+        );
+
+        let mut app = override_visibility_app(InitialEnter::Deferred);
+        let mut commands = app.world_mut().commands();
+        let separate = commands.spawn(TestState::A).insert(whitelist()).id();
+        let moved = commands.spawn(TestState::A).id();
+        app.world_mut().flush();
+        assert!(app.world().resource::<OverrideSeen>().0.is_empty());
+        // A transition before the deferred Enter replaces it
+        app.world_mut()
+            .trigger(StateChangeRequest::new(moved, TestState::B));
+        app.update();
+        assert_eq!(
+            app.world().resource::<OverrideSeen>().0,
+            vec![(moved, false), (separate, true)]
+        );
+        app.update();
+        assert_eq!(app.world().resource::<OverrideSeen>().0.len(), 2);
+    }
+
+    #[test]
+    fn once_per_frame_chaining_denies_further_transitions() {
+        use TestState::*;
+
+        let mut app = App::new();
+        app.add_plugins(
+            FSMPlugin::<TestState>::default().with_request_chaining(RequestChaining::OncePerFrame),
+        );
+        let entity = app.world_mut().spawn(A).id();
+        app.world_mut().flush();
+
+        let mut commands = app.world_mut().commands();
+        for next in [B, C, A] {
+            commands.trigger(StateChangeRequest::new(entity, next));
+        }
+        app.world_mut().flush();
+        assert_eq!(*app.world().get::<TestState>(entity).unwrap(), B);
+        assert_eq!(
+            validate(app.world(), entity, C),
+            TransitionVerdict::AlreadyTransitioned
+        );
+
+        // StateChanged is cleared at the start of the next frame
+        app.update();
+        app.world_mut().trigger(StateChangeRequest::new(entity, C));
+        app.world_mut().flush();
+        assert_eq!(*app.world().get::<TestState>(entity).unwrap(), C);
+    }
+
+    #[test]
+    fn removal_and_despawn_fire_exit_for_last_state() {
+        let mut world = World::new();
+        world.init_resource::<EventLog>();
+        world.add_observer(on_fsm_removed::<TestState>);
+        world.add_observer(on_exit);
+
+        let removed = world.spawn(TestState::B).id();
+        world.entity_mut(removed).remove::<TestState>();
+        let despawned = world.spawn(TestState::C).id();
+        world.despawn(despawned);
+        world.flush();
+
+        assert_eq!(
+            world.resource::<EventLog>().exits,
+            vec![TestState::B, TestState::C]
+        );
+    }
+
+    #[test]
+    fn require_on_inserts_initial_state() {
+        #[derive(Component)]
+        struct Worker;
+
+        let mut app = App::new();
+        app.init_resource::<PluginEventLog>();
+        app.add_plugins(
+            FSMPlugin::<PluginTestState>::default().require_on::<Worker>(PluginTestState::Active),
+        );
+        fsm_observer!(app, PluginTestState, on_plugin_enter);
+
+        let worker = app.world_mut().spawn(Worker).id();
+        let explicit = app.world_mut().spawn((Worker, PluginTestState::Done)).id();
+        app.world_mut().flush();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<PluginTestState>(worker),
+            Some(&PluginTestState::Active)
+        );
+        assert_eq!(
+            world.get::<PluginTestState>(explicit),
+            Some(&PluginTestState::Done)
+        );
+        assert_eq!(
+            world.resource::<PluginEventLog>().enters,
+            vec![PluginTestState::Active, PluginTestState::Done]
+        );
+    }
+
+    #[test]
+    fn require_on_keeps_initial_state_per_app() {
+        #[derive(Component)]
+        struct Worker;
+
+        let app_with = |state| {
+            let mut app = App::new();
+            app.add_plugins(FSMPlugin::<PluginTestState>::default().require_on::<Worker>(state));
+            app
+        };
+        let mut active = app_with(PluginTestState::Active);
+        let mut done = app_with(PluginTestState::Done);
+
+        let in_active = active.world_mut().spawn(Worker).id();
+        let in_done = done.world_mut().spawn(Worker).id();
+
+        assert_eq!(
+            active.world().get::<PluginTestState>(in_active),
+            Some(&PluginTestState::Active)
+        );
+        assert_eq!(
+            done.world().get::<PluginTestState>(in_done),
+            Some(&PluginTestState::Done)
+        );
+    }
+
+    #[test]
+    fn state_changed_marker_lasts_one_frame() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(FSMPlugin::<PluginTestState>::default());
+        let entity = app.world_mut().spawn(PluginTestState::Initial).id();
+        app.update();
+        assert!(app
+            .world()
+            .get::<StateChanged<PluginTestState>>(entity)
+            .is_none());
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(entity, PluginTestState::Active));
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().get::<StateChanged<PluginTestState>>(entity),
+            Some(&StateChanged {
+                from: PluginTestState::Initial
+            })
+        );
+
+        app.update();
+        assert!(app
+            .world()
+            .get::<StateChanged<PluginTestState>>(entity)
+            .is_none());
+    }
+
     #[test]
     fn fsm_config_blacklist_with_rules() {
         let mut app = App::new();
@@ -1568,10 +4645,7 @@ mod tests {
         // A->C: not blacklisted, but FSMTransition blocks it
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1582,20 +4656,14 @@ mod tests {
         // A->B: allowed by both blacklist and FSMTransition
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
 
         // B->C: blacklisted, should fail even though FSMTransition would allow it
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1619,10 +4687,7 @@ mod tests {
         // All transitions should be denied
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::A);
     }
@@ -1642,10 +4707,7 @@ mod tests {
         // Without call_rules, FSMTransition is bypassed - ALL transitions allowed
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
 
@@ -1655,10 +4717,7 @@ mod tests {
         // Even invalid transition (A->C) is allowed because FSMTransition is not checked
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1667,6 +4726,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fsm_config_state_locks_match_either_endpoint() {
+        use TestState::*;
+
+        let mut world = World::new();
+        let e = world
+            .spawn((A, FSMOverride::<TestState>::deny_exiting([B])))
+            .id();
+        assert_eq!(validate(&world, e, B), TransitionVerdict::Allowed);
+        // Rules still apply to unlisted transitions
+        assert_eq!(validate(&world, e, C), TransitionVerdict::DeniedByRules);
+
+        world.entity_mut(e).insert(B);
+        assert_eq!(validate(&world, e, A), TransitionVerdict::DeniedByOverride);
+        assert_eq!(validate(&world, e, C), TransitionVerdict::DeniedByOverride);
+
+        let mut config = FSMOverride::<TestState>::deny_entering([C]);
+        assert!(!config.is_transition_allowed(A, C));
+        assert!(!config.is_transition_allowed(B, C));
+        config.add_whitelist([(A, B)]);
+        config.clear();
+        assert!(config.is_transition_allowed(B, C));
+    }
+
+    #[test]
+    fn fsm_config_wildcard_edges() {
+        use TestState::*;
+
+        let mut world = World::new();
+        // A -> C is denied by the rules, but the wildcard whitelist bypasses them
+        let e = world
+            .spawn((A, FSMOverride::whitelist([(AnyState, C)]).with_rules()))
+            .id();
+        assert_eq!(validate(&world, e, C), TransitionVerdict::Allowed);
+
+        let config = FSMOverride::blacklist([FSMEdge::Exiting(B), FSMEdge::Exact(A, C)]);
+        assert!(!config.is_transition_allowed(B, A));
+        assert!(!config.is_transition_allowed(B, C));
+        assert!(!config.is_transition_allowed(A, C));
+        assert!(config.is_transition_allowed(A, B));
+        assert_eq!(config.edges().count(), 2);
+
+        let mut merged = FSMOverride::<TestState>::allow_all();
+        merged.merge(&config);
+        assert!(!merged.is_transition_allowed(B, A));
+
+        assert!(merged.remove((B, AnyState)));
+        assert!(!merged.remove((B, AnyState)));
+        assert!(merged.is_transition_allowed(B, A));
+        assert!(!merged.is_transition_allowed(A, C));
+    }
+
+    #[test]
+    fn type_rules_apply_to_entities_without_override() {
+        use TestState::*;
+
+        let mut world = World::new();
+        let plain = world.spawn(A).id();
+        let custom = world
+            .spawn((A, FSMOverride::<TestState>::allow_all().with_rules()))
+            .id();
+        world.insert_resource(FSMTypeRules::new(FSMOverride::<TestState>::deny_all()));
+
+        assert_eq!(
+            validate(&world, plain, B),
+            TransitionVerdict::DeniedByOverride
+        );
+        assert_eq!(validate(&world, custom, B), TransitionVerdict::Allowed);
+
+        world
+            .resource_mut::<FSMTypeRules<TestState>>()
+            .add_whitelist([(A, B)]);
+        assert_eq!(validate(&world, plain, B), TransitionVerdict::Allowed);
+    }
+
+    #[test]
+    fn fsm_config_runtime_mutation() {
+        use TestState::*;
+
+        let mut config = FSMOverride::<TestState>::deny_all();
+        config.add_whitelist([(A, B)]);
+        assert_eq!(config.mode, RuleType::Whitelist);
+        assert!(config.is_transition_allowed(A, B));
+        assert!(!config.is_transition_allowed(B, C));
+
+        config.add_blacklist([(A, B)]);
+        assert!(!config.is_transition_allowed(A, B));
+        assert!(!config.remove((A, B)));
+
+        let mut config = FSMOverride::<TestState>::allow_all();
+        config.add_blacklist([(A, C)]);
+        assert_eq!(config.mode, RuleType::Blacklist);
+        config.merge(&FSMOverride::whitelist([(A, C)]));
+        assert!(config.is_transition_allowed(A, C));
+        config.merge(&FSMOverride::blacklist([(B, C)]));
+        assert!(!config.is_transition_allowed(B, C));
+        assert!(config.remove((B, C)));
+        config = config.and_deny_entering([C]);
+        assert!(!config.remove((B, C)));
+        assert!(!config.is_transition_allowed(B, C));
+
+        config.set_mode(RuleType::Whitelist).clear();
+        assert!(!config.is_transition_allowed(A, B));
+        config.merge(&FSMOverride::deny_all());
+        assert_eq!(config.mode, RuleType::None);
+    }
+
     #[test]
     fn fsm_config_allow_all_with_rules() {
         let mut app = App::new();
@@ -1685,10 +4851,7 @@ mod tests {
         // Valid transition should succeed
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::B,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::B));
         app.update();
         assert_eq!(*app.world().get::<TestState>(e).unwrap(), TestState::B);
 
@@ -1698,10 +4861,7 @@ mod tests {
         // Invalid transition (A->C) should be blocked by FSMTransition
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<TestState> {
-                entity: e,
-                next: TestState::C,
-            });
+            .trigger(StateChangeRequest::<TestState>::new(e, TestState::C));
         app.update();
         assert_eq!(
             *app.world().get::<TestState>(e).unwrap(),
@@ -1740,6 +4900,32 @@ mod tests {
         log.enters.push(trigger.event().state);
     }
 
+    // FSM with an entry action, as `#[fsm(on_enter = count_lamp_enters)]` would add it
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LampFSM {
+        Off,
+        On,
+    }
+
+    impl FSMState for LampFSM {
+        fn add_actions(world: &mut World) {
+            actions::add_action::<Self, Enter<Self>, _>(world, count_lamp_enters);
+        }
+    }
+
+    impl FSMTransition for LampFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct LampEnters(usize);
+
+    fn count_lamp_enters(mut enters: ResMut<LampEnters>) {
+        enters.0 += 1;
+    }
+
     #[test]
     fn fsm_plugin_fires_initial_enter_event() {
         let mut app = App::new();
@@ -1769,10 +4955,10 @@ mod tests {
         // Verify that state transitions still work
         app.world_mut()
             .commands()
-            .trigger(StateChangeRequest::<PluginTestState> {
+            .trigger(StateChangeRequest::<PluginTestState>::new(
                 entity,
-                next: PluginTestState::Active,
-            });
+                PluginTestState::Active,
+            ));
         app.update();
 
         assert_eq!(
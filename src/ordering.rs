@@ -0,0 +1,322 @@
+//! Observers running in a declared order.
+//!
+//! Bevy runs the observers of an event in no particular order. Observers registered with an
+//! order, through [`fsm_observer!`](crate::fsm_observer) or
+//! [`FSMAppExt::add_ordered_fsm_observer`](crate::FSMAppExt::add_ordered_fsm_observer), run
+//! one after the other by ascending [`FSMObserverOrder`], ties in registration order:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{fsm_observer, Enter, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum LifeFSM { Alive, Dying }
+//! fn drop_loot(_: On<Enter<life_fsm::Dying>>) {}
+//! fn log_death(_: On<Enter<life_fsm::Dying>>) {}
+//!
+//! # let mut app = App::new();
+//! // The analytics logger sees the loot already dropped
+//! fsm_observer!(app, LifeFSM, log_death, order = 100);
+//! fsm_observer!(app, LifeFSM, drop_loot, order = 0);
+//! ```
+//!
+//! Ordered observers of an event run after its unordered ones, once the commands queued by
+//! those are applied, and before the next event of the transition fires. They are listed in
+//! the observer hierarchy like any other, next to one `ordered_dispatch` observer per event
+//! type that runs them. The event must be `Clone` and triggered with a default trigger, which
+//! holds for all FSM events; propagation controls such as `On::propagate` have no effect.
+
+use bevy::ecs::error::ErrorContext;
+use bevy::ecs::observer::TriggerContext;
+use bevy::ecs::system::{IntoObserverSystem, ObserverSystem, RunSystemError};
+use bevy::prelude::*;
+use core::mem::ManuallyDrop;
+use std::borrow::Cow;
+
+use crate::{attach_observer_to_group, FSMObserverDisabled, FSMObserverMarker};
+
+/// Execution order of an ordered FSM observer; lower values run first.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct FSMObserverOrder(pub i32);
+
+/// System of an ordered observer, run by the `ordered_dispatch` observer of its event.
+#[derive(Component)]
+struct OrderedObserver<E: Event> {
+    /// Taken out while the system runs
+    system: Option<Box<dyn ObserverSystem<E, ()>>>,
+    registration: u64,
+}
+
+/// Number of ordered observers registered in the world, breaking ties between equal orders.
+#[derive(Resource, Default)]
+struct OrderedRegistrations(u64);
+
+/// Marks the observer running the ordered observers of `E`.
+#[derive(Component)]
+struct OrderedDispatch<E>(core::marker::PhantomData<E>);
+
+/// Adds `observer` under the hierarchy group of `S`, run in `order` among the ordered
+/// observers of its event.
+///
+/// This is used by the `fsm_observer!` macro but needs to be public for the macro to work
+/// across crate boundaries.
+#[doc(hidden)]
+pub fn add_ordered_observer<S, E, M>(
+    world: &mut World,
+    name: impl Into<Cow<'static, str>>,
+    order: i32,
+    observer: impl IntoObserverSystem<E, (), M>,
+) -> Entity
+where
+    S: Send + Sync + 'static,
+    E: Event + Clone,
+    for<'a> E::Trigger<'a>: Default,
+{
+    let mut registrations = world.get_resource_or_init::<OrderedRegistrations>();
+    let registration = registrations.0;
+    registrations.0 += 1;
+    spawn_ordered::<S, E, M>(world, name, order, registration, observer)
+}
+
+/// Place of an ordered observer among the others: its order, and its registration if it
+/// observes `E`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OrderedPlace {
+    order: i32,
+    registration: Option<u64>,
+}
+
+/// The place of `entity` among the ordered observers, or `None` if it isn't one.
+pub(crate) fn ordered_place<E: Event>(world: &World, entity: Entity) -> Option<OrderedPlace> {
+    let FSMObserverOrder(order) = *world.get::<FSMObserverOrder>(entity)?;
+    Some(OrderedPlace {
+        order,
+        registration: world
+            .get::<OrderedObserver<E>>(entity)
+            .map(|observer| observer.registration),
+    })
+}
+
+/// Adds `observer` at `place`, e.g. to replace the observer it was taken from.
+pub(crate) fn add_ordered_observer_at<S, E, M>(
+    world: &mut World,
+    name: impl Into<Cow<'static, str>>,
+    place: OrderedPlace,
+    observer: impl IntoObserverSystem<E, (), M>,
+) -> Entity
+where
+    S: Send + Sync + 'static,
+    E: Event + Clone,
+    for<'a> E::Trigger<'a>: Default,
+{
+    match place.registration {
+        Some(registration) => {
+            spawn_ordered::<S, E, M>(world, name, place.order, registration, observer)
+        }
+        None => add_ordered_observer::<S, E, M>(world, name, place.order, observer),
+    }
+}
+
+/// Spawns the ordered observer under the group of `S`.
+fn spawn_ordered<S, E, M>(
+    world: &mut World,
+    name: impl Into<Cow<'static, str>>,
+    order: i32,
+    registration: u64,
+    observer: impl IntoObserverSystem<E, (), M>,
+) -> Entity
+where
+    S: Send + Sync + 'static,
+    E: Event + Clone,
+    for<'a> E::Trigger<'a>: Default,
+{
+    ensure_dispatch::<S, E>(world);
+    let mut system: Box<dyn ObserverSystem<E, ()>> =
+        Box::new(IntoObserverSystem::into_system(observer));
+    system.initialize(world);
+    let entity = world
+        .spawn((
+            Name::new(name),
+            FSMObserverMarker::<S>::default(),
+            FSMObserverOrder(order),
+            OrderedObserver {
+                system: Some(system),
+                registration,
+            },
+        ))
+        .id();
+    attach_observer_to_group::<S>(world, entity);
+    entity
+}
+
+/// Adds the `ordered_dispatch` observer of `E` to the group of `S`, unless it exists.
+fn ensure_dispatch<S, E>(world: &mut World)
+where
+    S: Send + Sync + 'static,
+    E: Event + Clone,
+    for<'a> E::Trigger<'a>: Default,
+{
+    let mut dispatchers =
+        world.query_filtered::<(), (With<OrderedDispatch<E>>, With<FSMObserverMarker<S>>)>();
+    if dispatchers.iter(world).next().is_some() {
+        return;
+    }
+    let entity = world
+        .add_observer(|event: On<E>, mut commands: Commands| {
+            let event = event.event().clone();
+            commands.queue(move |world: &mut World| run_ordered::<S, E>(world, &event));
+        })
+        .insert((
+            Name::new("ordered_dispatch"),
+            FSMObserverMarker::<S>::default(),
+            OrderedDispatch::<E>(core::marker::PhantomData),
+        ))
+        .id();
+    attach_observer_to_group::<S>(world, entity);
+}
+
+/// Runs the ordered observers of `E` in the group of `S` for `event`.
+fn run_ordered<S, E>(world: &mut World, event: &E)
+where
+    S: Send + Sync + 'static,
+    E: Event + Clone,
+    for<'a> E::Trigger<'a>: Default,
+{
+    let mut query = world.query_filtered::<
+        (Entity, &FSMObserverOrder, &OrderedObserver<E>),
+        (With<FSMObserverMarker<S>>, Without<FSMObserverDisabled>),
+    >();
+    let mut observers: Vec<_> = query
+        .iter(world)
+        .map(|(entity, &order, observer)| (order, observer.registration, entity))
+        .collect();
+    observers.sort_unstable();
+    let context = TriggerContext {
+        event_key: world.register_event_key::<E>(),
+        caller: bevy::ecs::change_detection::MaybeLocation::caller(),
+    };
+    for (_, _, entity) in observers {
+        // Despawned by an observer that ran before
+        let Some(mut system) = world
+            .get_mut::<OrderedObserver<E>>(entity)
+            .and_then(|mut observer| observer.system.take())
+        else {
+            continue;
+        };
+        let mut event = event.clone();
+        // Observer systems take `On<'a, 'a, E>`, borrowing the trigger for as long as its own
+        // lifetime, so the borrow checker can't let it be dropped afterwards. Not dropping it
+        // is a no-op, checked at compile time.
+        const { assert!(!core::mem::needs_drop::<E::Trigger<'static>>()) };
+        let mut trigger = ManuallyDrop::new(E::Trigger::default());
+        let on = On::new(&mut event, entity, &mut *trigger, &context);
+        if let Err(RunSystemError::Failed(err)) = system.run(on, world) {
+            let handler = world.default_error_handler();
+            handler(
+                err,
+                ErrorContext::Observer {
+                    name: system.name(),
+                    last_run: system.get_last_run(),
+                },
+            );
+        }
+        if let Some(mut observer) = world.get_mut::<OrderedObserver<E>>(entity) {
+            observer.system = Some(system);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fsm_observer, Enter, FSMAppExt, FSMPlugin, FSMState, FSMTransition, StateChangeRequest,
+    };
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Dying,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<&'static str>);
+
+    fn drop_loot(_: On<Enter<LifeFSM>>, mut log: ResMut<Log>) {
+        log.0.push("loot");
+    }
+
+    fn log_death(enter: On<Enter<LifeFSM>>, mut log: ResMut<Log>) {
+        if enter.state == LifeFSM::Dying {
+            log.0.push("analytics");
+        }
+    }
+
+    #[test]
+    fn ordered_observers_run_by_ascending_order() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        app.init_resource::<Log>();
+        fsm_observer!(app, LifeFSM, log_death, order = 100);
+        fsm_observer!(app, LifeFSM, drop_loot, order = 0);
+        app.add_ordered_fsm_observer::<LifeFSM, _, _>(
+            0,
+            |_: On<Enter<LifeFSM>>, mut log: ResMut<Log>| log.0.push("sound"),
+        );
+
+        let entity = app.world_mut().spawn(LifeFSM::Alive).id();
+        app.world_mut().resource_mut::<Log>().0.clear();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(entity, LifeFSM::Dying));
+        app.world_mut().flush();
+
+        assert_eq!(
+            app.world().resource::<Log>().0,
+            vec!["loot", "sound", "analytics"]
+        );
+    }
+
+    fn play_sound(_: On<Enter<LifeFSM>>, mut log: ResMut<Log>) {
+        log.0.push("sound");
+    }
+
+    #[test]
+    fn replaced_ordered_observers_keep_their_order() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        app.init_resource::<Log>();
+        fsm_observer!(app, LifeFSM, log_death, order = 100);
+        fsm_observer!(app, LifeFSM, drop_loot, order = 0);
+        fsm_observer!(app, LifeFSM, play_sound, order = 0);
+        let world = app.world_mut();
+        crate::replace_fsm_observer::<LifeFSM, _, _>(
+            world,
+            "log_death",
+            |_: On<Enter<LifeFSM>>, mut log: ResMut<Log>| log.0.push("analytics v2"),
+        );
+        // Still before `play_sound`, registered after it with the same order
+        crate::replace_fsm_observer::<LifeFSM, _, _>(
+            world,
+            "drop_loot",
+            |_: On<Enter<LifeFSM>>, mut log: ResMut<Log>| log.0.push("loot v2"),
+        );
+
+        let entity = world.spawn(LifeFSM::Alive).id();
+        world.resource_mut::<Log>().0.clear();
+        world.trigger(StateChangeRequest::new(entity, LifeFSM::Dying));
+        world.flush();
+
+        assert_eq!(
+            world.resource::<Log>().0,
+            vec!["loot v2", "sound", "analytics v2"]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Orthogonal regions: several FSMs of one entity declared as one logical machine.
+//!
+//! A player often runs independent machines side by side: movement, weapon and health.
+//! [`parallel_fsm!`](crate::parallel_fsm) declares them together as a marker component
+//! implementing [`FSMParallel`], which spawns, removes, pauses and saves all regions at once:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::parallel::FSMParallel;
+//! # use bevy_fsm::{parallel_fsm, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum MovementFSM { Idle, Running }
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum WeaponFSM { Holstered, Drawn }
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum HealthFSM { Alive, Dead }
+//! parallel_fsm!(Player => [MovementFSM, WeaponFSM, HealthFSM]);
+//!
+//! fn spawn_player(mut commands: Commands) {
+//!     commands.spawn(Player::bundle((
+//!         MovementFSM::Idle,
+//!         WeaponFSM::Holstered,
+//!         HealthFSM::Alive,
+//!     )));
+//! }
+//!
+//! fn open_menu(mut commands: Commands, players: Query<Entity, With<Player>>) {
+//!     for player in &players {
+//!         Player::pause(&mut commands.entity(player));
+//!     }
+//! }
+//! ```
+//!
+//! Each region keeps its own `FSMPlugin`, observers and rules; the group only addresses them
+//! together. Up to 8 regions can be declared.
+
+use bevy::prelude::*;
+
+use crate::save::{self, FSMSave};
+use crate::{FSMPaused, FSMState};
+
+/// A tuple of FSM states, one per region of an [`FSMParallel`] machine.
+pub trait FSMRegions: Bundle + Copy {
+    /// The current state of every region, or `None` if the entity misses one.
+    fn get(entity: EntityRef) -> Option<Self>;
+
+    /// Inserts an [`FSMPaused`] for every region.
+    fn pause(entity: &mut EntityCommands, buffered: bool);
+
+    /// Removes the [`FSMPaused`] of every region.
+    fn resume(entity: &mut EntityCommands);
+
+    /// Short type names of the regions, in order.
+    fn names() -> Vec<String>;
+}
+
+macro_rules! impl_fsm_regions {
+    ($($S:ident),+) => {
+        impl<$($S: FSMState),+> FSMRegions for ($($S,)+) {
+            fn get(entity: EntityRef) -> Option<Self> {
+                Some(($(*entity.get::<$S>()?,)+))
+            }
+
+            fn pause(entity: &mut EntityCommands, buffered: bool) {
+                entity.insert(($(
+                    if buffered {
+                        FSMPaused::<$S>::buffered()
+                    } else {
+                        FSMPaused::<$S>::new()
+                    },
+                )+));
+            }
+
+            fn resume(entity: &mut EntityCommands) {
+                entity.remove::<($(FSMPaused<$S>,)+)>();
+            }
+
+            fn names() -> Vec<String> {
+                vec![$(ShortName::of::<$S>().to_string()),+]
+            }
+        }
+    };
+}
+
+impl_fsm_regions!(A);
+impl_fsm_regions!(A, B);
+impl_fsm_regions!(A, B, C);
+impl_fsm_regions!(A, B, C, D);
+impl_fsm_regions!(A, B, C, D, E);
+impl_fsm_regions!(A, B, C, D, E, F);
+impl_fsm_regions!(A, B, C, D, E, F, G);
+impl_fsm_regions!(A, B, C, D, E, F, G, H);
+
+/// Marker component of a machine made of orthogonal regions, declared with
+/// [`parallel_fsm!`](crate::parallel_fsm).
+pub trait FSMParallel: Component + Default {
+    /// The FSM types of the regions.
+    type Regions: FSMRegions;
+
+    /// Name of the machine, e.g. for debug tooling.
+    const NAME: &'static str;
+
+    /// The marker with the initial state of every region, to spawn or insert together.
+    fn bundle(regions: Self::Regions) -> (Self, Self::Regions) {
+        (Self::default(), regions)
+    }
+
+    /// The current state of every region, or `None` if the entity isn't a complete machine.
+    fn states(entity: EntityRef) -> Option<Self::Regions> {
+        Self::Regions::get(entity)
+    }
+
+    /// Removes the marker and every region, firing the regions' `Exit` events.
+    fn remove(entity: &mut EntityCommands) {
+        entity.remove::<(Self, Self::Regions)>();
+    }
+
+    /// Pauses every region, dropping requests (see [`FSMPaused::new`]).
+    fn pause(entity: &mut EntityCommands) {
+        Self::Regions::pause(entity, false);
+    }
+
+    /// Pauses every region, replaying requests on resume (see [`FSMPaused::buffered`]).
+    fn pause_buffered(entity: &mut EntityCommands) {
+        Self::Regions::pause(entity, true);
+    }
+
+    /// Resumes every region.
+    fn resume(entity: &mut EntityCommands) {
+        Self::Regions::resume(entity);
+    }
+
+    /// Short type names of the regions, in order.
+    fn region_names() -> Vec<String> {
+        Self::Regions::names()
+    }
+
+    /// Snapshot of the regions of `entity`, restored with [`save::apply`].
+    fn snapshot(world: &mut World, entity: Entity) -> FSMSave {
+        let names = Self::region_names();
+        let mut save = save::snapshot(world);
+        save.entries
+            .retain(|entry| entry.entity == entity && names.contains(&entry.fsm));
+        save
+    }
+}
+
+/// Declares a marker component grouping FSM types as the orthogonal regions of one machine,
+/// e.g. `parallel_fsm!(pub Player => [MovementFSM, WeaponFSM, HealthFSM])`.
+///
+/// See the [`parallel`](crate::parallel) module.
+#[macro_export]
+macro_rules! parallel_fsm {
+    ($(#[$meta:meta])* $vis:vis $name:ident => [$($region:ty),+ $(,)?]) => {
+        $(#[$meta])*
+        #[derive(::bevy::prelude::Component, Debug, Clone, Copy, Default)]
+        $vis struct $name;
+
+        impl $crate::parallel::FSMParallel for $name {
+            type Regions = ($($region,)+);
+            const NAME: &'static str = stringify!($name);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum MovementFSM {
+        Idle,
+        Running,
+    }
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum WeaponFSM {
+        Holstered,
+        Drawn,
+    }
+
+    impl FSMState for MovementFSM {}
+    impl FSMState for WeaponFSM {}
+
+    impl FSMTransition for MovementFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    impl FSMTransition for WeaponFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    parallel_fsm!(Player => [MovementFSM, WeaponFSM]);
+
+    #[test]
+    fn regions_are_addressed_together() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<MovementFSM>::default(),
+            FSMPlugin::<WeaponFSM>::default(),
+        ));
+        let player = app
+            .world_mut()
+            .spawn(Player::bundle((MovementFSM::Idle, WeaponFSM::Holstered)))
+            .id();
+        app.world_mut().spawn(MovementFSM::Idle);
+        app.update();
+        assert_eq!(Player::NAME, "Player");
+        assert_eq!(Player::region_names(), ["MovementFSM", "WeaponFSM"]);
+        assert_eq!(Player::snapshot(app.world_mut(), player).entries.len(), 2);
+
+        Player::pause(&mut app.world_mut().commands().entity(player));
+        app.world_mut().flush();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(player, MovementFSM::Running));
+        app.world_mut()
+            .trigger(StateChangeRequest::new(player, WeaponFSM::Drawn));
+        app.update();
+        let states = |app: &App| Player::states(app.world().entity(player));
+        assert_eq!(
+            states(&app),
+            Some((MovementFSM::Idle, WeaponFSM::Holstered))
+        );
+
+        Player::resume(&mut app.world_mut().commands().entity(player));
+        app.world_mut().flush();
+        app.world_mut()
+            .trigger(StateChangeRequest::new(player, WeaponFSM::Drawn));
+        app.update();
+        assert_eq!(states(&app), Some((MovementFSM::Idle, WeaponFSM::Drawn)));
+
+        Player::remove(&mut app.world_mut().commands().entity(player));
+        app.world_mut().flush();
+        assert_eq!(states(&app), None);
+        assert!(!app.world().entity(player).contains::<Player>());
+    }
+}
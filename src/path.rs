@@ -0,0 +1,135 @@
+//! Multi-step transitions along the declared transition graph.
+//!
+//! [`FSMEntityCommandsExt::request_fsm_path`](crate::FSMEntityCommandsExt::request_fsm_path)
+//! plans the shortest legal path from the entity's current state (see [`FSMGraph`]) and
+//! requests every step at once; each step fires its regular Exit/Transition/Enter events.
+//! [`request_fsm_path_stepped`](crate::FSMEntityCommandsExt::request_fsm_path_stepped) inserts
+//! an [`FSMPath`] instead, advancing one step per frame:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMEntityCommandsExt, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum LifeFSM { Alive, Dying, Dead }
+//! fn scripted_death(mut commands: Commands, npc: Entity) {
+//!     // Alive -> Dying -> Dead, one transition per frame
+//!     commands.entity(npc).request_fsm_path_stepped(LifeFSM::Dead);
+//! }
+//! ```
+//!
+//! Steps are regular requests and go through full validation. A denied step is retried on
+//! the next frame; remove the [`FSMPath`] to cancel.
+
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+
+use crate::graph::FSMGraph;
+use crate::{FSMPaused, FSMState, StateChangeRequest};
+
+/// Moves the entity towards `target` one transition per frame.
+///
+/// Removed once the target is reached or no longer reachable from the current state.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FSMPath<S: FSMState> {
+    /// The state to reach.
+    pub target: S,
+}
+
+impl<S: FSMState> FSMPath<S> {
+    /// Path towards `target`.
+    pub fn new(target: S) -> Self {
+        Self { target }
+    }
+}
+
+/// System requesting the next step of every [`FSMPath`], replanning from the current state.
+/// Paused FSMs don't step.
+#[allow(clippy::type_complexity)]
+pub fn advance_fsm_paths<S: FSMState + core::hash::Hash + FromReflect + Typed>(
+    mut commands: Commands,
+    paths: Query<(Entity, &S, &FSMPath<S>), Without<FSMPaused<S>>>,
+) {
+    for (entity, &cur, path) in &paths {
+        match S::plan_path(cur, path.target) {
+            Some(steps) if !steps.is_empty() => {
+                commands.trigger(StateChangeRequest::new(entity, steps[0]));
+            }
+            // Arrived, or the target can't be reached anymore
+            _ => {
+                commands.entity(entity).remove::<FSMPath<S>>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMEntityCommandsExt, FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Dying,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (LifeFSM::Alive, LifeFSM::Dying) | (LifeFSM::Dying, LifeFSM::Dead)
+            )
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Entered(Vec<LifeFSM>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        app.init_resource::<Entered>();
+        app.add_observer(|enter: On<Enter<LifeFSM>>, mut entered: ResMut<Entered>| {
+            entered.0.push(enter.event().state);
+        });
+        app
+    }
+
+    #[test]
+    fn path_requests_every_step_at_once() {
+        let mut app = app();
+        let npc = app.world_mut().spawn(LifeFSM::Alive).id();
+        app.world_mut()
+            .commands()
+            .entity(npc)
+            .request_fsm_path(LifeFSM::Dead);
+        app.world_mut().flush();
+
+        assert_eq!(*app.world().get::<LifeFSM>(npc).unwrap(), LifeFSM::Dead);
+        assert_eq!(
+            app.world().resource::<Entered>().0,
+            vec![LifeFSM::Alive, LifeFSM::Dying, LifeFSM::Dead]
+        );
+    }
+
+    #[test]
+    fn stepped_path_advances_once_per_frame() {
+        let mut app = app();
+        let npc = app.world_mut().spawn(LifeFSM::Alive).id();
+        app.world_mut()
+            .commands()
+            .entity(npc)
+            .request_fsm_path_stepped(LifeFSM::Dead);
+
+        app.update();
+        assert_eq!(*app.world().get::<LifeFSM>(npc).unwrap(), LifeFSM::Dying);
+        app.update();
+        assert_eq!(*app.world().get::<LifeFSM>(npc).unwrap(), LifeFSM::Dead);
+        app.update();
+        assert!(app.world().get::<FSMPath<LifeFSM>>(npc).is_none());
+    }
+}
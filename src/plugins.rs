@@ -0,0 +1,146 @@
+//! Registering many FSM types at once.
+//!
+//! Projects with dozens of FSM types end up with a wall of `add_plugins` lines. [`FSMPlugins`]
+//! collects them into one plugin group, so the whole list lives in one place:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMPlugin, FSMPlugins, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum LifeFSM { Alive, Dead }
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum WeaponFSM { Idle, Firing }
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum DoorFSM { Open, Closed }
+//! # let mut app = App::new();
+//! app.add_plugins(
+//!     FSMPlugins::new()
+//!         .with::<LifeFSM>()
+//!         .with::<WeaponFSM>()
+//!         // Configured plugins are added as they are
+//!         .with_plugin(FSMPlugin::<DoorFSM>::buffered()),
+//! );
+//! ```
+//!
+//! Each entry adds a regular [`FSMPlugin`], so `FSMPlugins` behaves like any other plugin
+//! group: entries can be replaced with `.set(...)` or left out with `.disable::<FSMPlugin<S>>()`
+//! after calling `build()`. Listing a type twice keeps the last entry. Shared infrastructure
+//! (the `FSMObservers` hierarchy root) is set up once by whichever plugin is built first.
+
+use bevy::app::{PluginGroup, PluginGroupBuilder};
+use bevy::prelude::*;
+use bevy::reflect::{GetTypeRegistration, Typed};
+
+use crate::{FSMPlugin, FSMState};
+
+type AddPlugin = Box<dyn FnOnce(PluginGroupBuilder) -> PluginGroupBuilder + Send + Sync>;
+
+/// Plugin group adding an [`FSMPlugin`] for each listed FSM type.
+#[derive(Default)]
+pub struct FSMPlugins {
+    plugins: Vec<AddPlugin>,
+}
+
+impl FSMPlugins {
+    /// An empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `FSMPlugin::<S>::default()`.
+    #[must_use]
+    pub fn with<S>(self) -> Self
+    where
+        S: FSMState
+            + core::hash::Hash
+            + Component
+            + Reflect
+            + FromReflect
+            + Typed
+            + GetTypeRegistration,
+    {
+        self.with_plugin(FSMPlugin::<S>::default())
+    }
+
+    /// Add a configured `plugin`, e.g. `FSMPlugin::<S>::buffered()`.
+    #[must_use]
+    pub fn with_plugin<S>(mut self, plugin: FSMPlugin<S>) -> Self
+    where
+        S: FSMState
+            + core::hash::Hash
+            + Component
+            + Reflect
+            + FromReflect
+            + Typed
+            + GetTypeRegistration,
+    {
+        self.plugins.push(Box::new(move |group| group.add(plugin)));
+        self
+    }
+}
+
+impl PluginGroup for FSMPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        self.plugins
+            .into_iter()
+            .fold(PluginGroupBuilder::start::<Self>(), |group, add| add(group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fsm_groups, fsm_plugin_added, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+    }
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Open,
+    }
+
+    impl FSMState for LifeFSM {}
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn group_adds_a_plugin_per_type() {
+        let mut app = App::new();
+        app.add_plugins(
+            FSMPlugins::new()
+                .with::<LifeFSM>()
+                .with_plugin(FSMPlugin::<DoorFSM>::buffered())
+                // The last entry for a type wins
+                .with::<DoorFSM>(),
+        );
+
+        app.world_mut().spawn((LifeFSM::Alive, DoorFSM::Open));
+        app.update();
+
+        assert!(fsm_plugin_added::<LifeFSM>(app.world()));
+        assert!(fsm_plugin_added::<DoorFSM>(app.world()));
+        assert!(!app
+            .world()
+            .contains_resource::<Messages<crate::StateChangeRequest<DoorFSM>>>());
+        let groups: Vec<String> = fsm_groups(app.world())
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(groups, vec!["DoorFSM", "LifeFSM"]);
+    }
+}
@@ -0,0 +1,306 @@
+//! Number of entities in each state, kept up to date without scanning entities.
+//!
+//! [`FSMPopulationPlugin`] maintains an [`FSMPopulation`] resource from the lifecycle of the
+//! `S` component: every insert (spawn, transition, direct overwrite) counts the new state and
+//! every replace or removal (transition, despawn) uncounts the old one. Spawners and AI
+//! directors read it in O(1):
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::population::{FSMPopulation, FSMPopulationPlugin};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum EnemyFSM { Idle, Aggro }
+//! fn director(population: Res<FSMPopulation<EnemyFSM>>) {
+//!     if population.count(EnemyFSM::Aggro) < 5 {
+//!         // let another enemy attack
+//!     }
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins((FSMPlugin::<EnemyFSM>::default(), FSMPopulationPlugin::<EnemyFSM>::default()))
+//!     .add_systems(Update, director);
+//! ```
+//!
+//! # State caps
+//!
+//! [`FSMPlugin::with_state_cap`](crate::FSMPlugin::with_state_cap) limits how many entities
+//! may be in a state at once. Transitions into a full state are denied with
+//! [`TransitionVerdict::DeniedByCap`](crate::TransitionVerdict::DeniedByCap) and fire
+//! `TransitionDenied` like any other denial. Entities still transitioning into the state count
+//! towards its cap. The caps live in the [`FSMStateCaps`] resource and can be changed at
+//! runtime.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::hash::Hash;
+
+use crate::{FSMState, TransitioningTo};
+
+/// How many entities are currently in each state of `S`.
+#[derive(Resource, Debug, Clone)]
+pub struct FSMPopulation<S: FSMState + Hash> {
+    counts: HashMap<S, usize>,
+}
+
+impl<S: FSMState + Hash> Default for FSMPopulation<S> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::default(),
+        }
+    }
+}
+
+impl<S: FSMState + Hash> FSMPopulation<S> {
+    /// Number of entities in `state`.
+    pub fn count(&self, state: S) -> usize {
+        self.counts.get(&state).copied().unwrap_or_default()
+    }
+
+    /// Number of entities carrying `S`.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Occupied states with their counts, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (S, usize)> + '_ {
+        self.counts.iter().map(|(&state, &count)| (state, count))
+    }
+
+    fn add(&mut self, state: S) {
+        *self.counts.entry(state).or_default() += 1;
+    }
+
+    fn remove(&mut self, state: S) {
+        if let Some(count) = self.counts.get_mut(&state) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&state);
+            }
+        }
+    }
+}
+
+/// Maximum number of entities per state of `S`, checked when validating transitions.
+#[derive(Resource, Debug, Clone)]
+pub struct FSMStateCaps<S: FSMState + Hash> {
+    caps: HashMap<S, usize>,
+}
+
+impl<S: FSMState + Hash> Default for FSMStateCaps<S> {
+    fn default() -> Self {
+        Self {
+            caps: HashMap::default(),
+        }
+    }
+}
+
+impl<S: FSMState + Hash> FSMStateCaps<S> {
+    /// Allows at most `cap` entities in `state`, replacing any previous cap.
+    pub fn set(&mut self, state: S, cap: usize) -> &mut Self {
+        self.caps.insert(state, cap);
+        self
+    }
+
+    /// Lifts the cap of `state`, returning it.
+    pub fn remove(&mut self, state: S) -> Option<usize> {
+        self.caps.remove(&state)
+    }
+
+    /// Cap of `state`, if any.
+    pub fn get(&self, state: S) -> Option<usize> {
+        self.caps.get(&state).copied()
+    }
+}
+
+/// Whether `state` is capped and already holds (or is receiving) as many entities as allowed.
+pub(crate) fn is_full<S: FSMState + Hash>(world: &World, state: S) -> bool {
+    let Some(cap) = world
+        .get_resource::<FSMStateCaps<S>>()
+        .and_then(|caps| caps.get(state))
+    else {
+        return false;
+    };
+    let settled = world
+        .get_resource::<FSMPopulation<S>>()
+        .map_or(0, |population| population.count(state));
+    // Transitions into `state` that have not inserted it yet
+    let incoming = world
+        .try_query::<(&S, &TransitioningTo<S>)>()
+        .map_or(0, |mut query| {
+            query
+                .iter(world)
+                .filter(|(&cur, target)| target.0 == state && cur != state)
+                .count()
+        });
+    settled + incoming >= cap
+}
+
+/// Plugin maintaining [`FSMPopulation<S>`].
+///
+/// Entities already carrying `S` when the plugin is added are counted on build.
+pub struct FSMPopulationPlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMPopulationPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: FSMState + Hash> Plugin for FSMPopulationPlugin<S> {
+    fn build(&self, app: &mut App) {
+        let world = app.world_mut();
+        let mut population = FSMPopulation::<S>::default();
+        for &state in world.query::<&S>().iter(world) {
+            population.add(state);
+        }
+        app.insert_resource(population)
+            .add_observer(count_inserted_state::<S>)
+            .add_observer(uncount_replaced_state::<S>);
+    }
+}
+
+/// Observer counting the state of `S` just inserted on an entity.
+pub fn count_inserted_state<S: FSMState + Hash>(
+    trigger: On<Insert, S>,
+    states: Query<&S>,
+    mut population: ResMut<FSMPopulation<S>>,
+) {
+    if let Ok(&state) = states.get(trigger.entity) {
+        population.add(state);
+    }
+}
+
+/// Observer uncounting the state of `S` about to be replaced or removed.
+pub fn uncount_replaced_state<S: FSMState + Hash>(
+    trigger: On<Replace, S>,
+    states: Query<&S>,
+    mut population: ResMut<FSMPopulation<S>>,
+) {
+    if let Ok(&state) = states.get(trigger.entity) {
+        population.remove(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        validate, Exit, FSMPlugin, FSMTransition, StateChangeRequest, TransitionDenied,
+        TransitionVerdict,
+    };
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum EnemyFSM {
+        Idle,
+        Aggro,
+        Dead,
+    }
+
+    impl FSMState for EnemyFSM {}
+
+    impl FSMTransition for EnemyFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn population_follows_spawns_transitions_and_despawns() {
+        use EnemyFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<EnemyFSM>::default());
+        let early = app.world_mut().spawn(Idle).id();
+        app.add_plugins(FSMPopulationPlugin::<EnemyFSM>::default());
+        let enemies: Vec<_> = (0..3).map(|_| app.world_mut().spawn(Idle).id()).collect();
+        let population = |app: &App, state| {
+            app.world()
+                .resource::<FSMPopulation<EnemyFSM>>()
+                .count(state)
+        };
+        assert_eq!(population(&app, Idle), 4);
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(enemies[0], Aggro));
+        app.world_mut().flush();
+        app.world_mut().entity_mut(enemies[1]).insert(Aggro);
+        assert_eq!(population(&app, Idle), 2);
+        assert_eq!(population(&app, Aggro), 2);
+
+        app.world_mut().despawn(enemies[0]);
+        app.world_mut().entity_mut(early).remove::<EnemyFSM>();
+        let resource = app.world().resource::<FSMPopulation<EnemyFSM>>();
+        assert_eq!(resource.count(Dead), 0);
+        assert_eq!(resource.total(), 2);
+        assert_eq!((resource.count(Idle), resource.count(Aggro)), (1, 1));
+        assert_eq!(resource.iter().count(), 2);
+    }
+
+    #[derive(Resource, Default)]
+    struct Denied(usize);
+
+    #[test]
+    fn capped_states_deny_transitions_when_full() {
+        use EnemyFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<EnemyFSM>::default().with_state_cap(Aggro, 2));
+        app.init_resource::<Denied>();
+        app.add_observer(
+            |_: On<TransitionDenied<EnemyFSM>>, mut denied: ResMut<Denied>| denied.0 += 1,
+        );
+        let enemies: Vec<_> = (0..4).map(|_| app.world_mut().spawn(Idle).id()).collect();
+
+        for &enemy in &enemies {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(enemy, Aggro));
+            app.world_mut().flush();
+        }
+        let population = app.world().resource::<FSMPopulation<EnemyFSM>>();
+        assert_eq!(population.count(Aggro), 2);
+        assert_eq!(app.world().resource::<Denied>().0, 2);
+        assert_eq!(
+            validate(app.world(), enemies[3], Aggro),
+            TransitionVerdict::DeniedByCap
+        );
+
+        // A slot frees up once an aggroed enemy leaves the state
+        app.world_mut().despawn(enemies[0]);
+        assert!(validate(app.world(), enemies[3], Aggro).is_allowed());
+        app.world_mut()
+            .resource_mut::<FSMStateCaps<EnemyFSM>>()
+            .set(Aggro, 1);
+        assert_eq!(
+            validate(app.world(), enemies[3], Aggro),
+            TransitionVerdict::DeniedByCap
+        );
+    }
+
+    #[test]
+    fn transitions_in_flight_count_towards_the_cap() {
+        use EnemyFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<EnemyFSM>::default().with_state_cap(Aggro, 1));
+        let leader = app.world_mut().spawn(Idle).id();
+        let follower = app.world_mut().spawn(Idle).id();
+        // The follower tries to join while the leader is still exiting Idle
+        app.add_observer(move |exit: On<Exit<EnemyFSM>>, mut commands: Commands| {
+            if exit.event().entity == leader {
+                commands.trigger(StateChangeRequest::new(follower, Aggro));
+            }
+        });
+        app.world_mut()
+            .trigger(StateChangeRequest::new(leader, Aggro));
+        app.world_mut().flush();
+
+        assert_eq!(*app.world().get::<EnemyFSM>(leader).unwrap(), Aggro);
+        assert_eq!(*app.world().get::<EnemyFSM>(follower).unwrap(), Idle);
+    }
+}
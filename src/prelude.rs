@@ -0,0 +1,36 @@
+//! The types most FSM code needs, for a single glob import.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_fsm::prelude::*;
+//!
+//! #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum LifeFSM {
+//!     Alive,
+//!     Dying,
+//! }
+//!
+//! fn plugin(app: &mut App) {
+//!     app.add_plugins(FSMPlugin::<LifeFSM>::default());
+//!     fsm_observer!(app, LifeFSM, on_enter_dying);
+//! }
+//!
+//! fn on_enter_dying(enter: On<Enter<life_fsm::Dying>>, mut commands: Commands) {
+//!     commands.entity(enter.entity).request_fsm_state(LifeFSM::Alive);
+//! }
+//! ```
+//!
+//! It holds the derives and traits, the plugin and its app extension, the events, the
+//! per-entity configuration (overrides, pausing, timers) and the command and world
+//! extensions. Specialised modules such as [`graph`](crate::graph), [`save`](crate::save) or
+//! `test_utils` stay out; import them by path.
+
+pub use crate::fsm_observer;
+pub use crate::{
+    effective_state, validate, validate_with_priority, AnyTransition, ChildPropagation, DenyReason,
+    Enter, EnumEvent, Exit, FSMAppExt, FSMCleanup, FSMClock, FSMCommandsExt, FSMEntityCommandsExt,
+    FSMEntityExt, FSMHistory, FSMOverride, FSMPaused, FSMPlugin, FSMPlugins, FSMRemoved, FSMState,
+    FSMSystems, FSMTimer, FSMTransition, FSMWorldExt, GuardCtx, RetryPolicy, RuleType,
+    StateChangeRequest, StateChanged, TicketStatus, Transition, TransitionDenied, TransitionTicket,
+    TransitionVerdict, FSM,
+};
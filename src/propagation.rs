@@ -0,0 +1,247 @@
+//! Propagating transitions from parent entities to their children, and bubbling FSM events
+//! from children up to their ancestors.
+//!
+//! With [`FSMPlugin::with_child_propagation`](crate::FSMPlugin::with_child_propagation), every
+//! applied transition of a parent is requested on its direct children carrying the same FSM
+//! type, e.g. a squad ordering its members to retreat. Children propagate to their own
+//! children in turn:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{ChildPropagation, FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum SquadFSM { Patrol, Retreat }
+//! # let mut app = App::new();
+//! // Members finish retreating before the squad's own Exit/Enter events fire
+//! app.add_plugins(
+//!     FSMPlugin::<SquadFSM>::default().with_child_propagation(ChildPropagation::BeforeParent),
+//! );
+//! ```
+//!
+//! Propagated requests carry the parent as their source and are validated like any other
+//! request; a child that denies the transition keeps its state and fires `TransitionDenied`.
+//!
+//! In the other direction, [`FSMPlugin::with_event_bubbling`](crate::FSMPlugin::with_event_bubbling)
+//! lets the `Enter`, `Exit` and `Transition` events of `S` bubble up the `ChildOf` hierarchy
+//! with Bevy's event propagation, e.g. a character reacting when its weapon breaks. Each
+//! ancestor's entity-scoped observers see the event with `entity` set to that ancestor;
+//! `On::original_event_target` is the entity that transitioned, and `On::propagate(false)`
+//! stops the bubbling. Global observers run again for every ancestor, so compare both when
+//! they should only react once. Variant-specific events (`Enter<module::Variant>`) don't
+//! bubble.
+
+use bevy::ecs::event::PropagateEntityTrigger;
+use bevy::prelude::*;
+
+use crate::{apply_request, FSMState, StateChangeRequest};
+
+/// Trigger of the FSM events that can bubble up the hierarchy.
+type Bubbling<E> = PropagateEntityTrigger<false, E, &'static ChildOf>;
+
+/// When a parent's transition is propagated to its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChildPropagation {
+    /// Children transition before the parent fires Exit, so the parent's observers see the
+    /// children already in the new state.
+    BeforeParent,
+    /// Children transition after the parent fired Enter.
+    AfterParent,
+}
+
+/// Propagation configured by the `FSMPlugin` of `S`.
+#[derive(Resource)]
+pub(crate) struct PropagateToChildren<S> {
+    pub(crate) order: ChildPropagation,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> PropagateToChildren<S> {
+    pub(crate) fn new(order: ChildPropagation) -> Self {
+        Self {
+            order,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Whether transitions of `S` propagate to children in the given `order`.
+pub(crate) fn propagates<S: Send + Sync + 'static>(world: &World, order: ChildPropagation) -> bool {
+    world
+        .get_resource::<PropagateToChildren<S>>()
+        .is_some_and(|propagation| propagation.order == order)
+}
+
+/// Marks `S` as bubbling its events, set by the `FSMPlugin` of `S`.
+#[derive(Resource)]
+pub(crate) struct BubbleEvents<S>(std::marker::PhantomData<S>);
+
+impl<S> Default for BubbleEvents<S> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+/// Triggers an FSM event of `S`, bubbling it up to the ancestors if enabled.
+pub(crate) fn trigger_event<S: Send + Sync + 'static, E>(world: &mut World, event: E)
+where
+    E: EntityEvent + for<'a> Event<Trigger<'a> = Bubbling<E>>,
+{
+    let mut trigger = Bubbling::<E>::default();
+    trigger.propagate = world.contains_resource::<BubbleEvents<S>>();
+    world.trigger_with(event, trigger);
+}
+
+/// Queues [`trigger_event`] on `commands`.
+pub(crate) fn queue_event<S: Send + Sync + 'static, E>(commands: &mut Commands, event: E)
+where
+    E: EntityEvent + for<'a> Event<Trigger<'a> = Bubbling<E>>,
+{
+    commands.queue(move |world: &mut World| trigger_event::<S, E>(world, event));
+}
+
+/// Whether `event` reached an ancestor of the entity it was triggered for.
+///
+/// The crate's own global observers skip those, as they act on the transitioning entity.
+pub(crate) fn bubbled<E, B: Bundle>(event: &On<E, B>) -> bool
+where
+    E: EntityEvent + for<'a> Event<Trigger<'a> = Bubbling<E>>,
+{
+    event.original_event_target() != event.event_target()
+}
+
+/// Requests `next` on the direct children of `parent` carrying `S`.
+pub(crate) fn propagate<S: FSMState + core::hash::Hash>(
+    world: &mut World,
+    parent: Entity,
+    next: S,
+) {
+    let Some(children) = world.get::<Children>(parent) else {
+        return;
+    };
+    let children: Vec<Entity> = children
+        .iter()
+        .filter(|&child| world.get::<S>(child).is_some())
+        .collect();
+    for child in children {
+        apply_request(
+            world,
+            StateChangeRequest::new(child, next).with_source(parent),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, Exit, FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum SquadFSM {
+        Patrol,
+        Retreat,
+    }
+
+    impl FSMState for SquadFSM {}
+
+    impl FSMTransition for SquadFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<(Entity, &'static str)>);
+
+    fn squad(order: ChildPropagation) -> (App, Entity, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<SquadFSM>::default().with_child_propagation(order));
+        app.init_resource::<Log>();
+        app.add_observer(|exit: On<Exit<SquadFSM>>, mut log: ResMut<Log>| {
+            log.0.push((exit.event().entity, "exit"));
+        });
+        app.add_observer(|enter: On<Enter<SquadFSM>>, mut log: ResMut<Log>| {
+            log.0.push((enter.event().entity, "enter"));
+        });
+        let world = app.world_mut();
+        let member = world.spawn(SquadFSM::Patrol).id();
+        let bystander = world.spawn(Name::new("no FSM")).id();
+        let leader = world
+            .spawn(SquadFSM::Patrol)
+            .add_children(&[member, bystander])
+            .id();
+        world.flush();
+        world.resource_mut::<Log>().0.clear();
+        (app, leader, member, bystander)
+    }
+
+    #[test]
+    fn children_transition_before_or_after_the_parent() {
+        let (mut app, leader, member, _) = squad(ChildPropagation::BeforeParent);
+        app.world_mut()
+            .trigger(StateChangeRequest::new(leader, SquadFSM::Retreat));
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().resource::<Log>().0,
+            vec![
+                (member, "exit"),
+                (member, "enter"),
+                (leader, "exit"),
+                (leader, "enter")
+            ]
+        );
+
+        let (mut app, leader, member, bystander) = squad(ChildPropagation::AfterParent);
+        app.world_mut()
+            .trigger(StateChangeRequest::new(leader, SquadFSM::Retreat));
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().resource::<Log>().0,
+            vec![
+                (leader, "exit"),
+                (leader, "enter"),
+                (member, "exit"),
+                (member, "enter")
+            ]
+        );
+        assert_eq!(
+            *app.world().get::<SquadFSM>(member).unwrap(),
+            SquadFSM::Retreat
+        );
+        assert!(app.world().get::<SquadFSM>(bystander).is_none());
+    }
+
+    #[test]
+    fn events_bubble_to_ancestors_when_enabled() {
+        use crate::FSMEntityCommandsExt;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<SquadFSM>::default().with_event_bubbling());
+        app.init_resource::<Log>();
+        app.add_observer(|enter: On<Enter<SquadFSM>>, mut log: ResMut<Log>| {
+            if !bubbled(&enter) {
+                log.0.push((enter.entity, "global"));
+            }
+        });
+        let member = app.world_mut().spawn(SquadFSM::Patrol).id();
+        let mut commands = app.world_mut().commands();
+        let squad = commands
+            .spawn(Name::new("squad"))
+            .add_child(member)
+            .observe_fsm(|enter: On<Enter<SquadFSM>>, mut log: ResMut<Log>| {
+                log.0.push((enter.original_event_target(), "squad"));
+            })
+            .id();
+        commands.spawn(Name::new("army")).add_child(squad);
+        app.world_mut().flush();
+        app.world_mut().resource_mut::<Log>().0.clear();
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(member, SquadFSM::Retreat));
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().resource::<Log>().0,
+            vec![(member, "global"), (member, "squad")]
+        );
+    }
+}
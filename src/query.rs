@@ -0,0 +1,302 @@
+//! Time in state, state history and a combined query item.
+//!
+//! [`FSMTimer`] tracks how long an entity has been in its current state and [`FSMHistory`]
+//! remembers the states it left. Both are opt-in components, updated by `FSMPlugin` whenever
+//! the entity transitions. [`FSM`] queries the state together with both, so gameplay systems
+//! get a single handle to an entity's machine:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMHistory, FSMState, FSMTimer, FSMTransition, FSM};
+//! # use bevy_enum_event::EnumEvent;
+//! # use core::time::Duration;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum GuardFSM { Patrol, Chase, Search }
+//! fn spawn_guard(mut commands: Commands) {
+//!     commands.spawn((
+//!         GuardFSM::Patrol,
+//!         FSMTimer::<GuardFSM>::default(),
+//!         FSMHistory::<GuardFSM>::new(4),
+//!     ));
+//! }
+//!
+//! fn give_up(mut commands: Commands, guards: Query<(Entity, FSM<GuardFSM>)>) {
+//!     for (guard, fsm) in &guards {
+//!         let searching_long = fsm.is(GuardFSM::Search)
+//!             && fsm.elapsed().is_some_and(|elapsed| elapsed > Duration::from_secs(5));
+//!         if searching_long && fsm.previous() == Some(GuardFSM::Chase) {
+//!             // ...
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Timers advance with the [`FSMClock`](crate::FSMClock) in `Update`, before [`FSMSystems`](crate::FSMSystems),
+//! and restart when the entity enters a new state, before `Enter` fires.
+
+use bevy::ecs::query::QueryData;
+use bevy::prelude::*;
+use core::marker::PhantomData;
+use core::time::Duration;
+use std::collections::VecDeque;
+
+use crate::clock::{FSMTime, FSMTimeScale};
+use crate::{FSMPaused, FSMState};
+
+/// Component tracking how long the entity has been in its current `S` state.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FSMTimer<S: Send + Sync + 'static> {
+    elapsed: Duration,
+    _phantom: PhantomData<S>,
+}
+
+impl<S: Send + Sync + 'static> Default for FSMTimer<S> {
+    fn default() -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: Send + Sync + 'static> FSMTimer<S> {
+    /// Time spent in the current state.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Timer that has already run for `elapsed`, e.g. when restoring a save.
+    pub fn with_elapsed(elapsed: Duration) -> Self {
+        Self {
+            elapsed,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Component remembering the last `S` states the entity left, most recent first.
+#[derive(Component, Debug, Clone)]
+pub struct FSMHistory<S: Copy + Send + Sync + 'static> {
+    states: VecDeque<S>,
+    capacity: usize,
+}
+
+impl<S: Copy + Send + Sync + 'static> Default for FSMHistory<S> {
+    /// History of the last 8 states.
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
+
+impl<S: Copy + Send + Sync + 'static> FSMHistory<S> {
+    /// History of the last `capacity` states.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            states: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// History of the last `capacity` states, holding `states` (most recent first).
+    pub fn with_states(capacity: usize, states: impl IntoIterator<Item = S>) -> Self {
+        let mut history = Self::new(capacity);
+        history.states.extend(states.into_iter().take(capacity));
+        history
+    }
+
+    /// Maximum number of remembered states.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The state left most recently.
+    pub fn previous(&self) -> Option<S> {
+        self.states.front().copied()
+    }
+
+    /// The remembered states, most recent first.
+    pub fn iter(&self) -> impl Iterator<Item = S> + '_ {
+        self.states.iter().copied()
+    }
+
+    /// Number of remembered states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Whether the entity hasn't left a state yet.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Forgets all remembered states.
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+
+    fn push(&mut self, state: S) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.states.truncate(self.capacity - 1);
+        self.states.push_front(state);
+    }
+}
+
+/// Query data for an entity's `S` state with its optional [`FSMTimer`] and [`FSMHistory`].
+#[derive(QueryData)]
+pub struct FSM<S: FSMState> {
+    /// The current state.
+    pub state: &'static S,
+    /// Time in the current state, if tracked.
+    pub timer: Option<&'static FSMTimer<S>>,
+    /// Previous states, if tracked.
+    pub history: Option<&'static FSMHistory<S>>,
+}
+
+impl<S: FSMState> FSMItem<'_, '_, S> {
+    /// The current state.
+    pub fn get(&self) -> S {
+        *self.state
+    }
+
+    /// Whether the entity is in `state`.
+    pub fn is(&self, state: S) -> bool {
+        *self.state == state
+    }
+
+    /// Time spent in the current state, if the entity has an [`FSMTimer`].
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.timer.map(FSMTimer::elapsed)
+    }
+
+    /// The state left most recently, if the entity has an [`FSMHistory`] and left one.
+    pub fn previous(&self) -> Option<S> {
+        self.history.and_then(FSMHistory::previous)
+    }
+}
+
+/// Restarts the timer and extends the history of `entity`, which just left `from`.
+pub(crate) fn record<S: FSMState>(world: &mut World, entity: Entity, from: S) {
+    let Ok(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if let Some(mut timer) = entity.get_mut::<FSMTimer<S>>() {
+        timer.elapsed = Duration::ZERO;
+    }
+    if let Some(mut history) = entity.get_mut::<FSMHistory<S>>() {
+        history.push(from);
+    }
+}
+
+/// System advancing every [`FSMTimer`] of `S` by the delta time of the [`FSMClock`](crate::FSMClock),
+/// scaled by the entity's [`FSMTimeScale`]. Timers of paused FSMs ([`FSMPaused`]) stand still.
+#[allow(clippy::type_complexity)]
+pub fn tick_fsm_timers<S: FSMState>(
+    time: FSMTime<S>,
+    mut timers: Query<(&mut FSMTimer<S>, Option<&FSMTimeScale>), Without<FSMPaused<S>>>,
+) {
+    let Some(delta) = time.delta() else {
+        return;
+    };
+    for (mut timer, scale) in &mut timers {
+        timer.elapsed += scale.map_or(delta, |scale| scale.apply(delta));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum GuardFSM {
+        Patrol,
+        Chase,
+        Search,
+    }
+
+    impl FSMState for GuardFSM {}
+
+    impl FSMTransition for GuardFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn timer_and_history_follow_transitions() {
+        use GuardFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<GuardFSM>::default());
+        app.init_resource::<Time>();
+        let guard = app
+            .world_mut()
+            .spawn((
+                Patrol,
+                FSMTimer::<GuardFSM>::default(),
+                FSMHistory::<GuardFSM>::new(2),
+            ))
+            .id();
+        let plain = app.world_mut().spawn(Patrol).id();
+
+        let advance = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(100));
+            app.update();
+        };
+        advance(&mut app);
+        advance(&mut app);
+        for next in [Chase, Search, Patrol] {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(guard, next));
+        }
+        advance(&mut app);
+
+        let mut fsm = app.world_mut().query::<FSM<GuardFSM>>();
+        let item = fsm.get(app.world(), guard).unwrap();
+        assert!(item.is(Patrol));
+        assert_eq!(item.elapsed(), Some(Duration::from_millis(100)));
+        assert_eq!(item.previous(), Some(Search));
+        // Capped at two states
+        assert_eq!(
+            item.history.unwrap().iter().collect::<Vec<_>>(),
+            vec![Search, Chase]
+        );
+
+        let item = fsm.get(app.world(), plain).unwrap();
+        assert_eq!(item.get(), Patrol);
+        assert_eq!((item.elapsed(), item.previous()), (None, None));
+    }
+
+    #[test]
+    fn timers_stand_still_while_paused() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<GuardFSM>::default());
+        app.init_resource::<Time>();
+        let guard = app
+            .world_mut()
+            .spawn((
+                GuardFSM::Patrol,
+                FSMTimer::<GuardFSM>::default(),
+                FSMPaused::<GuardFSM>::new(),
+            ))
+            .id();
+        let advance = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(100));
+            app.update();
+        };
+        advance(&mut app);
+        app.world_mut()
+            .entity_mut(guard)
+            .remove::<FSMPaused<GuardFSM>>();
+        advance(&mut app);
+
+        let timer = app.world().get::<FSMTimer<GuardFSM>>(guard).unwrap();
+        assert_eq!(timer.elapsed(), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,243 @@
+//! Validating requests with queries instead of exclusive world access.
+//!
+//! By default every [`StateChangeRequest`] queues a command that validates it with `&World`:
+//! the override layers, type-wide rules and the `FSMTransition` rules, guards included. Most
+//! entities use none of that. With
+//! [`FSMPlugin::with_query_validation`](crate::FSMPlugin::with_query_validation), the request
+//! observer validates requests for such entities itself, from a query, and the command only
+//! applies the verdict:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum AgentFSM { Wander, Flee }
+//! # let mut app = App::new();
+//! app.add_plugins(FSMPlugin::<AgentFSM>::default().with_query_validation());
+//! ```
+//!
+//! A request takes the fast path when:
+//!
+//! - the rules don't read the world ([`FSMTransition::READS_WORLD`] is `false`, as derived
+//!   for tables without guards),
+//! - the entity has none of `FSMOverride`, `FSMOverrideStack`, `FSMPaused`, `FSMDebounce`,
+//!   `FSMHysteresis` or `FSMRedirect` for `S` and isn't mid-transition,
+//! - there are no `FSMTypeRules`, `FSMTypeRedirect` or state caps for `S`,
+//! - the request has no retry policy or ticket.
+//!
+//! Everything else goes through the usual World-reading validation, so both paths decide
+//! the same. If the state or any of the above changed between the observer and its command
+//! (e.g. several requests triggered before a flush), the command validates again with the
+//! world.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use core::hash::Hash;
+
+use crate::debounce::FSMDebounce;
+use crate::hysteresis::FSMHysteresis;
+use crate::population::FSMStateCaps;
+use crate::redirect::{FSMRedirect, FSMTypeRedirect};
+use crate::retry::RetryPolicy;
+use crate::stack::FSMOverrideStack;
+use crate::{
+    apply_request, fire_transition, rules_details, FSMCoreObservers, FSMOverride, FSMPaused,
+    FSMState, FSMTransition, FSMTypeRules, PendingTransition, RequestChaining, StateChangeRequest,
+    StateChanged, TransitionDenied, TransitionVerdict,
+};
+
+/// Type-wide resources that opt every entity of `S` out of the fast path.
+#[derive(SystemParam)]
+pub(crate) struct TypeLayers<'w, S: FSMState + Hash> {
+    rules: Option<Res<'w, FSMTypeRules<S>>>,
+    redirect: Option<Res<'w, FSMTypeRedirect<S>>>,
+    caps: Option<Res<'w, FSMStateCaps<S>>>,
+    core: Option<Res<'w, FSMCoreObservers<S>>>,
+}
+
+impl<S: FSMState + Hash> TypeLayers<'_, S> {
+    fn active(&self) -> bool {
+        self.rules.is_some() || self.redirect.is_some() || self.caps.is_some()
+    }
+
+    fn once_per_frame(&self) -> bool {
+        self.core
+            .as_ref()
+            .is_some_and(|core| core.chaining == RequestChaining::OncePerFrame)
+    }
+}
+
+/// Whether the world has type-wide layers for `S`, like [`TypeLayers::active`].
+fn type_layers_active<S: FSMState + Hash>(world: &World) -> bool {
+    world.contains_resource::<FSMTypeRules<S>>()
+        || world.contains_resource::<FSMTypeRedirect<S>>()
+        || world.contains_resource::<FSMStateCaps<S>>()
+}
+
+/// Whether `entity` carries a component that needs the World-reading validation.
+fn entity_layers_active<S: FSMState + Hash>(entity: &EntityRef) -> bool {
+    entity.contains::<FSMOverride<S>>()
+        || entity.contains::<FSMOverrideStack<S>>()
+        || entity.contains::<FSMPaused<S>>()
+        || entity.contains::<FSMDebounce<S>>()
+        || entity.contains::<FSMHysteresis<S>>()
+        || entity.contains::<FSMRedirect<S>>()
+        || entity.contains::<PendingTransition<S>>()
+}
+
+/// Observer validating requests from a query where possible.
+///
+/// Registered instead of [`apply_state_request`](crate::apply_state_request) by
+/// [`FSMPlugin::with_query_validation`](crate::FSMPlugin::with_query_validation).
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn apply_state_request_fast<S: FSMState + Hash>(
+    trigger: On<StateChangeRequest<S>>,
+    entities: Query<(EntityRef, &S)>,
+    layers: TypeLayers<S>,
+    mut commands: Commands,
+) {
+    let request = *trigger.event();
+    let fast = !<S as FSMTransition>::READS_WORLD
+        && request.retry == RetryPolicy::Never
+        && request.ticket.is_none()
+        && !layers.active();
+    let cur = entities
+        .get(request.entity)
+        .ok()
+        .filter(|(entity, _)| fast && !entity_layers_active::<S>(entity))
+        .map(|(entity, &cur)| {
+            let verdict = if cur == request.next {
+                TransitionVerdict::Unchanged
+            } else if layers.once_per_frame() && entity.contains::<StateChanged<S>>() {
+                TransitionVerdict::AlreadyTransitioned
+            } else if request.priority < <S as FSMTransition>::min_interrupt_priority(cur) {
+                TransitionVerdict::DeniedByPriority
+            } else if <S as FSMState>::can_transition(cur, request.next) {
+                TransitionVerdict::Allowed
+            } else {
+                TransitionVerdict::DeniedByRules
+            };
+            (cur, verdict)
+        });
+    commands.queue(move |world: &mut World| match cur {
+        Some((cur, verdict)) => apply_verdict(world, request, cur, verdict),
+        None => {
+            apply_request(world, request);
+        }
+    });
+}
+
+/// Applies a verdict reached by [`apply_state_request_fast`] against `cur`, validating again
+/// with the world if the entity changed since.
+fn apply_verdict<S: FSMState + Hash>(
+    world: &mut World,
+    request: StateChangeRequest<S>,
+    cur: S,
+    verdict: TransitionVerdict,
+) {
+    let StateChangeRequest {
+        entity,
+        next,
+        source,
+        ..
+    } = request;
+    let unchanged = world
+        .get_entity(entity)
+        .is_ok_and(|entity| entity.get::<S>() == Some(&cur) && !entity_layers_active::<S>(&entity))
+        && !type_layers_active::<S>(world);
+    if !unchanged {
+        apply_request(world, request);
+        return;
+    }
+    if verdict == TransitionVerdict::Unchanged {
+        return;
+    }
+    #[cfg(feature = "trace")]
+    crate::trace::decision(world, entity, cur, next, verdict);
+    if verdict == TransitionVerdict::Allowed {
+        fire_transition(world, entity, cur, next, source);
+        return;
+    }
+    let details = rules_details(world, entity, cur, next, verdict);
+    world.trigger(TransitionDenied {
+        entity,
+        from: cur,
+        to: next,
+        reason: verdict,
+        source,
+        details,
+    });
+    world.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Closed,
+        Open,
+        Locked,
+    }
+
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            !matches!((from, to), (DoorFSM::Locked, DoorFSM::Open))
+        }
+
+        const READS_WORLD: bool = false;
+    }
+
+    #[derive(Resource, Default)]
+    struct Log {
+        entered: Vec<DoorFSM>,
+        denied: Vec<(DoorFSM, TransitionVerdict)>,
+    }
+
+    #[test]
+    fn query_validation_decides_like_the_world_path() {
+        use DoorFSM::*;
+
+        let mut app = App::new();
+        app.init_resource::<Log>();
+        app.add_plugins(FSMPlugin::<DoorFSM>::default().with_query_validation());
+        app.add_observer(|enter: On<Enter<DoorFSM>>, mut log: ResMut<Log>| {
+            log.entered.push(enter.state);
+        });
+        app.add_observer(
+            |denied: On<TransitionDenied<DoorFSM>>, mut log: ResMut<Log>| {
+                log.denied.push((denied.to, denied.reason));
+            },
+        );
+        let plain = app.world_mut().spawn(Locked).id();
+        let unlocked = app
+            .world_mut()
+            .spawn((Locked, FSMOverride::whitelist([(Locked, Open)])))
+            .id();
+        app.world_mut().flush();
+
+        let world = app.world_mut();
+        for door in [plain, unlocked] {
+            world.trigger(StateChangeRequest::new(door, Open));
+        }
+        world.flush();
+        assert_eq!(world.get::<DoorFSM>(plain), Some(&Locked));
+        assert_eq!(world.get::<DoorFSM>(unlocked), Some(&Open));
+
+        // Both requests are checked by the observer against `Locked`; the second one is
+        // validated again once the first has applied
+        world.trigger(StateChangeRequest::new(plain, Closed));
+        world.trigger(StateChangeRequest::new(plain, Open));
+        world.flush();
+        assert_eq!(world.get::<DoorFSM>(plain), Some(&Open));
+
+        let log = world.resource::<Log>();
+        assert_eq!(log.entered, vec![Locked, Locked, Open, Closed, Open]);
+        assert_eq!(log.denied, vec![(Open, TransitionVerdict::DeniedByRules)]);
+    }
+}
@@ -0,0 +1,174 @@
+//! Rewriting requested target states before validation.
+//!
+//! An [`FSMRedirect`] maps requested states to substitutes, e.g. sending a player with
+//! credits left to a continue screen instead of game over, without every system that
+//! requests `GameOver` having to know about credits:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMRedirect, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum FlowFSM { Playing, GameOver, ContinueScreen }
+//! # #[derive(Component)]
+//! # struct Credits(u32);
+//! fn offer_continue(mut commands: Commands, players: Query<(Entity, &Credits), Changed<Credits>>) {
+//!     for (player, credits) in &players {
+//!         if credits.0 > 0 {
+//!             commands.entity(player).insert(
+//!                 FSMRedirect::new().with(FlowFSM::GameOver, FlowFSM::ContinueScreen),
+//!             );
+//!         } else {
+//!             commands.entity(player).remove::<FSMRedirect<FlowFSM>>();
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The substitute replaces the requested state before any validation, so it is what gets
+//! validated and what `Exit`, `Transition`, `Enter` and `TransitionDenied` report. Redirects
+//! are applied once and don't chain. An entity's `FSMRedirect<S>` takes precedence over the
+//! type-wide [`FSMTypeRedirect`] resource. `force_state` and `validate` ignore redirects.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::hash::Hash;
+
+/// Component mapping requested states of the FSM `S` to the states actually entered.
+#[derive(Component, Debug, Clone)]
+pub struct FSMRedirect<S: Copy + Eq + Hash + Send + Sync + 'static> {
+    targets: HashMap<S, S>,
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> Default for FSMRedirect<S> {
+    fn default() -> Self {
+        Self {
+            targets: HashMap::default(),
+        }
+    }
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> FSMRedirect<S> {
+    /// Redirect without any mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enter `substitute` whenever `target` is requested.
+    #[must_use]
+    pub fn with(mut self, target: S, substitute: S) -> Self {
+        self.set(target, substitute);
+        self
+    }
+
+    /// Enter `substitute` whenever `target` is requested, replacing any previous mapping.
+    pub fn set(&mut self, target: S, substitute: S) -> &mut Self {
+        self.targets.insert(target, substitute);
+        self
+    }
+
+    /// Stop redirecting `target`, returning its substitute.
+    pub fn remove(&mut self, target: S) -> Option<S> {
+        self.targets.remove(&target)
+    }
+
+    /// Substitute for `target`, if redirected.
+    pub fn get(&self, target: S) -> Option<S> {
+        self.targets.get(&target).copied()
+    }
+}
+
+/// Resource applying an [`FSMRedirect`] to every entity of the FSM type `S`.
+///
+/// Consulted for targets the entity's own `FSMRedirect<S>` doesn't map.
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct FSMTypeRedirect<S: Copy + Eq + Hash + Send + Sync + 'static>(pub FSMRedirect<S>);
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> FSMTypeRedirect<S> {
+    /// Type-wide redirect from `redirect`.
+    #[must_use]
+    pub fn new(redirect: FSMRedirect<S>) -> Self {
+        Self(redirect)
+    }
+}
+
+/// The state entered when `next` is requested for `entity`.
+pub(crate) fn redirect<S: Copy + Eq + Hash + Send + Sync + 'static>(
+    world: &World,
+    entity: Entity,
+    next: S,
+) -> S {
+    world
+        .get::<FSMRedirect<S>>(entity)
+        .and_then(|redirect| redirect.get(next))
+        .or_else(|| {
+            world
+                .get_resource::<FSMTypeRedirect<S>>()
+                .and_then(|redirect| redirect.get(next))
+        })
+        .unwrap_or(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, FSMState, FSMTransition, StateChangeRequest, TransitionDenied};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum FlowFSM {
+        Playing,
+        GameOver,
+        ContinueScreen,
+        Credits,
+    }
+
+    impl FSMState for FlowFSM {}
+
+    impl FSMTransition for FlowFSM {
+        fn can_transition(_: Self, to: Self) -> bool {
+            to != FlowFSM::Credits
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<FlowFSM>);
+
+    #[test]
+    fn redirected_targets_are_validated_and_reported() {
+        use FlowFSM::*;
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<FlowFSM>::default());
+        app.init_resource::<Log>();
+        app.add_observer(|enter: On<Enter<FlowFSM>>, mut log: ResMut<Log>| {
+            log.0.push(enter.event().state);
+        });
+        app.add_observer(
+            |denied: On<TransitionDenied<FlowFSM>>, mut log: ResMut<Log>| {
+                log.0.push(denied.event().to);
+            },
+        );
+        app.insert_resource(FSMTypeRedirect::new(
+            FSMRedirect::new().with(GameOver, Credits),
+        ));
+        let player = app
+            .world_mut()
+            .spawn((Playing, FSMRedirect::new().with(GameOver, ContinueScreen)))
+            .id();
+        let npc = app.world_mut().spawn(Playing).id();
+
+        for entity in [player, npc] {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(entity, GameOver));
+        }
+        app.world_mut().flush();
+
+        assert_eq!(*app.world().get::<FlowFSM>(player).unwrap(), ContinueScreen);
+        // The type-wide redirect leads to a state the rules deny
+        assert_eq!(*app.world().get::<FlowFSM>(npc).unwrap(), Playing);
+        assert_eq!(
+            app.world().resource::<Log>().0,
+            vec![Playing, Playing, ContinueScreen, Credits]
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Reflection-based access to FSM components.
+//!
+//! [`FSMPlugin`](crate::FSMPlugin) registers [`ReflectFSMState`] type data for every FSM
+//! type it sets up. Tools that only know type and variant names at runtime (dev consoles,
+//! editors, scripting layers) can use it to inspect and drive state machines without
+//! compile-time knowledge of the enum.
+//!
+//! Names that no longer match a variant, e.g. from saves made before a variant was renamed
+//! or removed, are resolved through the [`FSMMigrations`] registered with
+//! [`FSMAppExt::fsm_migration`](crate::FSMAppExt::fsm_migration).
+
+use bevy::prelude::*;
+use bevy::reflect::{DynamicEnum, DynamicVariant, FromType, ReflectRef, Typed};
+
+use crate::save::{self, FSMSaveEntry};
+use crate::{force_state, FSMState, StateChangeRequest};
+
+/// Errors returned by the reflection-based FSM API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FSMReflectError {
+    /// No FSM type with this name is registered (or the short name is ambiguous).
+    UnknownFSM(String),
+    /// The FSM type has no unit variant with this name.
+    UnknownVariant {
+        /// Name of the FSM type.
+        fsm: String,
+        /// The variant name that failed to resolve.
+        variant: String,
+    },
+    /// The entity does not exist.
+    NoSuchEntity(Entity),
+    /// The entity exists but has no component of the FSM type.
+    MissingComponent {
+        /// The entity that was queried.
+        entity: Entity,
+        /// Name of the FSM type.
+        fsm: String,
+    },
+}
+
+impl core::fmt::Display for FSMReflectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownFSM(name) => write!(f, "unknown FSM type '{name}'"),
+            Self::UnknownVariant { fsm, variant } => {
+                write!(f, "FSM type '{fsm}' has no variant '{variant}'")
+            }
+            Self::NoSuchEntity(entity) => write!(f, "entity {entity} does not exist"),
+            Self::MissingComponent { entity, fsm } => {
+                write!(f, "entity {entity} has no '{fsm}' component")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FSMReflectError {}
+
+/// Type data providing type-erased access to an FSM component.
+///
+/// Registered automatically by [`FSMPlugin`](crate::FSMPlugin). Look it up through the
+/// [`AppTypeRegistry`] or use [`fsm_type_data`] to resolve it by type name.
+#[derive(Clone)]
+pub struct ReflectFSMState {
+    type_name: fn() -> &'static str,
+    variant_names: fn() -> &'static [&'static str],
+    state_name: fn(&World, Entity) -> Option<&'static str>,
+    entities: fn(&mut World) -> Vec<Entity>,
+    request: fn(&mut World, Entity, &str) -> Result<(), FSMReflectError>,
+    force: fn(&mut World, Entity, &str) -> Result<(), FSMReflectError>,
+    snapshot: fn(&mut World) -> Vec<FSMSaveEntry>,
+    restore: fn(&mut World, &FSMSaveEntry, bool) -> Result<(), FSMReflectError>,
+}
+
+impl ReflectFSMState {
+    /// Short type name of the FSM (e.g. `LifeFSM`).
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// Names of all variants declared on the FSM enum.
+    pub fn variant_names(&self) -> &'static [&'static str] {
+        (self.variant_names)()
+    }
+
+    /// Name of the entity's current state, or `None` if it has no such FSM component.
+    pub fn state_name(&self, world: &World, entity: Entity) -> Option<&'static str> {
+        (self.state_name)(world, entity)
+    }
+
+    /// All entities that currently carry this FSM component.
+    pub fn entities(&self, world: &mut World) -> Vec<Entity> {
+        (self.entities)(world)
+    }
+
+    /// Issue a validated [`StateChangeRequest`] towards the named variant.
+    ///
+    /// The request goes through the regular observer pipeline, so transition rules and
+    /// overrides apply exactly as for typed requests. Pending commands are flushed before
+    /// returning.
+    pub fn request(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        variant: &str,
+    ) -> Result<(), FSMReflectError> {
+        (self.request)(world, entity, variant)
+    }
+
+    /// Put the entity into the named variant without validation (see [`force_state`]).
+    ///
+    /// Exit, Transition and Enter events still fire. Pending commands are flushed before
+    /// returning.
+    pub fn force(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        variant: &str,
+    ) -> Result<(), FSMReflectError> {
+        (self.force)(world, entity, variant)
+    }
+
+    /// Save entries of every entity carrying this FSM component (see [`save`]).
+    pub fn snapshot(&self, world: &mut World) -> Vec<FSMSaveEntry> {
+        (self.snapshot)(world)
+    }
+
+    /// Restores a save entry of this FSM type, optionally firing the initial `Enter` events
+    /// (see [`save::apply`]).
+    pub fn restore(
+        &self,
+        world: &mut World,
+        entry: &FSMSaveEntry,
+        fire_enter: bool,
+    ) -> Result<(), FSMReflectError> {
+        (self.restore)(world, entry, fire_enter)
+    }
+}
+
+impl<S> FromType<S> for ReflectFSMState
+where
+    S: FSMState + core::hash::Hash + Reflect + FromReflect + Typed,
+{
+    fn from_type() -> Self {
+        Self {
+            type_name: short_type_name::<S>,
+            variant_names: variant_names::<S>,
+            state_name: |world, entity| variant_name(world.get::<S>(entity)?),
+            entities: |world| {
+                let mut query = world.query_filtered::<Entity, With<S>>();
+                query.iter(world).collect()
+            },
+            request: |world, entity, variant| {
+                let next = resolve_target::<S>(world, entity, variant)?;
+                world.trigger(StateChangeRequest::<S>::new(entity, next));
+                world.flush();
+                Ok(())
+            },
+            force: |world, entity, variant| {
+                let next = resolve_target::<S>(world, entity, variant)?;
+                force_state(world, entity, next);
+                Ok(())
+            },
+            snapshot: save::snapshot_fsm::<S>,
+            restore: save::restore_fsm::<S>,
+        }
+    }
+}
+
+/// Resource mapping outdated variant names of `S` to current states.
+///
+/// Filled by [`FSMAppExt::fsm_migration`](crate::FSMAppExt::fsm_migration) and consulted
+/// whenever a name does not match a variant of `S`: by the functions of this module, by
+/// [`save::apply`] and by commands of the dev console.
+#[derive(Resource)]
+pub struct FSMMigrations<S> {
+    #[allow(clippy::type_complexity)]
+    migrations: Vec<Box<dyn Fn(&str) -> Option<S> + Send + Sync>>,
+}
+
+impl<S> Default for FSMMigrations<S> {
+    fn default() -> Self {
+        Self {
+            migrations: Vec::new(),
+        }
+    }
+}
+
+impl<S> FSMMigrations<S> {
+    /// Adds a migration, consulted after the ones added before it.
+    pub fn add(&mut self, migration: impl Fn(&str) -> Option<S> + Send + Sync + 'static) {
+        self.migrations.push(Box::new(migration));
+    }
+
+    /// The state an outdated variant name maps to, from the first migration knowing it.
+    pub fn migrate(&self, name: &str) -> Option<S> {
+        self.migrations.iter().find_map(|migration| migration(name))
+    }
+}
+
+/// Constructs the `S` state with the given variant name, falling back to the
+/// [`FSMMigrations`] of `S` for outdated names.
+pub(crate) fn resolve_state_name<S>(world: &World, variant: &str) -> Option<S>
+where
+    S: FromReflect + Typed + Send + Sync + 'static,
+{
+    state_from_name::<S>(variant)
+        .or_else(|| world.get_resource::<FSMMigrations<S>>()?.migrate(variant))
+}
+
+/// Resolves `variant` for an entity that is expected to carry an `S` component.
+fn resolve_target<S>(world: &World, entity: Entity, variant: &str) -> Result<S, FSMReflectError>
+where
+    S: FSMState + FromReflect + Typed,
+{
+    let fsm = short_type_name::<S>().to_string();
+    let Some(next) = resolve_state_name::<S>(world, variant) else {
+        return Err(FSMReflectError::UnknownVariant {
+            fsm,
+            variant: variant.to_string(),
+        });
+    };
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return Err(FSMReflectError::NoSuchEntity(entity));
+    };
+    if !entity_ref.contains::<S>() {
+        return Err(FSMReflectError::MissingComponent { entity, fsm });
+    }
+    Ok(next)
+}
+
+/// Resolves the [`ReflectFSMState`] type data for an FSM type by name.
+///
+/// Accepts either the short type name (`LifeFSM`) or the full type path
+/// (`my_game::life::LifeFSM`).
+pub fn fsm_type_data(world: &World, fsm_name: &str) -> Result<ReflectFSMState, FSMReflectError> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    registry
+        .get_with_short_type_path(fsm_name)
+        .or_else(|| registry.get_with_type_path(fsm_name))
+        .and_then(|registration| registration.data::<ReflectFSMState>())
+        .cloned()
+        .ok_or_else(|| FSMReflectError::UnknownFSM(fsm_name.to_string()))
+}
+
+/// Issues a validated state change request using only type and variant names.
+///
+/// Looks up the FSM type in the [`AppTypeRegistry`], resolves the variant and triggers a
+/// regular [`StateChangeRequest`], so transition rules and overrides apply as usual.
+/// Pending commands are flushed before returning, so the outcome is visible immediately.
+///
+/// Returns an error if the type, variant, entity or component cannot be found. A request
+/// that is denied by validation still returns `Ok`; compare the state before and after
+/// (e.g. via [`ReflectFSMState::state_name`]) to detect denials.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fsm::reflect::request_state_by_name;
+/// fn kill(world: &mut World, entity: Entity) {
+///     request_state_by_name(world, entity, "LifeFSM", "Dead").unwrap();
+/// }
+/// ```
+pub fn request_state_by_name(
+    world: &mut World,
+    entity: Entity,
+    fsm_name: &str,
+    variant: &str,
+) -> Result<(), FSMReflectError> {
+    fsm_type_data(world, fsm_name)?.request(world, entity, variant)
+}
+
+/// Constructs the unit variant of `S` with the given name.
+pub(crate) fn state_from_name<S: FromReflect + Typed>(variant: &str) -> Option<S> {
+    let info = S::type_info().as_enum().ok()?;
+    info.variant(variant)?;
+    S::from_reflect(&DynamicEnum::new(variant, DynamicVariant::Unit))
+}
+
+/// Name of the variant `state` is in, as listed in the type info.
+pub(crate) fn variant_name<S: Reflect + Typed>(state: &S) -> Option<&'static str> {
+    let ReflectRef::Enum(state) = state.reflect_ref() else {
+        return None;
+    };
+    variant_names::<S>()
+        .iter()
+        .copied()
+        .find(|name| *name == state.variant_name())
+}
+
+fn variant_names<S: Typed>() -> &'static [&'static str] {
+    S::type_info()
+        .as_enum()
+        .map(|info| info.variant_names())
+        .unwrap_or_default()
+}
+
+fn short_type_name<S: Typed>() -> &'static str {
+    S::type_info().type_path_table().short_path()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMOverride, FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[reflect(Component)]
+    enum LifeFSM {
+        Alive,
+        Dying,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (LifeFSM::Alive, LifeFSM::Dying) | (LifeFSM::Dying, LifeFSM::Dead)
+            )
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        app
+    }
+
+    #[test]
+    fn request_by_name_applies_valid_transition() {
+        let mut app = app();
+        let e = app.world_mut().spawn(LifeFSM::Alive).id();
+
+        request_state_by_name(app.world_mut(), e, "LifeFSM", "Dying").unwrap();
+        assert_eq!(*app.world().get::<LifeFSM>(e).unwrap(), LifeFSM::Dying);
+
+        // Full type paths resolve as well
+        let path = LifeFSM::type_path();
+        request_state_by_name(app.world_mut(), e, path, "Dead").unwrap();
+        assert_eq!(*app.world().get::<LifeFSM>(e).unwrap(), LifeFSM::Dead);
+    }
+
+    #[test]
+    fn request_by_name_respects_validation() {
+        let mut app = app();
+        let e = app
+            .world_mut()
+            .spawn((LifeFSM::Alive, FSMOverride::<LifeFSM>::deny_all()))
+            .id();
+
+        request_state_by_name(app.world_mut(), e, "LifeFSM", "Dying").unwrap();
+        assert_eq!(*app.world().get::<LifeFSM>(e).unwrap(), LifeFSM::Alive);
+    }
+
+    #[test]
+    fn request_by_name_reports_errors() {
+        let mut app = app();
+        let e = app.world_mut().spawn(LifeFSM::Alive).id();
+        let empty = app.world_mut().spawn_empty().id();
+
+        assert_eq!(
+            request_state_by_name(app.world_mut(), e, "ManaFSM", "Full"),
+            Err(FSMReflectError::UnknownFSM("ManaFSM".to_string()))
+        );
+        assert_eq!(
+            request_state_by_name(app.world_mut(), e, "LifeFSM", "Undead"),
+            Err(FSMReflectError::UnknownVariant {
+                fsm: "LifeFSM".to_string(),
+                variant: "Undead".to_string(),
+            })
+        );
+        assert_eq!(
+            request_state_by_name(app.world_mut(), empty, "LifeFSM", "Dead"),
+            Err(FSMReflectError::MissingComponent {
+                entity: empty,
+                fsm: "LifeFSM".to_string(),
+            })
+        );
+
+        app.world_mut().despawn(e);
+        assert_eq!(
+            request_state_by_name(app.world_mut(), e, "LifeFSM", "Dead"),
+            Err(FSMReflectError::NoSuchEntity(e))
+        );
+    }
+
+    #[test]
+    fn type_data_exposes_names() {
+        let mut app = app();
+        let e = app.world_mut().spawn(LifeFSM::Dying).id();
+
+        let data = fsm_type_data(app.world(), "LifeFSM").unwrap();
+        assert_eq!(data.type_name(), "LifeFSM");
+        assert_eq!(data.variant_names(), &["Alive", "Dying", "Dead"]);
+        assert_eq!(data.state_name(app.world(), e), Some("Dying"));
+        assert_eq!(data.entities(app.world_mut()), vec![e]);
+    }
+
+    #[test]
+    fn outdated_names_are_migrated() {
+        use crate::FSMAppExt;
+
+        let mut app = app();
+        app.fsm_migration::<LifeFSM>(|old| (old == "Fainting").then_some(LifeFSM::Dying))
+            .fsm_migration::<LifeFSM>(|old| match old {
+                "Fainting" => Some(LifeFSM::Alive),
+                "Deceased" => Some(LifeFSM::Dead),
+                _ => None,
+            });
+        let e = app.world_mut().spawn(LifeFSM::Alive).id();
+
+        request_state_by_name(app.world_mut(), e, "LifeFSM", "Fainting").unwrap();
+        assert_eq!(*app.world().get::<LifeFSM>(e).unwrap(), LifeFSM::Dying);
+        request_state_by_name(app.world_mut(), e, "LifeFSM", "Deceased").unwrap();
+        assert_eq!(*app.world().get::<LifeFSM>(e).unwrap(), LifeFSM::Dead);
+        assert!(matches!(
+            request_state_by_name(app.world_mut(), e, "LifeFSM", "Undead"),
+            Err(FSMReflectError::UnknownVariant { .. })
+        ));
+    }
+}
@@ -0,0 +1,415 @@
+//! Recording transition streams to a file and playing them back.
+//!
+//! [`FSMRecorderPlugin::record`] writes every applied transition of `S` to a text file, one
+//! line per transition: the frame, the entity's [`FSMReplayId`], and the states left and
+//! entered. [`FSMRecorderPlugin::playback`] re-issues a loaded recording as
+//! [`StateChangeRequest`]s in the same frames, e.g. to replay a bug report or drive a soak test:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::replay::{FSMRecorderPlugin, FSMReplay, FSMReplayId};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum EnemyFSM { Idle, Chase }
+//! # let replay_bug_report = false;
+//! # let mut app = App::new();
+//! app.add_plugins(FSMPlugin::<EnemyFSM>::default());
+//! if replay_bug_report {
+//!     let replay = FSMReplay::<EnemyFSM>::load("enemies.fsmreplay").unwrap();
+//!     app.add_plugins(FSMRecorderPlugin::playback(replay));
+//! } else {
+//!     app.add_plugins(FSMRecorderPlugin::<EnemyFSM>::record("enemies.fsmreplay"));
+//! }
+//!
+//! fn spawn_enemy(mut commands: Commands) {
+//!     // Ids must be assigned the same way in the recorded and the replayed session
+//!     commands.spawn((EnemyFSM::Idle, FSMReplayId(7)));
+//! }
+//! ```
+//!
+//! Frames are counted from the plugin's first update. Only entities with an [`FSMReplayId`]
+//! are recorded. Replayed requests are sent in `Update`, before
+//! [`FSMSystems`], and validated as usual; [`FSMReplay::mismatches`] counts
+//! those whose entity was missing or not in the recorded `from` state, i.e. where the session
+//! diverged from the recording.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::propagation;
+use crate::reflect::{state_from_name, variant_name};
+use crate::{FSMState, FSMSystems, StateChangeRequest, Transition};
+
+/// Stable id identifying an entity across a recorded and a replayed session.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FSMReplayId(pub u64);
+
+/// A transition read from or written to a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordedTransition<S> {
+    /// Frame the transition was applied in, counted from the plugin's first update.
+    pub frame: u64,
+    /// [`FSMReplayId`] of the entity.
+    pub id: u64,
+    /// State left.
+    pub from: S,
+    /// State entered.
+    pub to: S,
+}
+
+/// Errors reading a recording.
+#[derive(Debug)]
+pub enum FSMReplayError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// A line is not `frame id from to`.
+    Malformed {
+        /// 1-based line number.
+        line: usize,
+    },
+    /// A line names a state the FSM type doesn't have.
+    UnknownState {
+        /// 1-based line number.
+        line: usize,
+        /// The state name.
+        name: String,
+    },
+}
+
+impl core::fmt::Display for FSMReplayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "cannot read recording: {err}"),
+            Self::Malformed { line } => write!(f, "line {line} is not 'frame id from to'"),
+            Self::UnknownState { line, name } => write!(f, "line {line}: unknown state '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for FSMReplayError {}
+
+impl From<std::io::Error> for FSMReplayError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Resource writing the transitions of `S` to the recording file.
+#[derive(Resource)]
+pub struct FSMRecorder<S: Send + Sync + 'static> {
+    frame: u64,
+    file: BufWriter<File>,
+    error: Option<std::io::Error>,
+    _phantom: core::marker::PhantomData<S>,
+}
+
+impl<S: Send + Sync + 'static> FSMRecorder<S> {
+    /// The current frame.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The error that stopped the recording, if writing failed.
+    pub fn error(&self) -> Option<&std::io::Error> {
+        self.error.as_ref()
+    }
+}
+
+/// Resource holding a recording of `S` transitions, replayed by [`FSMRecorderPlugin::playback`].
+#[derive(Resource, Debug, Clone)]
+pub struct FSMReplay<S: Send + Sync + 'static> {
+    frame: u64,
+    transitions: VecDeque<RecordedTransition<S>>,
+    mismatches: u32,
+}
+
+impl<S: FSMState + FromReflect + Typed> FSMReplay<S> {
+    /// Reads a recording written by [`FSMRecorderPlugin::record`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FSMReplayError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parses the contents of a recording.
+    pub fn parse(recording: &str) -> Result<Self, FSMReplayError> {
+        let mut transitions = VecDeque::new();
+        for (index, text) in recording.lines().enumerate() {
+            let line = index + 1;
+            let fields: Vec<_> = text.split_whitespace().collect();
+            let [frame, id, from, to] = fields[..] else {
+                return Err(FSMReplayError::Malformed { line });
+            };
+            let (Ok(frame), Ok(id)) = (frame.parse(), id.parse()) else {
+                return Err(FSMReplayError::Malformed { line });
+            };
+            let state = |name: &str| {
+                state_from_name::<S>(name).ok_or_else(|| FSMReplayError::UnknownState {
+                    line,
+                    name: name.to_string(),
+                })
+            };
+            transitions.push_back(RecordedTransition {
+                frame,
+                id,
+                from: state(from)?,
+                to: state(to)?,
+            });
+        }
+        Ok(Self {
+            frame: 0,
+            transitions,
+            mismatches: 0,
+        })
+    }
+}
+
+impl<S: Send + Sync + 'static> FSMReplay<S> {
+    /// Transitions not replayed yet.
+    pub fn remaining(&self) -> impl Iterator<Item = &RecordedTransition<S>> {
+        self.transitions.iter()
+    }
+
+    /// Whether every transition was replayed.
+    pub fn is_finished(&self) -> bool {
+        self.transitions.is_empty()
+    }
+
+    /// Number of replayed transitions whose entity was missing or not in the recorded `from`
+    /// state.
+    pub fn mismatches(&self) -> u32 {
+        self.mismatches
+    }
+}
+
+enum Mode<S: Send + Sync + 'static> {
+    Record(PathBuf),
+    Playback(FSMReplay<S>),
+}
+
+/// Plugin recording the transitions of `S` to a file, or playing a recording back.
+pub struct FSMRecorderPlugin<S: Send + Sync + 'static> {
+    mode: Mode<S>,
+}
+
+impl<S: Send + Sync + 'static> FSMRecorderPlugin<S> {
+    /// Record every transition of entities with an [`FSMReplayId`] to `path`, replacing the
+    /// file.
+    ///
+    /// # Panics
+    ///
+    /// On build, if the file cannot be created.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: Mode::Record(path.into()),
+        }
+    }
+
+    /// Re-issue the transitions of `replay` as requests in their recorded frames.
+    pub fn playback(replay: FSMReplay<S>) -> Self {
+        Self {
+            mode: Mode::Playback(replay),
+        }
+    }
+}
+
+impl<S> Plugin for FSMRecorderPlugin<S>
+where
+    S: FSMState + Reflect + Typed,
+{
+    fn build(&self, app: &mut App) {
+        match &self.mode {
+            Mode::Record(path) => {
+                let file = File::create(path).unwrap_or_else(|err| {
+                    panic!("FSMRecorderPlugin cannot create {}: {err}", path.display())
+                });
+                app.insert_resource(FSMRecorder::<S> {
+                    frame: 0,
+                    file: BufWriter::new(file),
+                    error: None,
+                    _phantom: core::marker::PhantomData,
+                })
+                .add_observer(record_transition::<S>)
+                .add_systems(First, |mut recorder: ResMut<FSMRecorder<S>>| {
+                    recorder.frame += 1;
+                })
+                .add_systems(Last, flush_recording::<S>);
+            }
+            Mode::Playback(replay) => {
+                app.insert_resource(replay.clone())
+                    .add_systems(First, |mut replay: ResMut<FSMReplay<S>>| {
+                        replay.frame += 1;
+                    })
+                    .add_systems(Update, replay_transitions::<S>.before(FSMSystems));
+            }
+        }
+    }
+}
+
+/// Observer writing applied transitions to the recording.
+#[allow(clippy::needless_pass_by_value)]
+fn record_transition<S: FSMState + Reflect + Typed>(
+    transition: On<Transition<S, S>>,
+    ids: Query<&FSMReplayId>,
+    mut recorder: ResMut<FSMRecorder<S>>,
+) {
+    if propagation::bubbled(&transition) {
+        return;
+    }
+    let Ok(&FSMReplayId(id)) = ids.get(transition.entity) else {
+        return;
+    };
+    if recorder.error.is_some() {
+        return;
+    }
+    let (Some(from), Some(to)) = (variant_name(&transition.from), variant_name(&transition.to))
+    else {
+        return;
+    };
+    let frame = recorder.frame;
+    if let Err(err) = writeln!(recorder.file, "{frame} {id} {from} {to}") {
+        recorder.error = Some(err);
+    }
+}
+
+fn flush_recording<S: Send + Sync + 'static>(mut recorder: ResMut<FSMRecorder<S>>) {
+    if recorder.error.is_none() {
+        if let Err(err) = recorder.file.flush() {
+            recorder.error = Some(err);
+        }
+    }
+}
+
+/// System re-issuing the recorded transitions of the current frame.
+pub fn replay_transitions<S: FSMState>(
+    mut commands: Commands,
+    mut replay: ResMut<FSMReplay<S>>,
+    entities: Query<(Entity, &FSMReplayId, &S)>,
+) {
+    let frame = replay.frame;
+    if replay
+        .transitions
+        .front()
+        .is_none_or(|next| next.frame > frame)
+    {
+        return;
+    }
+    // Tracks the state each request leads to, for several transitions of one entity per frame
+    let mut entities: HashMap<_, _> = entities
+        .iter()
+        .map(|(entity, &FSMReplayId(id), &state)| (id, (entity, state)))
+        .collect();
+    while let Some(next) = replay.transitions.front().copied() {
+        if next.frame > frame {
+            break;
+        }
+        replay.transitions.pop_front();
+        match entities.get_mut(&next.id) {
+            Some((entity, state)) if next.frame == frame && *state == next.from => {
+                commands.trigger(StateChangeRequest::new(*entity, next.to));
+                *state = next.to;
+            }
+            _ => replay.mismatches += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum EnemyFSM {
+        Idle,
+        Chase,
+        Attack,
+    }
+
+    impl FSMState for EnemyFSM {}
+
+    impl FSMTransition for EnemyFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn recorded_sessions_replay_in_the_same_frames() {
+        use EnemyFSM::*;
+
+        let path = std::env::temp_dir().join(format!("bevy_fsm_{}.fsmreplay", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<EnemyFSM>::default(),
+            FSMRecorderPlugin::<EnemyFSM>::record(&path),
+        ));
+        let enemy = app.world_mut().spawn((Idle, FSMReplayId(7))).id();
+        let untracked = app.world_mut().spawn(Idle).id();
+        app.update();
+        for next in [Chase, Attack] {
+            for entity in [enemy, untracked] {
+                app.world_mut()
+                    .trigger(StateChangeRequest::new(entity, next));
+            }
+            app.update();
+        }
+        assert!(app
+            .world()
+            .resource::<FSMRecorder<EnemyFSM>>()
+            .error()
+            .is_none());
+        let recording = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Requests sent between updates apply with the next frame's commands
+        assert_eq!(recording, "2 7 Idle Chase\n3 7 Chase Attack\n");
+
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<EnemyFSM>::default(),
+            FSMRecorderPlugin::playback(FSMReplay::<EnemyFSM>::parse(&recording).unwrap()),
+        ));
+        let enemy = app.world_mut().spawn((Idle, FSMReplayId(7))).id();
+        app.update();
+        assert_eq!(app.world().get::<EnemyFSM>(enemy), Some(&Idle));
+        app.update();
+        assert_eq!(app.world().get::<EnemyFSM>(enemy), Some(&Chase));
+        app.update();
+        assert_eq!(app.world().get::<EnemyFSM>(enemy), Some(&Attack));
+        let replay = app.world().resource::<FSMReplay<EnemyFSM>>();
+        assert!(replay.is_finished());
+        assert_eq!(replay.mismatches(), 0);
+
+        assert!(matches!(
+            FSMReplay::<EnemyFSM>::parse("1 7 Idle Flee"),
+            Err(FSMReplayError::UnknownState { line: 1, .. })
+        ));
+        assert!(matches!(
+            FSMReplay::<EnemyFSM>::parse("1 7 Idle"),
+            Err(FSMReplayError::Malformed { line: 1 })
+        ));
+    }
+
+    #[test]
+    fn same_frame_transitions_replay_in_order() {
+        use EnemyFSM::*;
+
+        let recording = "1 7 Idle Chase\n1 7 Chase Attack\n";
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<EnemyFSM>::default(),
+            FSMRecorderPlugin::playback(FSMReplay::<EnemyFSM>::parse(recording).unwrap()),
+        ));
+        let enemy = app.world_mut().spawn((Idle, FSMReplayId(7))).id();
+        app.update();
+
+        assert_eq!(app.world().get::<EnemyFSM>(enemy), Some(&Attack));
+        let replay = app.world().resource::<FSMReplay<EnemyFSM>>();
+        assert!(replay.is_finished());
+        assert_eq!(replay.mismatches(), 0);
+    }
+}
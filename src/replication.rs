@@ -0,0 +1,198 @@
+//! State replication for multiplayer games using `bevy_replicon`.
+//!
+//! Enabled by the `replicon` feature. The server is authoritative: every transition applied
+//! on the server is broadcast as a [`ReplicatedStateChange`] message, and clients apply it
+//! directly, bypassing local `FSMOverride`/`FSMTransition` validation so both sides stay in
+//! sync even when client-side rules would disagree.
+//!
+//! Add the same FSM types to both apps, in the same order, so replicon's message
+//! registration lines up:
+//!
+//! ```ignore
+//! // Server
+//! app.add_plugins((
+//!     FSMPlugin::<LifeFSM>::default(),
+//!     FSMServerReplicationPlugin::<LifeFSM>::default(),
+//! ));
+//!
+//! // Client
+//! app.add_plugins((
+//!     FSMPlugin::<LifeFSM>::default(),
+//!     FSMClientReplicationPlugin::<LifeFSM>::default(),
+//! ));
+//! ```
+//!
+//! The FSM component itself should be replicated as usual (`app.replicate::<LifeFSM>()`) so
+//! late-joining clients receive the current state; these messages carry the transitions so
+//! clients fire the same Exit/Transition/Enter events as the server.
+//!
+//! Transitions are picked up from the `Transition` events of the server, which
+//! [`FSMPlugin::batched`](crate::FSMPlugin::batched) does not fire: with a batched plugin on
+//! the server, nothing is broadcast and clients only see the replicated component.
+
+use bevy::ecs::entity::MapEntities;
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::propagation;
+use crate::{force_state, FSMState, Transition};
+
+/// Server-to-client message describing an applied transition.
+#[derive(Message, Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ReplicatedStateChange<S> {
+    /// The entity that transitioned (mapped to the client entity on receipt).
+    pub entity: Entity,
+    /// The state the entity left.
+    pub from: S,
+    /// The state the entity entered.
+    pub to: S,
+}
+
+impl<S> MapEntities for ReplicatedStateChange<S> {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.entity = entity_mapper.get_mapped(self.entity);
+    }
+}
+
+/// Server-side plugin broadcasting every applied transition of `S` to all clients.
+pub struct FSMServerReplicationPlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMServerReplicationPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S> Plugin for FSMServerReplicationPlugin<S>
+where
+    S: FSMState + Serialize + DeserializeOwned,
+{
+    fn build(&self, app: &mut App) {
+        app.add_mapped_server_message::<ReplicatedStateChange<S>>(Channel::Ordered)
+            .add_observer(broadcast_transition::<S>);
+    }
+}
+
+/// Client-side plugin applying authoritative transitions received from the server.
+pub struct FSMClientReplicationPlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMClientReplicationPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S> Plugin for FSMClientReplicationPlugin<S>
+where
+    S: FSMState + core::hash::Hash + Serialize + DeserializeOwned,
+{
+    fn build(&self, app: &mut App) {
+        app.add_mapped_server_message::<ReplicatedStateChange<S>>(Channel::Ordered)
+            .add_systems(Update, apply_replicated_changes::<S>);
+    }
+}
+
+/// Observer sending each applied transition to all clients.
+#[allow(clippy::needless_pass_by_value)]
+fn broadcast_transition<S: FSMState + Serialize + DeserializeOwned>(
+    trigger: On<Transition<S, S>>,
+    mut messages: MessageWriter<ToClients<ReplicatedStateChange<S>>>,
+) {
+    if propagation::bubbled(&trigger) {
+        return;
+    }
+    let event = trigger.event();
+    messages.write(ToClients {
+        mode: SendMode::Broadcast,
+        message: ReplicatedStateChange {
+            entity: event.entity,
+            from: event.from,
+            to: event.to,
+        },
+    });
+}
+
+/// Applies received transitions without local validation.
+///
+/// Changes whose local state does not match the server's `from` state, e.g. because
+/// replication already caught up, are applied anyway: the server state always wins. Changes
+/// for entities that don't exist on the client yet, or have no `S` component yet, are
+/// dropped; the replicated component brings them to the current state when it arrives.
+fn apply_replicated_changes<S: FSMState + core::hash::Hash + Serialize + DeserializeOwned>(
+    mut messages: MessageReader<ReplicatedStateChange<S>>,
+    mut commands: Commands,
+) {
+    for change in messages.read() {
+        let ReplicatedStateChange { entity, to, .. } = *change;
+        commands.queue(move |world: &mut World| force_state(world, entity, to));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+    use bevy::ecs::message::Messages;
+
+    #[derive(
+        Component, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash,
+    )]
+    enum LifeFSM {
+        Alive,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn server_transitions_are_applied_on_clients() {
+        let mut server = App::new();
+        server.add_plugins(FSMPlugin::<LifeFSM>::default());
+        server.add_message::<ToClients<ReplicatedStateChange<LifeFSM>>>();
+        server.add_observer(broadcast_transition::<LifeFSM>);
+        let entity = server.world_mut().spawn(LifeFSM::Alive).id();
+        server
+            .world_mut()
+            .trigger(StateChangeRequest::new(entity, LifeFSM::Dead));
+        server.world_mut().flush();
+        let sent: Vec<_> = server
+            .world_mut()
+            .resource_mut::<Messages<ToClients<ReplicatedStateChange<LifeFSM>>>>()
+            .drain()
+            .map(|to_clients| to_clients.message)
+            .collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!((sent[0].from, sent[0].to), (LifeFSM::Alive, LifeFSM::Dead));
+
+        let mut client = App::new();
+        client.add_plugins(FSMPlugin::<LifeFSM>::default());
+        client.add_message::<ReplicatedStateChange<LifeFSM>>();
+        client.add_systems(Update, apply_replicated_changes::<LifeFSM>);
+        let local = client.world_mut().spawn(LifeFSM::Alive).id();
+        let missing = client.world_mut().spawn_empty().id();
+        for entity in [local, missing] {
+            client
+                .world_mut()
+                .write_message(ReplicatedStateChange { entity, ..sent[0] });
+        }
+        client.update();
+
+        assert_eq!(client.world().get::<LifeFSM>(local), Some(&LifeFSM::Dead));
+        assert!(!client.world().entity(missing).contains::<LifeFSM>());
+    }
+}
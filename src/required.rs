@@ -0,0 +1,52 @@
+//! Initial FSM states attached to marker components.
+//!
+//! Bevy's required components take a plain `fn() -> R` constructor, which cannot capture the
+//! state chosen in [`FSMPlugin::require_on`](crate::FSMPlugin::require_on), so two apps in
+//! one process could not pick different states for the same marker. Each app keeps its
+//! states in a resource instead, read by an observer inserting the state when the marker is
+//! added. `World::spawn` applies the observer's commands before returning, so the state is
+//! there as soon as the entity is spawned.
+
+use std::any::TypeId;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::{FSMAppExt, FSMState};
+
+/// Deferred registration of one `require_on` marker, run when the plugin is built.
+pub(crate) type Registration = Box<dyn Fn(&mut App) + Send + Sync>;
+
+/// Initial states of `S` by marker component, for this app.
+#[derive(Resource)]
+struct RequiredStates<S>(HashMap<TypeId, S>);
+
+/// Makes `M` bring `S`, inserted in `state` when not given explicitly.
+///
+/// # Panics
+///
+/// If `M` already requires `S` in this app.
+pub(crate) fn register<M: Component, S: FSMState>(app: &mut App, state: S) {
+    let mut states = app
+        .world_mut()
+        .get_resource_or_insert_with(|| RequiredStates::<S>(HashMap::default()));
+    assert!(
+        states.0.insert(TypeId::of::<M>(), state).is_none(),
+        "{} already requires {}",
+        ShortName::of::<M>(),
+        ShortName::of::<S>()
+    );
+    app.add_fsm_observer::<S, _, _, _>(insert_initial_state::<M, S>);
+}
+
+/// Observer inserting the state registered for `M`, unless the entity already has an `S`.
+#[allow(clippy::needless_pass_by_value)]
+fn insert_initial_state<M: Component, S: FSMState>(
+    trigger: On<Add, M>,
+    states: Res<RequiredStates<S>>,
+    mut commands: Commands,
+) {
+    if let Some(&state) = states.0.get(&TypeId::of::<M>()) {
+        commands.entity(trigger.entity).insert_if_new(state);
+    }
+}
@@ -0,0 +1,222 @@
+//! Parking denied requests until they pass.
+//!
+//! A request sent with [`StateChangeRequest::retry_for`] isn't dropped when it is denied: it is
+//! parked on the entity in an [`FSMRetry`] and re-validated every frame, and applied as soon
+//! as it would pass, e.g. once a guard's condition becomes true:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMEntityCommandsExt, FSMState, FSMTransition, StateChangeRequest};
+//! # use bevy_enum_event::EnumEvent;
+//! # use core::time::Duration;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum MageFSM { Idle, Casting }
+//! fn queue_spell(mut commands: Commands, mage: Entity) {
+//!     // Casts as soon as the mage has enough energy, if within two seconds
+//!     commands.entity(mage).request_fsm(
+//!         StateChangeRequest::to(MageFSM::Casting).retry_for(Duration::from_secs(2)),
+//!     );
+//! }
+//! ```
+//!
+//! Parked requests are checked in `Update`, before [`FSMSystems`](crate::FSMSystems), with the
+//! [`FSMClock`](crate::FSMClock) measuring their expiry. `TransitionDenied` only fires when a
+//! request expires, with the verdict of its last check. Each entity parks one request per FSM
+//! type; a newer denied request replaces it. Without the clock's time resource requests are
+//! denied right away.
+
+use bevy::prelude::*;
+use core::time::Duration;
+
+use crate::clock;
+use crate::ticket::{self, TicketStatus};
+use crate::{
+    decide_transition, redirect, rules_details, FSMState, StateChangeRequest, TransitionDenied,
+    TransitionVerdict,
+};
+
+/// How long a denied request is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RetryPolicy {
+    /// Deny it right away.
+    #[default]
+    Never,
+    /// Park it and retry every frame for the given time.
+    For(Duration),
+}
+
+/// Component holding a denied request of the FSM `S` that is retried every frame.
+///
+/// Inserted when a request with a [`RetryPolicy`] is denied and removed once it is applied or
+/// expires. Remove it to cancel the retry.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FSMRetry<S: Copy + Send + Sync + 'static> {
+    request: StateChangeRequest<S>,
+    expires: Duration,
+    verdict: TransitionVerdict,
+}
+
+impl<S: Copy + Send + Sync + 'static> FSMRetry<S> {
+    /// The parked request.
+    pub fn request(&self) -> &StateChangeRequest<S> {
+        &self.request
+    }
+
+    /// Why the request was denied when last checked.
+    pub fn verdict(&self) -> TransitionVerdict {
+        self.verdict
+    }
+}
+
+/// Parks a denied `request` if it carries a retry policy, returning whether it did.
+pub(crate) fn park<S: FSMState>(
+    world: &mut World,
+    request: StateChangeRequest<S>,
+    verdict: TransitionVerdict,
+) -> bool {
+    let RetryPolicy::For(duration) = request.retry else {
+        return false;
+    };
+    let Some(now) = clock::elapsed::<S>(world) else {
+        return false;
+    };
+    let Ok(mut entity) = world.get_entity_mut(request.entity) else {
+        return false;
+    };
+    let replaced = entity.take::<FSMRetry<S>>().map(|retry| retry.request);
+    entity.insert(FSMRetry {
+        request,
+        expires: now + duration,
+        verdict,
+    });
+    ticket::supersede(world, replaced);
+    true
+}
+
+/// System re-validating every parked request of `S`, applying the ones that pass and denying
+/// the expired ones.
+pub fn retry_parked_requests<S: FSMState + core::hash::Hash>(world: &mut World) {
+    let Some(now) = clock::elapsed::<S>(world) else {
+        return;
+    };
+    let mut parked = world.query::<(Entity, &S, &FSMRetry<S>)>();
+    let parked: Vec<_> = parked
+        .iter(world)
+        .map(|(entity, &cur, &retry)| (entity, cur, retry))
+        .collect();
+    for (entity, cur, retry) in parked {
+        let request = retry.request;
+        let next = redirect::redirect(world, entity, request.next);
+        let verdict = if cur == next {
+            TransitionVerdict::Unchanged
+        } else {
+            decide_transition(world, entity, cur, next, request.priority)
+        };
+        match verdict {
+            TransitionVerdict::Allowed => {
+                world.entity_mut(entity).remove::<FSMRetry<S>>();
+                world.trigger(StateChangeRequest {
+                    retry: RetryPolicy::Never,
+                    ..request
+                });
+            }
+            TransitionVerdict::Unchanged => {
+                world.entity_mut(entity).remove::<FSMRetry<S>>();
+                ticket::resolve(world, &request, TicketStatus::Denied(verdict));
+            }
+            reason if now >= retry.expires => {
+                world.entity_mut(entity).remove::<FSMRetry<S>>();
+                let details = rules_details(world, entity, cur, next, reason);
+                world.trigger(TransitionDenied {
+                    entity,
+                    from: cur,
+                    to: next,
+                    reason,
+                    source: request.source,
+                    details,
+                });
+                ticket::resolve(world, &request, TicketStatus::Denied(reason));
+            }
+            reason => {
+                if let Some(mut retry) = world.get_mut::<FSMRetry<S>>(entity) {
+                    retry.verdict = reason;
+                }
+            }
+        }
+    }
+    world.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum MageFSM {
+        Idle,
+        Casting,
+    }
+
+    #[derive(Component)]
+    struct Energy(u32);
+
+    impl FSMState for MageFSM {}
+
+    impl FSMTransition for MageFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+
+        fn can_transition_ctx(world: &World, entity: Entity, _: Self, to: Self) -> bool {
+            to != MageFSM::Casting || world.get::<Energy>(entity).is_some_and(|e| e.0 >= 10)
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Denied(Vec<(Entity, TransitionVerdict)>);
+
+    #[test]
+    fn parked_requests_apply_once_they_pass_or_expire() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<MageFSM>::default());
+        app.init_resource::<Time>();
+        app.init_resource::<Denied>();
+        app.add_observer(
+            |denied: On<TransitionDenied<MageFSM>>, mut log: ResMut<Denied>| {
+                log.0.push((denied.entity, denied.reason));
+            },
+        );
+        let patient = app.world_mut().spawn((MageFSM::Idle, Energy(0))).id();
+        let hasty = app.world_mut().spawn((MageFSM::Idle, Energy(0))).id();
+        app.update();
+
+        let retry = Duration::from_millis(200);
+        for mage in [patient, hasty] {
+            app.world_mut()
+                .trigger(StateChangeRequest::new(mage, MageFSM::Casting).retry_for(retry));
+        }
+        let advance = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(100));
+            app.update();
+        };
+        advance(&mut app);
+        assert!(app.world().entity(patient).contains::<FSMRetry<MageFSM>>());
+        assert!(app.world().resource::<Denied>().0.is_empty());
+
+        app.world_mut().entity_mut(patient).insert(Energy(20));
+        advance(&mut app);
+        assert_eq!(app.world().get::<MageFSM>(patient), Some(&MageFSM::Casting));
+        assert!(!app.world().entity(patient).contains::<FSMRetry<MageFSM>>());
+
+        advance(&mut app);
+        assert_eq!(app.world().get::<MageFSM>(hasty), Some(&MageFSM::Idle));
+        assert_eq!(
+            app.world().resource::<Denied>().0,
+            vec![(hasty, TransitionVerdict::DeniedByRules)]
+        );
+        assert!(!app.world().entity(hasty).contains::<FSMRetry<MageFSM>>());
+    }
+}
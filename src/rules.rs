@@ -0,0 +1,940 @@
+//! The validation core: transition rules and overrides, independent of the app.
+//!
+//! Everything deciding whether a transition is allowed lives here: the [`FSMTransition`]
+//! rules, the [`FSMOverride`] lists and the [`OverrideDecision`] they produce. None of it
+//! needs an `App`, observers or a schedule, so server-side simulations and plain unit tests
+//! can validate transitions with [`check`]:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::rules::check;
+//! # use bevy_fsm::{FSMOverride, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! #[derive(Component, EnumEvent, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum LifeFSM { Alive, Dying, Dead }
+//!
+//! impl FSMTransition for LifeFSM {
+//!     fn can_transition(from: Self, to: Self) -> bool {
+//!         matches!((from, to), (LifeFSM::Alive, LifeFSM::Dying) | (LifeFSM::Dying, LifeFSM::Dead))
+//!     }
+//! }
+//!
+//! assert!(check(LifeFSM::Alive, LifeFSM::Dying, 0, &[]).is_allowed());
+//! let immortal = FSMOverride::deny_all();
+//! assert!(!check(LifeFSM::Alive, LifeFSM::Dying, 0, &[&immortal]).is_allowed());
+//! ```
+//!
+//! [`FSMPlugin`](crate::FSMPlugin) and [`validate`](crate::validate) are the Bevy-facing shell
+//! around this core: they collect the overrides of an entity from the world, add the checks
+//! that depend on it (pausing, debouncing, state caps) and fire the transition events. The
+//! transition graph in [`graph`](crate::graph) is derived from the same rules.
+//!
+//! The plain types among them (verdicts, deny reasons, override decisions, edges and the
+//! adjacency matrix) are defined in [`headless`](crate::headless), without Bevy, and
+//! re-exported here.
+
+use bevy::ecs::change_detection::Tick;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use core::time::Duration;
+
+pub use crate::headless::{
+    AnyState, DenyReason, FSMAdjacency, FSMEdge, IntoFSMEdge, IntoTransitionResult,
+    OverrideDecision, TransitionVerdict,
+};
+use crate::query::FSMTimer;
+use crate::FSMState;
+
+/// Trait for defining transition logic.
+///
+/// Implement this trait on your FSM enum to define which transitions are valid.
+///
+/// # Example
+/// ```rust
+/// use bevy_fsm::FSMTransition;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// enum LifeFSM { Alive, Dying, Dead }
+///
+/// impl FSMTransition for LifeFSM {
+///     fn can_transition(from: Self, to: Self) -> bool {
+///         matches!((from, to),
+///             (LifeFSM::Alive, LifeFSM::Dying) |
+///             (LifeFSM::Dying, LifeFSM::Dead)) || from == to
+///     }
+/// }
+/// ```
+///
+/// Or derive it from a table of edges, with guards checked in `can_transition_ctx`:
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_fsm::FSMTransition;
+///
+/// fn has_energy(world: &World, entity: Entity) -> bool {
+///     world.get::<Name>(entity).is_some_and(|name| name.as_str() == "archmage")
+/// }
+///
+/// #[derive(FSMTransition, Clone, Copy, Debug, PartialEq, Eq)]
+/// #[transitions(Idle => Casting if has_energy, Casting => Idle)]
+/// enum MageFSM { Idle, Casting }
+///
+/// let mut world = World::new();
+/// let apprentice = world.spawn(Name::new("apprentice")).id();
+/// assert!(MageFSM::can_transition(MageFSM::Idle, MageFSM::Casting));
+/// assert!(!MageFSM::can_transition(MageFSM::Casting, MageFSM::Casting));
+/// assert!(!MageFSM::can_transition_ctx(&world, apprentice, MageFSM::Idle, MageFSM::Casting));
+///
+/// // The table is also available as an adjacency matrix
+/// let matrix = MageFSM::ADJACENCY.unwrap();
+/// assert!(matrix.contains(1, 0));
+/// ```
+pub trait FSMTransition {
+    /// Returns true if transition from `from` to `to` is allowed.
+    fn can_transition(from: Self, to: Self) -> bool
+    where
+        Self: Sized;
+
+    /// Optional context-aware validation with world access.
+    ///
+    /// Defaults to calling `can_transition`.
+    fn can_transition_ctx(world: &World, entity: Entity, from: Self, to: Self) -> bool
+    where
+        Self: Sized,
+    {
+        let _ = (world, entity);
+        Self::can_transition(from, to)
+    }
+
+    /// Like [`Self::can_transition_ctx`], but tells why a transition is denied.
+    ///
+    /// The [`DenyReason`] ends up in the `TransitionDenied` event, e.g. for a tooltip.
+    /// Defaults to `can_transition_ctx` with an empty reason.
+    fn try_transition_ctx(
+        world: &World,
+        entity: Entity,
+        from: Self,
+        to: Self,
+    ) -> Result<(), DenyReason>
+    where
+        Self: Sized,
+    {
+        if Self::can_transition_ctx(world, entity, from, to) {
+            Ok(())
+        } else {
+            Err(DenyReason::default())
+        }
+    }
+
+    /// Like [`Self::try_transition_ctx`], with a [`GuardCtx`] that also tells when the entity
+    /// last transitioned and how long it has been in its state, for time- and change-based
+    /// rules.
+    ///
+    /// This is what validation calls. Defaults to `try_transition_ctx`.
+    fn try_transition_guarded(ctx: &GuardCtx<'_>, from: Self, to: Self) -> Result<(), DenyReason>
+    where
+        Self: Sized,
+    {
+        Self::try_transition_ctx(ctx.world, ctx.entity, from, to)
+    }
+
+    /// Minimum request priority needed to leave `state`.
+    ///
+    /// Checked before any other validation, so a heavy attack that can only be cancelled by
+    /// death returns a high value here and the death request uses
+    /// [`StateChangeRequest::with_priority`](crate::StateChangeRequest::with_priority). Defaults to 0 (any request may interrupt).
+    fn min_interrupt_priority(state: Self) -> u32
+    where
+        Self: Sized,
+    {
+        let _ = state;
+        0
+    }
+
+    /// The rules as an adjacency matrix, if they come from a `#[transitions(...)]` table.
+    ///
+    /// `#[derive(FSMTransition)]` builds it at compile time and implements `can_transition`
+    /// as a bit test in it; code validating many requests at once can test edges with
+    /// [`Self::adjacency_index`] directly.
+    const ADJACENCY: Option<&'static FSMAdjacency> = None;
+
+    /// Row and column of `state` in [`Self::ADJACENCY`] (its position in the enum).
+    fn adjacency_index(state: Self) -> usize
+    where
+        Self: Sized,
+    {
+        let _ = state;
+        0
+    }
+
+    /// Whether the rules read the world, i.e. `can_transition` alone doesn't decide them.
+    ///
+    /// Defaults to `true`. `#[derive(FSMTransition)]` sets it to `false` unless the table has
+    /// `if` guards. With `false`, [`FSMPlugin::with_query_validation`] may validate requests
+    /// with `can_transition` only.
+    ///
+    /// [`FSMPlugin::with_query_validation`]: crate::FSMPlugin::with_query_validation
+    const READS_WORLD: bool = true;
+}
+
+/// What [`FSMTransition::try_transition_guarded`] and table guards taking `&GuardCtx` see of
+/// the entity being validated.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_fsm::{FSMTransition, GuardCtx};
+/// use core::time::Duration;
+///
+/// fn settled(ctx: &GuardCtx) -> bool {
+///     ctx.time_in_state >= Some(Duration::from_secs(1))
+/// }
+///
+/// #[derive(FSMTransition, Clone, Copy, Debug, PartialEq, Eq)]
+/// #[transitions(Guarding => Attacking if settled, Attacking => Guarding)]
+/// enum StanceFSM { Guarding, Attacking }
+///
+/// let mut world = World::new();
+/// let knight = world.spawn_empty().id();
+/// let ctx = GuardCtx {
+///     time_in_state: Some(Duration::from_secs(2)),
+///     ..GuardCtx::new(&world, knight)
+/// };
+/// assert!(StanceFSM::try_transition_guarded(&ctx, StanceFSM::Guarding, StanceFSM::Attacking).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct GuardCtx<'w> {
+    pub world: &'w World,
+    pub entity: Entity,
+    /// Change tick of the FSM component, set by its last transition or its insertion. `None`
+    /// if the rules are called without an FSM type, e.g. through `try_transition_ctx`.
+    pub last_transition_tick: Option<Tick>,
+    /// Time spent in the current state, from the entity's [`FSMTimer`]. `None` without one.
+    pub time_in_state: Option<Duration>,
+}
+
+impl<'w> GuardCtx<'w> {
+    /// Context of `entity` without transition timing.
+    pub fn new(world: &'w World, entity: Entity) -> Self {
+        Self {
+            world,
+            entity,
+            last_transition_tick: None,
+            time_in_state: None,
+        }
+    }
+
+    /// Context of `entity` for its FSM `S`, as validation builds it.
+    pub fn of<S: FSMState>(world: &'w World, entity: Entity) -> Self {
+        let entity_ref = world.get_entity(entity).ok();
+        Self {
+            world,
+            entity,
+            last_transition_tick: entity_ref
+                .and_then(|entity| entity.get_change_ticks::<S>())
+                .map(|ticks| ticks.changed),
+            time_in_state: entity_ref
+                .and_then(|entity| entity.get::<FSMTimer<S>>())
+                .map(FSMTimer::elapsed),
+        }
+    }
+
+    /// Whether the component `C` of the entity changed since its last transition.
+    ///
+    /// `false` if the entity has no `C` or the context no `last_transition_tick`.
+    pub fn changed_since_transition<C: Component>(&self) -> bool {
+        let Some(since) = self.last_transition_tick else {
+            return false;
+        };
+        self.world
+            .get_entity(self.entity)
+            .ok()
+            .and_then(|entity| entity.get_change_ticks::<C>())
+            .is_some_and(|ticks| {
+                ticks
+                    .changed
+                    .is_newer_than(since, self.world.read_change_tick())
+            })
+    }
+}
+
+/// Guard of a `#[transitions(...)]` edge: a `fn(&World, Entity)` or a `fn(&GuardCtx)`
+/// returning an [`IntoTransitionResult`].
+pub trait TransitionGuard<Marker> {
+    /// Runs the guard for the entity of `ctx`.
+    fn check(&self, ctx: &GuardCtx<'_>) -> Result<(), DenyReason>;
+}
+
+impl<F, R> TransitionGuard<fn(&World, Entity) -> R> for F
+where
+    F: Fn(&World, Entity) -> R,
+    R: IntoTransitionResult,
+{
+    fn check(&self, ctx: &GuardCtx<'_>) -> Result<(), DenyReason> {
+        self(ctx.world, ctx.entity).into_transition_result()
+    }
+}
+
+impl<F, R> TransitionGuard<fn(&GuardCtx<'_>) -> R> for F
+where
+    F: Fn(&GuardCtx<'_>) -> R,
+    R: IntoTransitionResult,
+{
+    fn check(&self, ctx: &GuardCtx<'_>) -> Result<(), DenyReason> {
+        self(ctx).into_transition_result()
+    }
+}
+
+/// Configuration mode for FSM transition validation set in the [`FSMOverride`] component.
+///
+/// The mode determines priority behavior - config wins over `FSMTransition` rules:
+/// - **Whitelist**: Transitions ON the list are **immediately accepted** (override rules)
+/// - **Blacklist**: Transitions ON the list are **immediately denied** (override rules)
+/// - Transitions NOT decided by the config can still use `FSMTransition` rules (if `call_rules: true`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RuleType {
+    /// No config restrictions - defer to `FSMTransition` rules.
+    ///
+    /// The `transitions` set is ignored. If `call_rules: true`, `FSMTransition` validation
+    /// applies. If `call_rules: false`, all transitions are allowed.
+    All,
+
+    /// Deny all transitions (immutable state).
+    ///
+    /// The `transitions` set is ignored. All transitions are hard-denied.
+    /// `FSMTransition` validation is never reached.
+    None,
+
+    /// Whitelist mode - listed transitions are prioritized and accepted.
+    ///
+    /// **Priority behavior:**
+    /// - If transition IS in set → **ACCEPT** (whitelist wins, `FSMTransition` NOT checked)
+    /// - If transition is NOT in set:
+    ///   - With `call_rules: true` → Check `FSMTransition` (can accept or deny)
+    ///   - With `call_rules: false` → **DENY** (default)
+    ///
+    /// Use this to explicitly allow specific transitions regardless of `FSMTransition` rules.
+    Whitelist,
+
+    /// Blacklist mode - listed transitions are prioritized and denied.
+    ///
+    /// **Priority behavior:**
+    /// - If transition IS in set → **DENY** (blacklist wins, `FSMTransition` NOT checked)
+    /// - If transition is NOT in set:
+    ///   - With `call_rules: true` → Check `FSMTransition` (can accept or deny)
+    ///   - With `call_rules: false` → **ACCEPT** (default)
+    ///
+    /// Use this to explicitly forbid specific transitions regardless of `FSMTransition` rules.
+    Blacklist,
+}
+
+/// Component for optional per-entity state machine configuration.
+///
+/// Attach this component alongside your FSM enum to constrain transitions
+/// for that specific entity.
+///
+/// # Priority Model: Config Wins, Rules Fill Gaps
+///
+/// `FSMOverride` has **priority** over `FSMTransition` rules:
+///
+/// ```text
+/// Transition Request
+///       ↓
+/// Is it ON whitelist?  → YES → ACCEPT (config wins)
+///       ↓ NO
+/// call_rules enabled?  → YES → Check FSMTransition
+///       ↓ NO
+///   DENY (not whitelisted)
+/// ```
+///
+/// ```text
+/// Transition Request
+///       ↓
+/// Is it ON blacklist?  → YES → DENY (config wins)
+///       ↓ NO
+/// call_rules enabled?  → YES → Check FSMTransition
+///       ↓ NO
+///   ACCEPT (not blacklisted)
+/// ```
+///
+/// # Validation Flow
+///
+/// 1. **`FSMOverride` (if present):**
+///    - **Whitelist mode:**
+///      - ON list: ACCEPT immediately
+///      - NOT on list: Check `call_rules` → if true check `FSMTransition`, else DENY
+///    - **Blacklist mode:**
+///      - ON list: DENY immediately
+///      - NOT on list: Check `call_rules` → if true check `FSMTransition`, else ACCEPT
+///    - **All mode:** Check `call_rules` → if true check `FSMTransition`, else ACCEPT all
+///    - **None mode:** DENY all (immutable)
+///
+/// 2. **No `FSMOverride`:**
+///    - Uses the [`FSMTypeRules`](crate::FSMTypeRules) resource the same way, if present
+///    - Otherwise falls back to the `FSMTransition` rules only
+///      ([`FSMTransition::try_transition_guarded`])
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy_fsm::{FSMOverride, RuleType};
+/// # #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+/// # enum MyState { A, B, C }
+///
+/// // Example 1: Whitelist A->C (overrides FSMTransition)
+/// let config = FSMOverride::whitelist([
+///     (MyState::A, MyState::C),  // Force allow
+/// ]);
+/// // A->C: ACCEPT (ON whitelist, config wins)
+/// // A->B: DENY (NOT on whitelist, call_rules=false so denied)
+/// // B->C: DENY (NOT on whitelist)
+///
+/// // Example 2: Whitelist + FSMTransition for unlisted transitions
+/// let config = FSMOverride::whitelist([
+///     (MyState::A, MyState::C),  // Force allow (overrides FSMTransition)
+/// ]).with_rules();
+/// // A->C: ACCEPT (ON whitelist, config wins, FSMTransition NOT checked)
+/// // A->B: Check FSMTransition (NOT on whitelist, but call_rules=true)
+/// // B->C: Check FSMTransition (NOT on whitelist, but call_rules=true)
+///
+/// // Example 3: Blacklist A->C (force deny)
+/// let config = FSMOverride::blacklist([
+///     (MyState::A, MyState::C),  // Force deny
+/// ]);
+/// // A->C: DENY (ON blacklist, config wins)
+/// // A->B: ACCEPT (NOT on blacklist, call_rules=false so allowed)
+/// // B->C: ACCEPT (NOT on blacklist)
+///
+/// // Example 4: Blacklist + FSMTransition for non-blacklisted
+/// let config = FSMOverride::blacklist([
+///     (MyState::A, MyState::C),  // Force deny
+/// ]).with_rules();
+/// // A->C: DENY (ON blacklist, config wins, FSMTransition NOT checked)
+/// // A->B: Check FSMTransition (NOT on blacklist, but call_rules=true)
+/// // B->C: Check FSMTransition (NOT on blacklist, but call_rules=true)
+///
+/// // Example 5: Allow all (bypass FSMTransition)
+/// let config = FSMOverride::<MyState>::allow_all();
+/// // All transitions: ACCEPT (no restrictions)
+///
+/// // Example 6: Allow all but enforce FSMTransition
+/// let config = FSMOverride::<MyState>::allow_all().with_rules();
+/// // All transitions: Check FSMTransition
+///
+/// // Example 7: Immutable state
+/// let config = FSMOverride::<MyState>::deny_all();
+/// // All transitions: DENY
+/// ```
+///
+/// # Use Cases
+///
+/// - **Force allow specific transitions**: Use `whitelist([...])` to allow transitions
+///   that `FSMTransition` would normally forbid - whitelist wins
+/// - **Force deny specific transitions**: Use `blacklist([...])` to prevent transitions
+///   that `FSMTransition` would normally allow - blacklist wins
+/// - **Whitelist + fallback to rules**: Use `whitelist([...]).with_rules()` to allow
+///   specific transitions unconditionally while checking `FSMTransition` for others
+/// - **Blacklist + fallback to rules**: Use `blacklist([...]).with_rules()` to deny
+///   specific transitions unconditionally while checking `FSMTransition` for others
+/// - **Immutable states**: Use `deny_all()` for entities that should never change state
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct FSMOverride<S: Copy + Eq + core::hash::Hash + Send + Sync + 'static> {
+    /// Transition filtering mode.
+    pub mode: RuleType,
+    /// Transitions set (interpretation depends on mode).
+    pub(crate) transitions: HashSet<(S, S)>,
+    /// States listed as targets: every transition into them counts as listed.
+    pub(crate) entering: HashSet<S>,
+    /// States listed as sources: every transition out of them counts as listed.
+    pub(crate) exiting: HashSet<S>,
+    /// Whether to check `FSMTransition` for transitions NOT decided by the config.
+    ///
+    /// - **Whitelist mode**: If `true`, transitions NOT on whitelist check `FSMTransition`.
+    ///   If `false`, they are denied.
+    /// - **Blacklist mode**: If `true`, transitions NOT on blacklist check `FSMTransition`.
+    ///   If `false`, they are allowed.
+    /// - **All mode**: If `true`, check `FSMTransition`. If `false`, allow everything.
+    /// - **None mode**: Ignored (all transitions denied).
+    ///
+    /// **Note**: Transitions ON whitelist/blacklist are decided by config and do NOT
+    /// check `FSMTransition` regardless of this flag (config has priority).
+    pub call_rules: bool,
+}
+
+impl<S> Default for FSMOverride<S>
+where
+    S: Copy + Eq + core::hash::Hash + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            mode: RuleType::All,
+            transitions: HashSet::new(),
+            entering: HashSet::new(),
+            exiting: HashSet::new(),
+            call_rules: false,
+        }
+    }
+}
+
+impl<S> FSMOverride<S>
+where
+    S: Copy + Eq + core::hash::Hash + Send + Sync + 'static,
+{
+    /// Allow all transitions (validate only via `FSMTransition` trait).
+    #[must_use]
+    pub fn allow_all() -> Self {
+        Self {
+            mode: RuleType::All,
+            transitions: HashSet::new(),
+            entering: HashSet::new(),
+            exiting: HashSet::new(),
+            call_rules: false,
+        }
+    }
+
+    /// Deny all transitions (immutable state).
+    #[must_use]
+    pub fn deny_all() -> Self {
+        Self {
+            mode: RuleType::None,
+            transitions: HashSet::new(),
+            entering: HashSet::new(),
+            exiting: HashSet::new(),
+            call_rules: false,
+        }
+    }
+
+    /// Allow only whitelisted transitions.
+    ///
+    /// Entries are `(from, to)` pairs, either of which may be [`AnyState`].
+    pub fn whitelist<I>(edges: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        Self {
+            mode: RuleType::Whitelist,
+            ..Self::default()
+        }
+        .and_allow(edges)
+    }
+
+    /// Allow all except blacklisted transitions.
+    ///
+    /// Entries are `(from, to)` pairs, either of which may be [`AnyState`]:
+    ///
+    /// ```rust
+    /// # use bevy_fsm::{AnyState, FSMOverride};
+    /// # #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    /// # enum LifeFSM { Alive, Stunned, Dead }
+    /// // Stunned entities can't go anywhere...
+    /// let stunned = FSMOverride::blacklist([(LifeFSM::Stunned, AnyState)]);
+    /// assert!(!stunned.is_transition_allowed(LifeFSM::Stunned, LifeFSM::Dead));
+    ///
+    /// // ...while anything can die, bypassing the rules
+    /// let mortal = FSMOverride::whitelist([(AnyState, LifeFSM::Dead)]).with_rules();
+    /// assert!(mortal.is_transition_allowed(LifeFSM::Stunned, LifeFSM::Dead));
+    /// ```
+    pub fn blacklist<I>(edges: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        Self {
+            mode: RuleType::Blacklist,
+            ..Self::default()
+        }
+        .and_deny(edges)
+    }
+
+    /// Deny every transition into `states`, from any state.
+    ///
+    /// Rules stay enabled for everything else, so this only adds a lock on top of the
+    /// `FSMTransition` rules:
+    ///
+    /// ```rust
+    /// # use bevy_fsm::FSMOverride;
+    /// # #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    /// # enum SpellFSM { Idle, Casting, Channeling }
+    /// // Silenced: no new casts, whatever the current state
+    /// let silenced = FSMOverride::deny_entering([SpellFSM::Casting]);
+    /// assert!(!silenced.is_transition_allowed(SpellFSM::Idle, SpellFSM::Casting));
+    /// assert!(silenced.is_transition_allowed(SpellFSM::Casting, SpellFSM::Idle));
+    /// ```
+    pub fn deny_entering<I>(states: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        Self::blacklist(states.into_iter().map(FSMEdge::Entering)).with_rules()
+    }
+
+    /// Deny every transition out of `states`, to any state.
+    ///
+    /// Rules stay enabled for everything else, like [`deny_entering`](Self::deny_entering).
+    pub fn deny_exiting<I>(states: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        Self::blacklist(states.into_iter().map(FSMEdge::Exiting)).with_rules()
+    }
+
+    /// List every transition into `states` (for blacklist mode: deny entering them).
+    #[must_use]
+    pub fn and_deny_entering<I>(mut self, states: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        self.entering.extend(states);
+        self
+    }
+
+    /// List every transition out of `states` (for blacklist mode: deny leaving them).
+    #[must_use]
+    pub fn and_deny_exiting<I>(mut self, states: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        self.exiting.extend(states);
+        self
+    }
+
+    /// Enable `FSMTransition` validation for transitions NOT decided by config.
+    ///
+    /// By default, `FSMOverride` is the sole authority - whitelisted/blacklisted transitions
+    /// are decided by config alone, others have default behavior. Calling `with_rules()`
+    /// applies `FSMTransition` validation to the "gap" transitions not explicitly listed.
+    ///
+    /// **Behavior by mode:**
+    /// - **Whitelist**: Listed transitions still ACCEPT (config priority). Unlisted
+    ///   transitions now check `FSMTransition` instead of auto-denying.
+    /// - **Blacklist**: Listed transitions still DENY (config priority). Unlisted
+    ///   transitions now check `FSMTransition` instead of auto-allowing.
+    /// - **All**: No whitelist/blacklist, so all transitions check `FSMTransition`.
+    /// - **None**: No effect (all transitions denied).
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use bevy_fsm::FSMOverride;
+    /// # #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    /// # enum MyState { A, B, C }
+    ///
+    /// // Whitelist without with_rules: whitelist is sole authority
+    /// let config = FSMOverride::whitelist([(MyState::A, MyState::C)]);
+    /// // A->C: ACCEPT (whitelisted)
+    /// // A->B: DENY (not whitelisted, no rules check)
+    ///
+    /// // Whitelist with with_rules: whitelist wins, rules fill gaps
+    /// let config = FSMOverride::whitelist([(MyState::A, MyState::C)]).with_rules();
+    /// // A->C: ACCEPT (whitelisted, config priority)
+    /// // A->B: Check FSMTransition (not whitelisted, rules enabled)
+    ///
+    /// // Blacklist without with_rules: blacklist is sole authority
+    /// let config = FSMOverride::blacklist([(MyState::A, MyState::C)]);
+    /// // A->C: DENY (blacklisted)
+    /// // A->B: ACCEPT (not blacklisted, no rules check)
+    ///
+    /// // Blacklist with with_rules: blacklist wins, rules fill gaps
+    /// let config = FSMOverride::blacklist([(MyState::A, MyState::C)]).with_rules();
+    /// // A->C: DENY (blacklisted, config priority)
+    /// // A->B: Check FSMTransition (not blacklisted, rules enabled)
+    /// ```
+    #[must_use]
+    pub fn with_rules(mut self) -> Self {
+        self.call_rules = true;
+        self
+    }
+
+    /// Add transitions to the set.
+    ///
+    /// For whitelist mode: adds allowed transitions.
+    /// For blacklist mode: adds denied transitions.
+    /// For All/None modes: has no effect.
+    #[must_use]
+    pub fn and_allow<I>(mut self, edges: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        self.insert_edges(edges);
+        self
+    }
+
+    /// Add denied transitions (for blacklist mode).
+    ///
+    /// Alias for `and_allow()` when using blacklist mode for semantic clarity.
+    #[must_use]
+    pub fn and_deny<I>(mut self, edges: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        self.insert_edges(edges);
+        self
+    }
+
+    /// Unlocks `edges`, whatever the current mode.
+    ///
+    /// - **Whitelist**: adds them to the whitelist.
+    /// - **Blacklist**: removes them from the blacklist. With `call_rules` they then fall
+    ///   through to the rules.
+    /// - **None**: switches to a whitelist of exactly `edges` (nothing else is unlocked).
+    /// - **All**: with `call_rules`, switches to a whitelist of `edges` so they bypass the
+    ///   rules while everything else still checks them. Without, everything is already
+    ///   accepted and nothing changes.
+    pub fn add_whitelist<I>(&mut self, edges: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        self.list(RuleType::Whitelist, edges)
+    }
+
+    /// Locks `edges`, whatever the current mode.
+    ///
+    /// - **Blacklist**: adds them to the blacklist.
+    /// - **Whitelist**: removes them from the whitelist. With `call_rules` they then fall
+    ///   through to the rules.
+    /// - **All**: switches to a blacklist of `edges`, keeping `call_rules`.
+    /// - **None**: everything is already denied and nothing changes.
+    pub fn add_blacklist<I>(&mut self, edges: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        self.list(RuleType::Blacklist, edges)
+    }
+
+    /// Shared implementation of [`add_whitelist`](Self::add_whitelist) (`mode` is
+    /// `Whitelist`) and [`add_blacklist`](Self::add_blacklist) (`mode` is `Blacklist`).
+    fn list<I>(&mut self, mode: RuleType, edges: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        let mut listed = Self::default();
+        listed.insert_edges(edges);
+        let Self {
+            transitions,
+            entering,
+            exiting,
+            ..
+        } = listed;
+        let allow = mode == RuleType::Whitelist;
+        match self.mode {
+            current if current == mode => {
+                self.transitions.extend(transitions);
+                self.entering.extend(entering);
+                self.exiting.extend(exiting);
+            }
+            RuleType::Whitelist | RuleType::Blacklist => {
+                self.transitions.retain(|edge| !transitions.contains(edge));
+                self.entering.retain(|state| !entering.contains(state));
+                self.exiting.retain(|state| !exiting.contains(state));
+            }
+            RuleType::None if !allow => {}
+            RuleType::All if allow && !self.call_rules => {}
+            current => {
+                if current == RuleType::None {
+                    self.call_rules = false;
+                }
+                self.mode = mode;
+                self.transitions = transitions;
+                self.entering = entering;
+                self.exiting = exiting;
+            }
+        }
+        self
+    }
+
+    /// Removes `edge` from the set. Returns whether it was listed.
+    ///
+    /// The effect depends on the mode: a removed whitelist entry is no longer accepted by
+    /// config, a removed blacklist entry is no longer denied.
+    ///
+    /// Entries are `(from, to)` pairs, either of which may be [`AnyState`], and only the entry
+    /// itself is removed: removing `(from, to)` leaves a `(AnyState, to)` entry listing the
+    /// same transition, and removing `(AnyState, to)` leaves the explicit pairs into `to`.
+    pub fn remove<E: IntoFSMEdge<S>>(&mut self, edge: E) -> bool {
+        match edge.into_edge() {
+            FSMEdge::Exact(from, to) => self.transitions.remove(&(from, to)),
+            FSMEdge::Entering(state) => self.entering.remove(&state),
+            FSMEdge::Exiting(state) => self.exiting.remove(&state),
+        }
+    }
+
+    /// Removes every listed transition and state, keeping the mode and `call_rules`.
+    pub fn clear(&mut self) -> &mut Self {
+        self.transitions.clear();
+        self.entering.clear();
+        self.exiting.clear();
+        self
+    }
+
+    /// Changes the mode, keeping the listed transitions.
+    ///
+    /// Switching between whitelist and blacklist flips the meaning of the set. Use
+    /// [`clear`](Self::clear) first to start from an empty list.
+    pub fn set_mode(&mut self, mode: RuleType) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Layers `other` on top of this config: its decisions win for the transitions it
+    /// decides, everything else keeps this config's behavior.
+    ///
+    /// - Whitelist `other`: its entries become accepted ([`add_whitelist`](Self::add_whitelist)).
+    /// - Blacklist `other`: its entries become denied ([`add_blacklist`](Self::add_blacklist)).
+    /// - None `other`: everything is denied.
+    /// - All `other`: no change.
+    ///
+    /// `call_rules` is kept from this config.
+    pub fn merge(&mut self, other: &Self) -> &mut Self {
+        match other.mode {
+            RuleType::Whitelist | RuleType::Blacklist => {
+                let edges: Vec<_> = other.edges().collect();
+                self.list(other.mode, edges)
+            }
+            RuleType::None => {
+                self.mode = RuleType::None;
+                self.clear()
+            }
+            RuleType::All => self,
+        }
+    }
+
+    /// All listed entries, in no particular order.
+    pub fn edges(&self) -> impl Iterator<Item = FSMEdge<S>> + '_ {
+        self.transitions
+            .iter()
+            .map(|&(from, to)| FSMEdge::Exact(from, to))
+            .chain(self.entering.iter().copied().map(FSMEdge::Entering))
+            .chain(self.exiting.iter().copied().map(FSMEdge::Exiting))
+    }
+
+    fn insert_edges<I>(&mut self, edges: I)
+    where
+        I: IntoIterator,
+        I::Item: IntoFSMEdge<S>,
+    {
+        for edge in edges {
+            match edge.into_edge() {
+                FSMEdge::Exact(from, to) => self.transitions.insert((from, to)),
+                FSMEdge::Entering(state) => self.entering.insert(state),
+                FSMEdge::Exiting(state) => self.exiting.insert(state),
+            };
+        }
+    }
+
+    /// Whether `from -> to` is listed, as an edge or through one of its endpoints.
+    fn is_listed(&self, from: S, to: S) -> bool {
+        self.transitions.contains(&(from, to))
+            || self.entering.contains(&to)
+            || self.exiting.contains(&from)
+    }
+
+    /// How this config decides `from -> to`, before `FSMTransition` rules are consulted.
+    pub fn decision(&self, from: S, to: S) -> OverrideDecision {
+        match self.mode {
+            // All mode: no config restrictions, optionally check rules
+            RuleType::All => OverrideDecision::of_list(false, false, self.call_rules),
+            // None mode: deny everything
+            RuleType::None => OverrideDecision::Deny,
+            // Listed transitions are decided by the list, the others by the rules if enabled
+            RuleType::Whitelist => {
+                OverrideDecision::of_list(true, self.is_listed(from, to), self.call_rules)
+            }
+            RuleType::Blacklist => {
+                OverrideDecision::of_list(false, self.is_listed(from, to), self.call_rules)
+            }
+        }
+    }
+
+    /// Check if a transition is allowed by this config.
+    pub fn is_transition_allowed(&self, from: S, to: S) -> bool {
+        match self.mode {
+            RuleType::All => true,
+            RuleType::None => false,
+            RuleType::Whitelist => self.is_listed(from, to),
+            RuleType::Blacklist => !self.is_listed(from, to),
+        }
+    }
+}
+
+/// Validates `from -> to` without a world: interrupt priority, then `overrides` (most
+/// specific first, the first one deciding wins), then [`FSMTransition::can_transition`].
+///
+/// This is the decision [`validate`](crate::validate) makes for an entity whose overrides are
+/// `overrides`, minus the checks that need the world (pausing, debouncing, state caps and
+/// [`FSMTransition::can_transition_ctx`]).
+pub fn check<S>(from: S, to: S, priority: u32, overrides: &[&FSMOverride<S>]) -> TransitionVerdict
+where
+    S: FSMTransition + Copy + Eq + core::hash::Hash + Send + Sync + 'static,
+{
+    if from == to {
+        return TransitionVerdict::Unchanged;
+    }
+    if priority < S::min_interrupt_priority(from) {
+        return TransitionVerdict::DeniedByPriority;
+    }
+    overrides
+        .iter()
+        .fold(OverrideDecision::Defer, |decision, config| {
+            decision.or_else(|| config.decision(from, to))
+        })
+        .verdict(|| S::can_transition(from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            !matches!((from, to), (DoorFSM::Open, DoorFSM::Locked))
+        }
+
+        fn min_interrupt_priority(state: Self) -> u32 {
+            u32::from(state == DoorFSM::Locked)
+        }
+    }
+
+    #[test]
+    fn check_validates_without_a_world() {
+        use DoorFSM::*;
+
+        assert_eq!(check(Open, Open, 0, &[]), TransitionVerdict::Unchanged);
+        assert_eq!(check(Open, Closed, 0, &[]), TransitionVerdict::Allowed);
+        assert_eq!(
+            check(Open, Locked, 0, &[]),
+            TransitionVerdict::DeniedByRules
+        );
+        assert_eq!(
+            check(Locked, Open, 0, &[]),
+            TransitionVerdict::DeniedByPriority
+        );
+
+        // The first override deciding wins, the rules only apply if all defer
+        let cheat = FSMOverride::whitelist([(Open, Locked)]).with_rules();
+        let jammed = FSMOverride::deny_all();
+        assert_eq!(
+            check(Open, Locked, 0, &[&cheat, &jammed]),
+            TransitionVerdict::Allowed
+        );
+        assert_eq!(
+            check(Open, Closed, 0, &[&cheat, &jammed]),
+            TransitionVerdict::DeniedByOverride
+        );
+        assert_eq!(
+            check(Closed, Open, 1, &[&cheat]),
+            TransitionVerdict::Allowed
+        );
+    }
+}
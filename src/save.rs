@@ -0,0 +1,367 @@
+//! Saving and restoring the FSM components of a world.
+//!
+//! [`snapshot`] walks the type registry for every FSM set up by [`FSMPlugin`](crate::FSMPlugin)
+//! and collects each entity's state, together with its [`FSMOverride`], [`FSMTimer`] and
+//! [`FSMHistory`] if present. [`apply`] puts them back without firing any events, so loading a
+//! save doesn't replay the effects of entering the saved states:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::save::{self, FSMSave};
+//! fn quick_save(world: &mut World) -> FSMSave {
+//!     save::snapshot(world)
+//! }
+//!
+//! fn quick_load(world: &mut World, save: &FSMSave) {
+//!     for error in save::apply(world, save) {
+//!         eprintln!("could not restore FSM: {error}");
+//!     }
+//! }
+//! ```
+//!
+//! States are stored by type and variant name, so saves survive reordering the enum; renamed or
+//! removed variants can be mapped with [`FSMAppExt::fsm_migration`](crate::FSMAppExt::fsm_migration). With the
+//! `serde` feature [`FSMSave`] implements `Serialize` and `Deserialize`. Entities are stored as
+//! they were when the snapshot was taken; use [`FSMSave::map_entities`] when restoring into
+//! respawned entities. [`apply_with_enter`] restores the same way but then fires the initial
+//! `Enter` events for every restored state, for games that rebuild visuals from them.
+
+use bevy::prelude::*;
+use bevy::reflect::{FromReflect, Typed};
+use core::hash::Hash;
+use core::time::Duration;
+
+use crate::reflect::{resolve_state_name, variant_name, FSMReflectError, ReflectFSMState};
+use crate::{
+    enter_initial_state, FSMCoreObservers, FSMHistory, FSMOverride, FSMState, FSMTimer, RuleType,
+    SkipInitialEnter,
+};
+
+/// Snapshot of the FSM components of a world, taken by [`snapshot`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FSMSave {
+    /// One entry per entity and FSM type, ordered by type name, then entity.
+    pub entries: Vec<FSMSaveEntry>,
+}
+
+impl FSMSave {
+    /// Replaces the saved entities, e.g. with the entities respawned when loading a level.
+    pub fn map_entities(&mut self, mut map: impl FnMut(Entity) -> Entity) {
+        for entry in &mut self.entries {
+            entry.entity = map(entry.entity);
+        }
+    }
+}
+
+/// Saved state of one FSM of one entity.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FSMSaveEntry {
+    /// The entity carrying the FSM.
+    pub entity: Entity,
+    /// Short type name of the FSM, e.g. `LifeFSM`.
+    pub fsm: String,
+    /// Variant name of the state.
+    pub state: String,
+    /// The entity's [`FSMOverride`], if any.
+    pub overrides: Option<SavedOverride>,
+    /// Time in the state from the entity's [`FSMTimer`], if any.
+    pub elapsed: Option<Duration>,
+    /// The entity's [`FSMHistory`], if any.
+    pub history: Option<SavedHistory>,
+}
+
+/// Saved [`FSMOverride`], with states stored by variant name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedOverride {
+    /// See [`FSMOverride::mode`].
+    pub mode: RuleType,
+    /// See [`FSMOverride::call_rules`].
+    pub call_rules: bool,
+    /// Listed `(from, to)` transitions, sorted.
+    pub transitions: Vec<(String, String)>,
+    /// States whose every incoming transition is listed, sorted.
+    pub entering: Vec<String>,
+    /// States whose every outgoing transition is listed, sorted.
+    pub exiting: Vec<String>,
+}
+
+/// Saved [`FSMHistory`], with states stored by variant name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedHistory {
+    /// See [`FSMHistory::capacity`].
+    pub capacity: usize,
+    /// Remembered states, most recent first.
+    pub states: Vec<String>,
+}
+
+/// Collects the FSM components of every entity, for all FSM types in the type registry.
+pub fn snapshot(world: &mut World) -> FSMSave {
+    let mut entries = Vec::new();
+    for fsm in fsm_types(world) {
+        entries.extend(fsm.snapshot(world));
+    }
+    FSMSave { entries }
+}
+
+/// Restores `save` without firing any events, returning the entries that could not be
+/// restored.
+///
+/// Entities must exist. Entities without the FSM component get it inserted, skipping the
+/// initial `Enter`; entities with it are set to the saved state as is. Timers, histories and
+/// overrides missing from an entry are left untouched.
+pub fn apply(world: &mut World, save: &FSMSave) -> Vec<FSMReflectError> {
+    restore(world, save, false)
+}
+
+/// Like [`apply`], then fires the initial `Enter` events (with no previous state) for every
+/// restored state.
+pub fn apply_with_enter(world: &mut World, save: &FSMSave) -> Vec<FSMReflectError> {
+    restore(world, save, true)
+}
+
+fn restore(world: &mut World, save: &FSMSave, fire_enter: bool) -> Vec<FSMReflectError> {
+    let fsms = fsm_types(world);
+    let errors = save
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let Some(fsm) = fsms.iter().find(|fsm| fsm.type_name() == entry.fsm) else {
+                return Some(FSMReflectError::UnknownFSM(entry.fsm.clone()));
+            };
+            fsm.restore(world, entry, fire_enter).err()
+        })
+        .collect();
+    world.flush();
+    errors
+}
+
+/// Type data of all registered FSM types, ordered by name.
+fn fsm_types(world: &World) -> Vec<ReflectFSMState> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut fsms: Vec<_> = registry
+        .iter_with_data::<ReflectFSMState>()
+        .map(|(_, fsm)| fsm.clone())
+        .collect();
+    fsms.sort_by_key(ReflectFSMState::type_name);
+    fsms
+}
+
+/// Snapshot entries of every entity with an `S` component.
+pub(crate) fn snapshot_fsm<S>(world: &mut World) -> Vec<FSMSaveEntry>
+where
+    S: FSMState + Hash + Reflect + Typed,
+{
+    let fsm = S::type_info().type_path_table().short_path();
+    let name = |state: &S| variant_name(state).unwrap_or_default().to_string();
+    let mut query = world.query::<(
+        Entity,
+        &S,
+        Option<&FSMOverride<S>>,
+        Option<&FSMTimer<S>>,
+        Option<&FSMHistory<S>>,
+    )>();
+    let mut entries: Vec<_> = query
+        .iter(world)
+        .map(|(entity, state, overrides, timer, history)| FSMSaveEntry {
+            entity,
+            fsm: fsm.to_string(),
+            state: name(state),
+            overrides: overrides.map(|config| {
+                let mut transitions: Vec<_> = config
+                    .transitions
+                    .iter()
+                    .map(|(from, to)| (name(from), name(to)))
+                    .collect();
+                let mut entering: Vec<_> = config.entering.iter().map(name).collect();
+                let mut exiting: Vec<_> = config.exiting.iter().map(name).collect();
+                transitions.sort();
+                entering.sort();
+                exiting.sort();
+                SavedOverride {
+                    mode: config.mode,
+                    call_rules: config.call_rules,
+                    transitions,
+                    entering,
+                    exiting,
+                }
+            }),
+            elapsed: timer.map(FSMTimer::elapsed),
+            history: history.map(|history| SavedHistory {
+                capacity: history.capacity(),
+                states: history.iter().map(|state| name(&state)).collect(),
+            }),
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.entity);
+    entries
+}
+
+/// Restores one entry of the FSM `S`. Nothing is changed if any of its states is unknown.
+pub(crate) fn restore_fsm<S>(
+    world: &mut World,
+    entry: &FSMSaveEntry,
+    fire_enter: bool,
+) -> Result<(), FSMReflectError>
+where
+    S: FSMState + Hash + FromReflect + Typed,
+{
+    let parse = |variant: &String| {
+        resolve_state_name::<S>(world, variant).ok_or_else(|| FSMReflectError::UnknownVariant {
+            fsm: entry.fsm.clone(),
+            variant: variant.clone(),
+        })
+    };
+    let state = parse(&entry.state)?;
+    let overrides = entry
+        .overrides
+        .as_ref()
+        .map(|saved| -> Result<_, FSMReflectError> {
+            Ok(FSMOverride::<S> {
+                mode: saved.mode,
+                transitions: saved
+                    .transitions
+                    .iter()
+                    .map(|(from, to)| Ok((parse(from)?, parse(to)?)))
+                    .collect::<Result<_, _>>()?,
+                entering: saved.entering.iter().map(parse).collect::<Result<_, _>>()?,
+                exiting: saved.exiting.iter().map(parse).collect::<Result<_, _>>()?,
+                call_rules: saved.call_rules,
+            })
+        })
+        .transpose()?;
+    let history = entry
+        .history
+        .as_ref()
+        .map(|saved| -> Result<_, FSMReflectError> {
+            let states = saved
+                .states
+                .iter()
+                .map(parse)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(FSMHistory::<S>::with_states(saved.capacity, states))
+        })
+        .transpose()?;
+
+    let Ok(mut entity) = world.get_entity_mut(entry.entity) else {
+        return Err(FSMReflectError::NoSuchEntity(entry.entity));
+    };
+    if let Some(overrides) = overrides {
+        entity.insert(overrides);
+    }
+    if let Some(elapsed) = entry.elapsed {
+        entity.insert(FSMTimer::<S>::with_elapsed(elapsed));
+    }
+    if let Some(history) = history {
+        entity.insert(history);
+    }
+    if entity.contains::<S>() {
+        entity.insert(state);
+    } else {
+        entity
+            .insert((state, SkipInitialEnter::<S>(core::marker::PhantomData)))
+            .remove::<SkipInitialEnter<S>>();
+    }
+
+    if fire_enter {
+        let variant_events = world
+            .get_resource::<FSMCoreObservers<S>>()
+            .is_none_or(|core| core.variant_events);
+        enter_initial_state(&mut world.commands(), entry.entity, state, variant_events);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Stunned,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Entered(Vec<LifeFSM>);
+
+    #[test]
+    fn snapshots_restore_without_enter_events() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        app.init_resource::<Entered>();
+        app.add_observer(|enter: On<Enter<LifeFSM>>, mut entered: ResMut<Entered>| {
+            entered.0.push(enter.state);
+        });
+        let hero = app
+            .world_mut()
+            .spawn((
+                LifeFSM::Stunned,
+                FSMOverride::<LifeFSM>::blacklist([(LifeFSM::Stunned, LifeFSM::Dead)]),
+                FSMTimer::<LifeFSM>::with_elapsed(Duration::from_secs(2)),
+                FSMHistory::<LifeFSM>::with_states(4, [LifeFSM::Alive]),
+            ))
+            .id();
+        let foe = app.world_mut().spawn(LifeFSM::Dead).id();
+        app.update();
+
+        let mut save = snapshot(app.world_mut());
+        assert_eq!(save.entries.len(), 2);
+        let entry = save
+            .entries
+            .iter()
+            .find(|entry| entry.entity == hero)
+            .unwrap();
+        assert_eq!(entry.state, "Stunned");
+        assert_eq!(entry.elapsed, Some(Duration::from_secs(2)));
+        assert_eq!(
+            entry.overrides.as_ref().unwrap().transitions,
+            vec![("Stunned".to_string(), "Dead".to_string())]
+        );
+
+        app.world_mut().entity_mut(hero).insert(LifeFSM::Alive);
+        app.world_mut().entity_mut(foe).despawn();
+        let respawned = app.world_mut().spawn_empty().id();
+        save.map_entities(|entity| if entity == foe { respawned } else { entity });
+        app.world_mut().resource_mut::<Entered>().0.clear();
+
+        assert!(apply(app.world_mut(), &save).is_empty());
+        assert!(app.world().resource::<Entered>().0.is_empty());
+        assert_eq!(app.world().get::<LifeFSM>(hero), Some(&LifeFSM::Stunned));
+        assert_eq!(app.world().get::<LifeFSM>(respawned), Some(&LifeFSM::Dead));
+        assert_eq!(
+            app.world()
+                .get::<FSMHistory<LifeFSM>>(hero)
+                .unwrap()
+                .previous(),
+            Some(LifeFSM::Alive)
+        );
+
+        assert!(apply_with_enter(app.world_mut(), &save).is_empty());
+        let mut entered = app.world().resource::<Entered>().0.clone();
+        entered.sort_by_key(|state| *state as u8);
+        assert_eq!(entered, vec![LifeFSM::Stunned, LifeFSM::Dead]);
+
+        save.entries[0].state = "Zombie".to_string();
+        assert_eq!(
+            apply(app.world_mut(), &save)[0],
+            FSMReflectError::UnknownVariant {
+                fsm: "LifeFSM".to_string(),
+                variant: "Zombie".to_string(),
+            }
+        );
+    }
+}
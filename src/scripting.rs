@@ -0,0 +1,145 @@
+//! String-based FSM facade for scripting layers (Lua, Rhai, ...).
+//!
+//! Enabled by the `scripting` feature. Every function here works purely with type names,
+//! variant names and [`Entity`] ids, so script bindings (e.g. `bevy_mod_scripting`) can read
+//! and drive any FSM added via [`FSMPlugin`](crate::FSMPlugin) without compile-time types.
+//! All lookups go through the [`ReflectFSMState`] type data stored in the type registry.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::scripting;
+//! fn script_tick(world: &mut World, npc: Entity) {
+//!     if scripting::state(world, npc, "LifeFSM") == Ok(Some("Dying")) {
+//!         let applied = scripting::request(world, npc, "LifeFSM", "Dead").unwrap();
+//!         assert!(applied);
+//!     }
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+use crate::reflect::{fsm_type_data, FSMReflectError, ReflectFSMState};
+
+/// Short names of every FSM type registered in the type registry.
+pub fn fsm_types(world: &World) -> Vec<&'static str> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut names: Vec<_> = registry
+        .iter_with_data::<ReflectFSMState>()
+        .map(|(_, data)| data.type_name())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+/// Variant names of the given FSM type.
+pub fn variants(world: &World, fsm: &str) -> Result<&'static [&'static str], FSMReflectError> {
+    Ok(fsm_type_data(world, fsm)?.variant_names())
+}
+
+/// Current state name of the entity, or `None` if it has no such FSM component.
+pub fn state(
+    world: &World,
+    entity: Entity,
+    fsm: &str,
+) -> Result<Option<&'static str>, FSMReflectError> {
+    Ok(fsm_type_data(world, fsm)?.state_name(world, entity))
+}
+
+/// All entities that currently carry the given FSM type.
+pub fn entities(world: &mut World, fsm: &str) -> Result<Vec<Entity>, FSMReflectError> {
+    Ok(fsm_type_data(world, fsm)?.entities(world))
+}
+
+/// Requests a validated transition and reports whether the entity ended up in `variant`.
+pub fn request(
+    world: &mut World,
+    entity: Entity,
+    fsm: &str,
+    variant: &str,
+) -> Result<bool, FSMReflectError> {
+    let data = fsm_type_data(world, fsm)?;
+    data.request(world, entity, variant)?;
+    Ok(data.state_name(world, entity) == Some(variant))
+}
+
+/// Puts the entity into `variant` without validation, still firing all events.
+pub fn force(
+    world: &mut World,
+    entity: Entity,
+    fsm: &str,
+    variant: &str,
+) -> Result<(), FSMReflectError> {
+    fsm_type_data(world, fsm)?.force(world, entity, variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, FSMState, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[reflect(Component)]
+    enum QuestFSM {
+        Locked,
+        Active,
+        Done,
+    }
+
+    impl FSMState for QuestFSM {}
+
+    impl FSMTransition for QuestFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (QuestFSM::Locked, QuestFSM::Active) | (QuestFSM::Active, QuestFSM::Done)
+            )
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Entered(Vec<QuestFSM>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(FSMPlugin::<QuestFSM>::new().ignore_fsm_addition());
+        app.init_resource::<Entered>();
+        app.add_observer(
+            |trigger: On<Enter<QuestFSM>>, mut entered: ResMut<Entered>| {
+                entered.0.push(trigger.state);
+            },
+        );
+        app
+    }
+
+    #[test]
+    fn facade_reads_and_requests() {
+        let mut app = app();
+        let e = app.world_mut().spawn(QuestFSM::Locked).id();
+        let world = app.world_mut();
+
+        assert_eq!(fsm_types(world), vec!["QuestFSM"]);
+        assert_eq!(
+            variants(world, "QuestFSM").unwrap(),
+            &["Locked", "Active", "Done"]
+        );
+        assert_eq!(state(world, e, "QuestFSM"), Ok(Some("Locked")));
+        assert_eq!(entities(world, "QuestFSM"), Ok(vec![e]));
+
+        // Locked -> Done is not a valid edge
+        assert_eq!(request(world, e, "QuestFSM", "Done"), Ok(false));
+        assert_eq!(request(world, e, "QuestFSM", "Active"), Ok(true));
+        assert_eq!(state(world, e, "QuestFSM"), Ok(Some("Active")));
+    }
+
+    #[test]
+    fn force_bypasses_validation_but_fires_events() {
+        let mut app = app();
+        let e = app.world_mut().spawn(QuestFSM::Done).id();
+
+        force(app.world_mut(), e, "QuestFSM", "Locked").unwrap();
+
+        assert_eq!(*app.world().get::<QuestFSM>(e).unwrap(), QuestFSM::Locked);
+        assert_eq!(app.world().resource::<Entered>().0, vec![QuestFSM::Locked]);
+    }
+}
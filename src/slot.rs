@@ -0,0 +1,160 @@
+//! Multiple independent instances of the same FSM on one entity.
+//!
+//! Components are unique per type, so an entity can only carry one `WeaponFSM`. Wrapping the
+//! enum in [`FSMSlot`] gives every slot its own component type, and with it its own requests,
+//! events, overrides and observer group:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMSlot, FSMPlugin, FSMState, FSMTransition, Enter, StateChangeRequest};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum WeaponFSM { Idle, Firing }
+//! type LeftHand = FSMSlot<WeaponFSM, 0>;
+//! type RightHand = FSMSlot<WeaponFSM, 1>;
+//!
+//! fn plugin(app: &mut App) {
+//!     app.add_plugins((FSMPlugin::<LeftHand>::default(), FSMPlugin::<RightHand>::default()));
+//!     app.add_observer(|trigger: On<Enter<RightHand>>| {
+//!         println!("right hand entered {:?}", *trigger.event().state);
+//!     });
+//! }
+//!
+//! fn spawn_player(mut commands: Commands) {
+//!     commands.spawn((LeftHand::new(WeaponFSM::Idle), RightHand::new(WeaponFSM::Idle)));
+//! }
+//!
+//! fn fire_right(commands: &mut Commands, player: Entity) {
+//!     commands.trigger(StateChangeRequest::new(player, RightHand::new(WeaponFSM::Firing)));
+//! }
+//! ```
+//!
+//! Validation delegates to the wrapped type's `FSMTransition` rules. Variant-specific events
+//! (`Enter<weapon_fsm::Firing>`) are not fired for slots, since they could not tell the slots
+//! apart; match on the state in the generic `Enter`/`Exit`/`Transition` events instead.
+
+use bevy::prelude::*;
+
+use crate::{DenyReason, FSMState, FSMTransition, GuardCtx};
+
+/// Component wrapping FSM state `S` in slot `N`.
+///
+/// Each `N` is a distinct component type, so `FSMSlot<WeaponFSM, 0>` and
+/// `FSMSlot<WeaponFSM, 1>` run independently on the same entity. Add an
+/// [`FSMPlugin`](crate::FSMPlugin) for every slot in use.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FSMSlot<S: Copy + Send + Sync + 'static, const N: usize>(pub S);
+
+impl<S: Copy + Send + Sync + 'static, const N: usize> FSMSlot<S, N> {
+    /// Wraps a state for slot `N`.
+    pub const fn new(state: S) -> Self {
+        Self(state)
+    }
+
+    /// The slot index.
+    pub const fn slot(&self) -> usize {
+        N
+    }
+}
+
+impl<S: Copy + Send + Sync + 'static, const N: usize> core::ops::Deref for FSMSlot<S, N> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S: FSMState, const N: usize> FSMTransition for FSMSlot<S, N> {
+    fn can_transition(from: Self, to: Self) -> bool {
+        <S as FSMTransition>::can_transition(from.0, to.0)
+    }
+
+    fn can_transition_ctx(world: &World, entity: Entity, from: Self, to: Self) -> bool {
+        <S as FSMTransition>::can_transition_ctx(world, entity, from.0, to.0)
+    }
+
+    fn try_transition_ctx(
+        world: &World,
+        entity: Entity,
+        from: Self,
+        to: Self,
+    ) -> Result<(), DenyReason> {
+        <S as FSMTransition>::try_transition_ctx(world, entity, from.0, to.0)
+    }
+
+    fn try_transition_guarded(ctx: &GuardCtx<'_>, from: Self, to: Self) -> Result<(), DenyReason> {
+        <S as FSMTransition>::try_transition_guarded(ctx, from.0, to.0)
+    }
+
+    fn min_interrupt_priority(state: Self) -> u32 {
+        <S as FSMTransition>::min_interrupt_priority(state.0)
+    }
+
+    const READS_WORLD: bool = <S as FSMTransition>::READS_WORLD;
+}
+
+// `can_transition_ctx` keeps its default, building the `GuardCtx` of the slot component
+impl<S: FSMState, const N: usize> FSMState for FSMSlot<S, N> {
+    fn can_transition(from: Self, to: Self) -> bool {
+        <S as FSMState>::can_transition(from.0, to.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, FSMPlugin, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum WeaponFSM {
+        Idle,
+        Firing,
+        Reloading,
+    }
+
+    impl FSMState for WeaponFSM {}
+
+    impl FSMTransition for WeaponFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            !matches!((from, to), (WeaponFSM::Reloading, WeaponFSM::Firing))
+        }
+    }
+
+    type Left = FSMSlot<WeaponFSM, 0>;
+    type Right = FSMSlot<WeaponFSM, 1>;
+
+    #[derive(Resource, Default)]
+    struct RightEnters(Vec<WeaponFSM>);
+
+    #[test]
+    fn slots_transition_independently() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins((FSMPlugin::<Left>::default(), FSMPlugin::<Right>::default()));
+        app.init_resource::<RightEnters>();
+        app.add_observer(|trigger: On<Enter<Right>>, mut log: ResMut<RightEnters>| {
+            log.0.push(*trigger.event().state);
+        });
+
+        let e = app
+            .world_mut()
+            .spawn((Left::new(WeaponFSM::Reloading), Right::new(WeaponFSM::Idle)))
+            .id();
+        app.world_mut().resource_mut::<RightEnters>().0.clear();
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, Right::new(WeaponFSM::Firing)));
+        // Denied by the wrapped rules: Reloading -> Firing
+        app.world_mut()
+            .trigger(StateChangeRequest::new(e, Left::new(WeaponFSM::Firing)));
+        app.world_mut().flush();
+
+        assert_eq!(app.world().get::<Right>(e).unwrap().0, WeaponFSM::Firing);
+        assert_eq!(app.world().get::<Left>(e).unwrap().0, WeaponFSM::Reloading);
+        assert_eq!(
+            app.world().resource::<RightEnters>().0,
+            vec![WeaponFSM::Firing]
+        );
+    }
+}
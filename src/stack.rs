@@ -0,0 +1,213 @@
+//! Several independent overrides on one entity.
+//!
+//! An entity has at most one [`FSMOverride`], so two systems restricting the same FSM (a stun
+//! effect and a scripted sequence) would overwrite each other's rules. [`FSMOverrideStack`]
+//! holds named layers instead, which each system adds and removes on its own:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMOverride, FSMOverrideStack, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum MoveFSM { Idle, Walking, Dead }
+//! fn stun(mut stacks: Query<&mut FSMOverrideStack<MoveFSM>>, player: Entity) {
+//!     let mut stack = stacks.get_mut(player).unwrap();
+//!     // Only dying is possible while stunned...
+//!     stack.push(
+//!         "stun",
+//!         0,
+//!         FSMOverride::whitelist([(MoveFSM::Idle, MoveFSM::Dead)]),
+//!     );
+//!     // ...unless a cutscene with higher priority says otherwise
+//!     stack.push("cutscene", 10, FSMOverride::deny_all());
+//! }
+//!
+//! fn end_stun(mut stacks: Query<&mut FSMOverrideStack<MoveFSM>>, player: Entity) {
+//!     stacks.get_mut(player).unwrap().remove("stun");
+//! }
+//! ```
+//!
+//! # Combination rule
+//!
+//! Priority wins: among the layers that decide a transition (accept or deny), the one with the
+//! highest priority decides. On equal priority, deny wins. Layers that leave the transition
+//! open (e.g. an unlisted edge of a whitelist with rules) are skipped. If no layer decides,
+//! the entity's [`FSMOverride`] is consulted, then the `FSMTransition` rules.
+
+use bevy::prelude::*;
+use core::hash::Hash;
+
+use crate::{FSMOverride, OverrideDecision};
+
+/// One named layer of an [`FSMOverrideStack`].
+#[derive(Debug)]
+pub struct FSMOverrideLayer<S: Copy + Eq + Hash + Send + Sync + 'static> {
+    /// Name identifying the layer, e.g. the effect that added it.
+    pub name: &'static str,
+    /// Higher priorities win over lower ones.
+    pub priority: i32,
+    /// Rules of this layer.
+    pub config: FSMOverride<S>,
+}
+
+/// Component holding prioritized override layers for the FSM `S`.
+///
+/// See the [module documentation](self) for how layers are combined.
+#[derive(Component, Debug)]
+pub struct FSMOverrideStack<S: Copy + Eq + Hash + Send + Sync + 'static> {
+    layers: Vec<FSMOverrideLayer<S>>,
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> Default for FSMOverrideStack<S> {
+    fn default() -> Self {
+        Self { layers: Vec::new() }
+    }
+}
+
+impl<S: Copy + Eq + Hash + Send + Sync + 'static> FSMOverrideStack<S> {
+    /// Empty stack; every transition falls through to `FSMOverride` and the rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder form of [`push`](Self::push).
+    #[must_use]
+    pub fn with_layer(mut self, name: &'static str, priority: i32, config: FSMOverride<S>) -> Self {
+        self.push(name, priority, config);
+        self
+    }
+
+    /// Adds a layer, replacing any layer with the same name.
+    pub fn push(&mut self, name: &'static str, priority: i32, config: FSMOverride<S>) -> &mut Self {
+        self.remove(name);
+        self.layers.push(FSMOverrideLayer {
+            name,
+            priority,
+            config,
+        });
+        self
+    }
+
+    /// Removes the layer called `name`, returning its rules.
+    pub fn remove(&mut self, name: &str) -> Option<FSMOverride<S>> {
+        let index = self.layers.iter().position(|layer| layer.name == name)?;
+        Some(self.layers.remove(index).config)
+    }
+
+    /// Rules of the layer called `name`.
+    pub fn get(&self, name: &str) -> Option<&FSMOverride<S>> {
+        self.layers
+            .iter()
+            .find(|layer| layer.name == name)
+            .map(|layer| &layer.config)
+    }
+
+    /// Mutable rules of the layer called `name`.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut FSMOverride<S>> {
+        self.layers
+            .iter_mut()
+            .find(|layer| layer.name == name)
+            .map(|layer| &mut layer.config)
+    }
+
+    /// All layers, in the order they were added.
+    pub fn layers(&self) -> &[FSMOverrideLayer<S>] {
+        &self.layers
+    }
+
+    /// Whether the stack has no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Combined decision of all layers for `from -> to`.
+    pub(crate) fn decision(&self, from: S, to: S) -> OverrideDecision {
+        let mut decided: Option<(i32, OverrideDecision)> = None;
+        for layer in &self.layers {
+            let decision = layer.config.decision(from, to);
+            if decision == OverrideDecision::Defer {
+                continue;
+            }
+            let wins = match decided {
+                None => true,
+                Some((priority, _)) if layer.priority > priority => true,
+                Some((priority, _)) => {
+                    layer.priority == priority && decision == OverrideDecision::Deny
+                }
+            };
+            if wins {
+                decided = Some((layer.priority, decision));
+            }
+        }
+        decided.map_or(OverrideDecision::Defer, |(_, decision)| decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{validate, FSMState, FSMTransition, TransitionVerdict};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum MoveFSM {
+        Idle,
+        Walking,
+        Dead,
+    }
+
+    impl FSMState for MoveFSM {}
+
+    impl FSMTransition for MoveFSM {
+        fn can_transition(_from: Self, to: Self) -> bool {
+            to != MoveFSM::Dead
+        }
+    }
+
+    #[test]
+    fn highest_priority_decides_and_deny_wins_ties() {
+        use MoveFSM::*;
+
+        let mut world = World::new();
+        let stack = FSMOverrideStack::new()
+            .with_layer("stun", 0, FSMOverride::whitelist([(Idle, Dead)]))
+            .with_layer(
+                "script",
+                0,
+                FSMOverride::blacklist([(Idle, Walking)]).with_rules(),
+            );
+        let e = world.spawn((Idle, stack)).id();
+
+        // Only the stun decides Idle -> Dead, bypassing the rules
+        assert_eq!(validate(&world, e, Dead), TransitionVerdict::Allowed);
+        // Stun denies (not whitelisted) and script denies: deny
+        assert_eq!(
+            validate(&world, e, Walking),
+            TransitionVerdict::DeniedByOverride
+        );
+
+        let mut stack = world.get_mut::<FSMOverrideStack<MoveFSM>>(e).unwrap();
+        stack.push("cutscene", 10, FSMOverride::whitelist([(Idle, Walking)]));
+        assert_eq!(validate(&world, e, Walking), TransitionVerdict::Allowed);
+
+        // Equal priority: deny wins over the cutscene's accept
+        let mut stack = world.get_mut::<FSMOverrideStack<MoveFSM>>(e).unwrap();
+        stack.push("stun", 10, FSMOverride::deny_all());
+        assert_eq!(
+            validate(&world, e, Walking),
+            TransitionVerdict::DeniedByOverride
+        );
+
+        // Without layers deciding, the FSMOverride and then the rules apply
+        let mut stack = world.get_mut::<FSMOverrideStack<MoveFSM>>(e).unwrap();
+        for name in ["stun", "script", "cutscene"] {
+            assert!(stack.remove(name).is_some());
+        }
+        assert!(stack.is_empty());
+        assert_eq!(validate(&world, e, Dead), TransitionVerdict::DeniedByRules);
+        world
+            .entity_mut(e)
+            .insert(FSMOverride::whitelist([(Idle, Dead)]));
+        assert_eq!(validate(&world, e, Dead), TransitionVerdict::Allowed);
+    }
+}
@@ -0,0 +1,385 @@
+//! Helpers for unit-testing FSM types in downstream crates.
+//!
+//! Enabled by the `test-utils` feature (typically as a dev-dependency feature).
+//! [`assert_transition_matrix`] checks a table of transitions at once, [`FSMTestHarness`]
+//! drives a single entity step by step and records the events it fires:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum DoorFSM { Open, Closed, Locked }
+//! use bevy_fsm::test_utils::{assert_transition_matrix, CapturedEvent, FSMTestHarness};
+//!
+//! assert_transition_matrix(&[
+//!     (DoorFSM::Open, DoorFSM::Closed, true),
+//!     (DoorFSM::Closed, DoorFSM::Locked, true),
+//!     (DoorFSM::Locked, DoorFSM::Open, false),
+//! ]);
+//!
+//! let mut door = FSMTestHarness::new(DoorFSM::Open);
+//! door.clear_events();
+//! door.request(DoorFSM::Closed);
+//! door.assert_order(CapturedEvent::Exit(DoorFSM::Open), CapturedEvent::Enter(DoorFSM::Closed));
+//! ```
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::reflect::{GetTypeRegistration, Typed};
+
+use crate::propagation;
+use crate::{
+    force_state, Enter, Exit, FSMPlugin, FSMState, StateChangeRequest, Transition, TransitionDenied,
+};
+
+/// An FSM event observed during a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapturedEvent<S> {
+    /// `Exit<S>` for the given state.
+    Exit(S),
+    /// `Transition<S, S>` between the given states.
+    Transition(S, S),
+    /// `Enter<S>` for the given state.
+    Enter(S),
+    /// `TransitionDenied<S>` for a rejected `from -> to` request.
+    Denied(S, S),
+}
+
+/// Events captured per entity, in firing order.
+#[derive(Resource)]
+struct CapturedEvents<S: Send + Sync + 'static>(HashMap<Entity, Vec<CapturedEvent<S>>>);
+
+impl<S: Send + Sync + 'static> CapturedEvents<S> {
+    fn push(&mut self, entity: Entity, event: CapturedEvent<S>) {
+        self.0.entry(entity).or_default().push(event);
+    }
+}
+
+/// Builds an app with `plugin` and observers recording every event of `S`.
+fn capturing_app<S>(plugin: FSMPlugin<S>) -> App
+where
+    S: FSMState + core::hash::Hash + Reflect + FromReflect + Typed + GetTypeRegistration,
+{
+    let mut app = App::new();
+    app.add_plugins(plugin);
+    app.insert_resource(CapturedEvents::<S>(HashMap::default()));
+    app.add_observer(|trigger: On<Exit<S>>, mut log: ResMut<CapturedEvents<S>>| {
+        if propagation::bubbled(&trigger) {
+            return;
+        }
+        let event = trigger.event();
+        log.push(event.entity, CapturedEvent::Exit(event.state));
+    });
+    app.add_observer(
+        |trigger: On<Transition<S, S>>, mut log: ResMut<CapturedEvents<S>>| {
+            if propagation::bubbled(&trigger) {
+                return;
+            }
+            let event = trigger.event();
+            log.push(
+                event.entity,
+                CapturedEvent::Transition(event.from, event.to),
+            );
+        },
+    );
+    app.add_observer(
+        |trigger: On<Enter<S>>, mut log: ResMut<CapturedEvents<S>>| {
+            if propagation::bubbled(&trigger) {
+                return;
+            }
+            let event = trigger.event();
+            log.push(event.entity, CapturedEvent::Enter(event.state));
+        },
+    );
+    app.add_observer(
+        |trigger: On<TransitionDenied<S>>, mut log: ResMut<CapturedEvents<S>>| {
+            let event = trigger.event();
+            log.push(event.entity, CapturedEvent::Denied(event.from, event.to));
+        },
+    );
+    app
+}
+
+/// Asserts that each `(from, to, allowed)` request behaves as expected.
+///
+/// Every pair runs on a fresh entity in an [`App`] with the default [`FSMPlugin<S>`]:
+/// the entity is spawned in `from`, a [`StateChangeRequest`] to `to` is triggered, and the
+/// resulting state and fired events are checked. An allowed transition must end in `to` and
+/// fire exactly `Exit(from)`, `Transition(from, to)`, `Enter(to)`; a denied one must leave
+/// the state untouched and fire only `Denied(from, to)`. Requests with `from == to` must
+/// fire nothing.
+///
+/// All mismatches are collected and reported in a single panic.
+pub fn assert_transition_matrix<S>(expected: &[(S, S, bool)])
+where
+    S: FSMState
+        + core::hash::Hash
+        + core::fmt::Debug
+        + Reflect
+        + FromReflect
+        + Typed
+        + GetTypeRegistration,
+{
+    let mut app = capturing_app(FSMPlugin::<S>::default());
+
+    let mut failures = Vec::new();
+    for &(from, to, allowed) in expected {
+        let world = app.world_mut();
+        let entity = world.spawn(from).id();
+        world.flush();
+        world.resource_mut::<CapturedEvents<S>>().0.remove(&entity);
+
+        world.trigger(StateChangeRequest::new(entity, to));
+        world.flush();
+
+        let (expected_state, expected_events) = match (from == to, allowed) {
+            (true, _) => (from, Vec::new()),
+            (false, true) => (
+                to,
+                vec![
+                    CapturedEvent::Exit(from),
+                    CapturedEvent::Transition(from, to),
+                    CapturedEvent::Enter(to),
+                ],
+            ),
+            (false, false) => (from, vec![CapturedEvent::Denied(from, to)]),
+        };
+        let state = world.get::<S>(entity).copied();
+        let events = world
+            .resource_mut::<CapturedEvents<S>>()
+            .0
+            .remove(&entity)
+            .unwrap_or_default();
+
+        if state != Some(expected_state) || events != expected_events {
+            failures.push(format!(
+                "{from:?} -> {to:?} (expected {}): ended in {state:?}, fired {events:?}",
+                if allowed { "allowed" } else { "denied" },
+            ));
+        }
+        world.despawn(entity);
+    }
+
+    assert!(
+        failures.is_empty(),
+        "transition matrix mismatches:\n{}",
+        failures.join("\n")
+    );
+}
+
+/// A minimal [`App`] with one FSM entity, recording every event fired for it.
+///
+/// The initial `Enter` event of the spawned entity is captured too; call
+/// [`clear_events`](Self::clear_events) to start from a clean slate. Requests and forced
+/// changes are flushed before returning, so assertions can follow directly.
+pub struct FSMTestHarness<S: FSMState> {
+    app: App,
+    entity: Entity,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> FSMTestHarness<S>
+where
+    S: FSMState
+        + core::hash::Hash
+        + core::fmt::Debug
+        + Reflect
+        + FromReflect
+        + Typed
+        + GetTypeRegistration,
+{
+    /// Harness with the default [`FSMPlugin<S>`] and an entity spawned in `initial`.
+    pub fn new(initial: S) -> Self {
+        Self::with_plugin(FSMPlugin::default(), initial)
+    }
+
+    /// Harness with a configured plugin and an entity spawned in `initial`.
+    pub fn with_plugin(plugin: FSMPlugin<S>, initial: S) -> Self {
+        let mut app = capturing_app(plugin);
+        let entity = app.world_mut().spawn(initial).id();
+        app.world_mut().flush();
+        Self {
+            app,
+            entity,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// The underlying app, e.g. to register observers under test.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// The FSM entity.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The entity's current state.
+    pub fn state(&self) -> S {
+        *self
+            .app
+            .world()
+            .get::<S>(self.entity)
+            .expect("FSM entity lost its state component")
+    }
+
+    /// Triggers a [`StateChangeRequest`] to `next` and flushes it.
+    ///
+    /// In buffered modes this also runs one [`App::update`] so the request is processed.
+    pub fn request(&mut self, next: S) -> &mut Self {
+        self.send(StateChangeRequest::new(self.entity, next))
+    }
+
+    /// Triggers a request with an interrupt priority and flushes it.
+    pub fn request_with_priority(&mut self, next: S, priority: u32) -> &mut Self {
+        self.send(StateChangeRequest::new(self.entity, next).with_priority(priority))
+    }
+
+    fn send(&mut self, request: StateChangeRequest<S>) -> &mut Self {
+        let world = self.app.world_mut();
+        world.trigger(request);
+        world.flush();
+        if world.contains_resource::<Messages<StateChangeRequest<S>>>() {
+            self.app.update();
+        }
+        self
+    }
+
+    /// Applies `next` without validation (see [`force_state`]).
+    pub fn force(&mut self, next: S) -> &mut Self {
+        force_state(self.app.world_mut(), self.entity, next);
+        self
+    }
+
+    /// Events captured so far, in firing order.
+    pub fn events(&self) -> &[CapturedEvent<S>] {
+        self.app
+            .world()
+            .resource::<CapturedEvents<S>>()
+            .0
+            .get(&self.entity)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Forgets all captured events.
+    pub fn clear_events(&mut self) -> &mut Self {
+        self.app
+            .world_mut()
+            .resource_mut::<CapturedEvents<S>>()
+            .0
+            .remove(&self.entity);
+        self
+    }
+
+    /// Asserts the entity is in `expected`.
+    #[track_caller]
+    pub fn assert_state(&self, expected: S) -> &Self {
+        assert_eq!(self.state(), expected, "unexpected FSM state");
+        self
+    }
+
+    /// Asserts the captured events are exactly `expected`, in order.
+    #[track_caller]
+    pub fn assert_events(&self, expected: &[CapturedEvent<S>]) -> &Self {
+        assert_eq!(self.events(), expected, "unexpected FSM events");
+        self
+    }
+
+    /// Asserts `first` and `then` were both captured, `first` before `then`.
+    #[track_caller]
+    pub fn assert_order(&self, first: CapturedEvent<S>, then: CapturedEvent<S>) -> &Self {
+        let events = self.events();
+        let position = |event| events.iter().position(|e| *e == event);
+        let (Some(a), Some(b)) = (position(first), position(then)) else {
+            panic!("{first:?} or {then:?} was not fired; events: {events:?}");
+        };
+        assert!(a < b, "{first:?} fired after {then:?}; events: {events:?}");
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMOverride, FSMTransition, TransitionVerdict};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum DoorFSM {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    impl FSMState for DoorFSM {}
+
+    impl FSMTransition for DoorFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (DoorFSM::Open, DoorFSM::Closed)
+                    | (DoorFSM::Closed, DoorFSM::Open)
+                    | (DoorFSM::Closed, DoorFSM::Locked)
+                    | (DoorFSM::Locked, DoorFSM::Closed)
+            )
+        }
+    }
+
+    #[test]
+    fn matrix_matches_rules() {
+        assert_transition_matrix(&[
+            (DoorFSM::Open, DoorFSM::Closed, true),
+            (DoorFSM::Closed, DoorFSM::Locked, true),
+            (DoorFSM::Locked, DoorFSM::Closed, true),
+            (DoorFSM::Open, DoorFSM::Locked, false),
+            (DoorFSM::Locked, DoorFSM::Open, false),
+            (DoorFSM::Open, DoorFSM::Open, false),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Locked -> Open (expected allowed)")]
+    fn matrix_reports_mismatches() {
+        assert_transition_matrix(&[(DoorFSM::Locked, DoorFSM::Open, true)]);
+    }
+
+    #[test]
+    fn harness_captures_events_in_order() {
+        let mut door = FSMTestHarness::new(DoorFSM::Open);
+        door.assert_events(&[CapturedEvent::Enter(DoorFSM::Open)]);
+
+        door.clear_events()
+            .request(DoorFSM::Locked)
+            .request(DoorFSM::Closed);
+        door.assert_state(DoorFSM::Closed).assert_events(&[
+            CapturedEvent::Denied(DoorFSM::Open, DoorFSM::Locked),
+            CapturedEvent::Exit(DoorFSM::Open),
+            CapturedEvent::Transition(DoorFSM::Open, DoorFSM::Closed),
+            CapturedEvent::Enter(DoorFSM::Closed),
+        ]);
+
+        door.clear_events().force(DoorFSM::Open);
+        door.assert_state(DoorFSM::Open).assert_order(
+            CapturedEvent::Exit(DoorFSM::Closed),
+            CapturedEvent::Enter(DoorFSM::Open),
+        );
+    }
+
+    #[test]
+    fn denied_events_carry_the_verdict() {
+        let mut door = FSMTestHarness::new(DoorFSM::Open);
+        let entity = door.entity();
+        door.app_mut()
+            .world_mut()
+            .entity_mut(entity)
+            .insert(FSMOverride::<DoorFSM>::deny_all());
+        door.app_mut()
+            .add_observer(|trigger: On<TransitionDenied<DoorFSM>>| {
+                assert_eq!(trigger.event().reason, TransitionVerdict::DeniedByOverride);
+            });
+
+        door.clear_events().request(DoorFSM::Closed);
+        door.assert_events(&[CapturedEvent::Denied(DoorFSM::Open, DoorFSM::Closed)]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Tickets tracking the outcome of a request.
+//!
+//! [`FSMEntityCommandsExt::request_fsm_tracked`](crate::FSMEntityCommandsExt::request_fsm_tracked)
+//! returns a [`TransitionTicket`] for the request it sends. The ticket stays
+//! [`TicketStatus::Pending`] while the request is queued, debounced, parked or buffered, and
+//! resolves once to whether it was applied, denied or superseded by another request. Poll it,
+//! e.g. from an ability system that chains its next step only after the state changed:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::{FSMEntityCommandsExt, FSMState, FSMTransition, StateChangeRequest, TicketStatus, TransitionTicket};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum HeroFSM { Idle, Dashing }
+//! #[derive(Component)]
+//! struct DashCombo(TransitionTicket);
+//!
+//! fn dash(mut commands: Commands, hero: Entity) {
+//!     let ticket = commands
+//!         .entity(hero)
+//!         .request_fsm_tracked(StateChangeRequest::to(HeroFSM::Dashing));
+//!     commands.entity(hero).insert(DashCombo(ticket));
+//! }
+//!
+//! fn follow_up(mut commands: Commands, combos: Query<(Entity, &DashCombo)>) {
+//!     for (hero, combo) in &combos {
+//!         match combo.0.status() {
+//!             TicketStatus::Pending => {}
+//!             TicketStatus::Applied => { /* start the slash */ }
+//!             _ => { /* the dash never happened */ }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Or observe [`TicketResolved`], triggered on the requested entity when a ticket resolves.
+//! Requests without a ticket cost nothing extra.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::any::TypeId;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use crate::{StateChangeRequest, TransitionVerdict};
+
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies the request a [`TransitionTicket`] was issued for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TicketId(u64);
+
+/// What became of a tracked request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TicketStatus {
+    /// Not decided yet, e.g. buffered, queued mid-transition, debounced or parked for a retry.
+    Pending,
+    /// The entity transitioned to the requested state.
+    Applied,
+    /// The request was denied, or was a no-op ([`TransitionVerdict::Unchanged`]).
+    Denied(TransitionVerdict),
+    /// The request was dropped for another one: replaced while debounced or parked, discarded
+    /// by a [`ConflictPolicy`](crate::ConflictPolicy) or held back by hysteresis.
+    Superseded,
+    /// The entity despawned or lost its FSM component while the request was pending (see
+    /// [`cleanup`](crate::cleanup)).
+    Cancelled,
+}
+
+impl TicketStatus {
+    /// Whether the ticket has resolved.
+    pub fn is_resolved(self) -> bool {
+        self != Self::Pending
+    }
+}
+
+/// Handle to the outcome of a request sent with
+/// [`request_fsm_tracked`](crate::FSMEntityCommandsExt::request_fsm_tracked).
+///
+/// Cheap to clone; every clone sees the same status.
+#[derive(Debug, Clone)]
+pub struct TransitionTicket {
+    id: TicketId,
+    entity: Entity,
+    status: Arc<OnceLock<TicketStatus>>,
+}
+
+impl TransitionTicket {
+    pub(crate) fn new(entity: Entity) -> Self {
+        Self {
+            id: TicketId(NEXT_TICKET.fetch_add(1, Ordering::Relaxed)),
+            entity,
+            status: Arc::default(),
+        }
+    }
+
+    /// Id carried by the tracked request in [`StateChangeRequest::ticket`].
+    pub fn id(&self) -> TicketId {
+        self.id
+    }
+
+    /// The requested entity.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// What became of the request so far.
+    pub fn status(&self) -> TicketStatus {
+        self.status.get().copied().unwrap_or(TicketStatus::Pending)
+    }
+
+    /// Whether the request was applied.
+    pub fn is_applied(&self) -> bool {
+        self.status() == TicketStatus::Applied
+    }
+}
+
+/// Event triggered on the requested entity when a [`TransitionTicket`] resolves.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct TicketResolved {
+    pub entity: Entity,
+    pub ticket: TicketId,
+    pub status: TicketStatus,
+}
+
+/// A ticket whose request is still in flight.
+struct Tracked {
+    entity: Entity,
+    /// The FSM type of the request
+    fsm: TypeId,
+    status: Arc<OnceLock<TicketStatus>>,
+}
+
+/// Statuses of the tickets whose requests are still in flight.
+#[derive(Resource, Default)]
+struct FSMTickets(HashMap<TicketId, Tracked>);
+
+/// Tracks `ticket`, issued for a request of the FSM `S`, until its request resolves.
+pub(crate) fn register<S: 'static>(world: &mut World, ticket: &TransitionTicket) {
+    let tracked = Tracked {
+        entity: ticket.entity,
+        fsm: TypeId::of::<S>(),
+        status: ticket.status.clone(),
+    };
+    world
+        .get_resource_or_init::<FSMTickets>()
+        .0
+        .insert(ticket.id, tracked);
+}
+
+/// Resolves the ticket of `request`, if it carries one.
+pub(crate) fn resolve<S: Copy + Send + Sync + 'static>(
+    world: &mut World,
+    request: &StateChangeRequest<S>,
+    status: TicketStatus,
+) {
+    let Some(id) = request.ticket else {
+        return;
+    };
+    let Some(tracked) = world
+        .get_resource_mut::<FSMTickets>()
+        .and_then(|mut tickets| tickets.0.remove(&id))
+    else {
+        return;
+    };
+    settle(world, id, tracked, status);
+}
+
+/// Resolves every pending ticket of `S` on `entity` as [`TicketStatus::Cancelled`].
+pub(crate) fn cancel<S: 'static>(world: &mut World, entity: Entity) {
+    let Some(mut tickets) = world.get_resource_mut::<FSMTickets>() else {
+        return;
+    };
+    let fsm = TypeId::of::<S>();
+    let mut ids: Vec<TicketId> = tickets
+        .0
+        .iter()
+        .filter(|(_, tracked)| tracked.entity == entity && tracked.fsm == fsm)
+        .map(|(&id, _)| id)
+        .collect();
+    // Resolve in the order the requests were sent
+    ids.sort_unstable();
+    let cancelled: Vec<_> = ids
+        .into_iter()
+        .filter_map(|id| tickets.0.remove(&id).map(|tracked| (id, tracked)))
+        .collect();
+    for (id, tracked) in cancelled {
+        settle(world, id, tracked, TicketStatus::Cancelled);
+    }
+}
+
+fn settle(world: &mut World, id: TicketId, tracked: Tracked, status: TicketStatus) {
+    let _ = tracked.status.set(status);
+    world.trigger(TicketResolved {
+        entity: tracked.entity,
+        ticket: id,
+        status,
+    });
+}
+
+/// Resolves the tickets of `requests` as [`TicketStatus::Superseded`].
+pub(crate) fn supersede<S: Copy + Send + Sync + 'static>(
+    world: &mut World,
+    requests: impl IntoIterator<Item = StateChangeRequest<S>>,
+) {
+    for request in requests {
+        resolve(world, &request, TicketStatus::Superseded);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMEntityCommandsExt, FSMPlugin, FSMState, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum HeroFSM {
+        Idle,
+        Dashing,
+        Stunned,
+    }
+
+    impl FSMState for HeroFSM {}
+
+    impl FSMTransition for HeroFSM {
+        fn can_transition(from: Self, _: Self) -> bool {
+            from != HeroFSM::Stunned
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Resolved(Vec<(TicketId, TicketStatus)>);
+
+    #[test]
+    fn tickets_resolve_with_the_outcome_of_their_request() {
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<HeroFSM>::default());
+        app.init_resource::<Resolved>();
+        app.add_observer(|resolved: On<TicketResolved>, mut log: ResMut<Resolved>| {
+            log.0.push((resolved.ticket, resolved.status));
+        });
+        let hero = app.world_mut().spawn(HeroFSM::Idle).id();
+        let stunned = app.world_mut().spawn(HeroFSM::Stunned).id();
+
+        let mut commands = app.world_mut().commands();
+        let dash = commands
+            .entity(hero)
+            .request_fsm_tracked(StateChangeRequest::to(HeroFSM::Dashing));
+        let denied = commands
+            .entity(stunned)
+            .request_fsm_tracked(StateChangeRequest::to(HeroFSM::Dashing));
+        assert_eq!(dash.status(), TicketStatus::Pending);
+        app.world_mut().flush();
+
+        assert!(dash.is_applied());
+        assert_eq!(
+            denied.status(),
+            TicketStatus::Denied(TransitionVerdict::DeniedByRules)
+        );
+        assert_eq!(
+            app.world().resource::<Resolved>().0,
+            vec![
+                (dash.id(), TicketStatus::Applied),
+                (denied.id(), denied.status())
+            ]
+        );
+        assert!(app.world().resource::<FSMTickets>().0.is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//! `tracing` output for the transition pipeline, enabled by the `trace` feature.
+//!
+//! Every request is logged at `debug` level with its outcome (accepted, denied with the
+//! [`TransitionVerdict`], held back by a pause or a running transition). Accepted transitions
+//! run inside an `fsm_transition` span, and each fired `Exit`, `Transition` and `Enter` event
+//! is logged at `trace` level. All records carry the FSM type, the entity and the states
+//! involved, so they can be filtered with e.g. `RUST_LOG=bevy_fsm=trace`.
+//!
+//! The FSM type is recorded by its [`fsm_name`], and
+//! [`FSMPlugin::with_verbosity`](crate::FSMPlugin::with_verbosity) limits the records of
+//! each FSM type.
+
+use bevy::log::tracing::span::EnteredSpan;
+use bevy::log::tracing::Span;
+use bevy::log::{debug, debug_span, trace};
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+
+use crate::reflect::variant_name;
+use crate::{fsm_name, fsm_verbosity, FSMVerbosity, TransitionPhase, TransitionVerdict};
+
+/// Formats states of `S` by variant name, inserted by the `FSMPlugin`.
+#[derive(Resource)]
+pub(crate) struct StateNames<S: 'static> {
+    name: fn(&S) -> Option<&'static str>,
+}
+
+impl<S: Reflect + Typed> StateNames<S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            name: variant_name::<S>,
+        }
+    }
+}
+
+/// Variant name of `state`, or `?` if the FSM was not added through the plugin.
+fn name<S: 'static>(world: &World, state: &S) -> &'static str {
+    world
+        .get_resource::<StateNames<S>>()
+        .and_then(|names| (names.name)(state))
+        .unwrap_or("?")
+}
+
+/// Whether records at `level` are enabled for `S`.
+fn enabled<S: Send + Sync + 'static>(world: &World, level: FSMVerbosity) -> bool {
+    fsm_verbosity::<S>(world) >= level
+}
+
+/// Logs the outcome of a validated request.
+pub(crate) fn decision<S: Send + Sync + 'static>(
+    world: &World,
+    entity: Entity,
+    from: S,
+    to: S,
+    verdict: TransitionVerdict,
+) {
+    let level = match verdict {
+        TransitionVerdict::Allowed | TransitionVerdict::Paused => FSMVerbosity::Decisions,
+        _ => FSMVerbosity::Denials,
+    };
+    if !enabled::<S>(world, level) {
+        return;
+    }
+    let fsm = fsm_name::<S>(world);
+    let (from, to) = (name(world, &from), name(world, &to));
+    match verdict {
+        TransitionVerdict::Allowed => {
+            debug!(%fsm, %entity, from, to, "transition accepted");
+        }
+        TransitionVerdict::Paused => {
+            debug!(%fsm, %entity, from, to, "request held back, FSM is paused");
+        }
+        reason => debug!(%fsm, %entity, from, to, ?reason, "transition denied"),
+    }
+}
+
+/// Logs a request held back until the entity's running transition completes.
+pub(crate) fn queued<S: Send + Sync + 'static>(world: &World, entity: Entity, to: S) {
+    if !enabled::<S>(world, FSMVerbosity::Decisions) {
+        return;
+    }
+    let fsm = fsm_name::<S>(world);
+    let to = name(world, &to);
+    debug!(%fsm, %entity, to, "request queued behind running transition");
+}
+
+/// Span covering the events of one accepted transition.
+pub(crate) fn span<S: Send + Sync + 'static>(
+    world: &World,
+    entity: Entity,
+    from: S,
+    to: S,
+) -> EnteredSpan {
+    if !enabled::<S>(world, FSMVerbosity::Decisions) {
+        return Span::none().entered();
+    }
+    let fsm = fsm_name::<S>(world);
+    let (from, to) = (name(world, &from), name(world, &to));
+    debug_span!("fsm_transition", %fsm, %entity, from, to).entered()
+}
+
+/// Logs a fired `Exit`, `Transition` or `Enter` event.
+pub(crate) fn event<S: Send + Sync + 'static>(
+    world: &World,
+    entity: Entity,
+    event: &'static str,
+    state: S,
+) {
+    if !enabled::<S>(world, FSMVerbosity::Events) {
+        return;
+    }
+    let fsm = fsm_name::<S>(world);
+    let state = name(world, &state);
+    trace!(%fsm, %entity, state, "{event} fired");
+}
+
+/// Logs a transition aborted because the entity lost its FSM.
+pub(crate) fn aborted<S: Send + Sync + 'static>(
+    world: &World,
+    entity: Entity,
+    phase: TransitionPhase,
+) {
+    if !enabled::<S>(world, FSMVerbosity::Denials) {
+        return;
+    }
+    let fsm = fsm_name::<S>(world);
+    debug!(%fsm, %entity, ?phase, "transition aborted");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMState, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(from: Self, _to: Self) -> bool {
+            from == LifeFSM::Alive
+        }
+    }
+
+    #[test]
+    fn states_are_named_by_variant() {
+        let mut world = World::new();
+        assert_eq!(name(&world, &LifeFSM::Dead), "?");
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        assert_eq!(name(app.world(), &LifeFSM::Alive), "Alive");
+        assert_eq!(name(app.world(), &LifeFSM::Dead), "Dead");
+        world.insert_resource(StateNames::<LifeFSM>::new());
+        assert_eq!(name(&world, &LifeFSM::Dead), "Dead");
+    }
+}
@@ -0,0 +1,162 @@
+//! State sequences checked against the transition table at compile time.
+//!
+//! For enums deriving `FSMTransition` with a `#[transitions(...)]` table, `#[derive(FSMState)]`
+//! also implements [`FSMVariant`] for each variant type and [`FSMEdgeTo`] for each listed edge.
+//! [`FSMTypedPath`] chains variant types along these edges, so a scripted sequence or test
+//! walking an edge that isn't in the table doesn't compile:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::typestate::FSMTypedPath;
+//! # use bevy_fsm::{FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! #[transitions(Alive => Dying, Dying => Dead, Dying => Alive)]
+//! enum LifeFSM { Alive, Dying, Dead }
+//!
+//! fn scripted_death(mut commands: Commands, npc: Entity) {
+//!     use life_fsm::*;
+//!     let path = FSMTypedPath::<LifeFSM>::from(Alive).to(Dying).to(Dead);
+//!     for request in path.requests(npc) {
+//!         commands.trigger(request);
+//!     }
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::typestate::FSMTypedPath;
+//! # use bevy_fsm::{FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # #[transitions(Alive => Dying, Dying => Dead, Dying => Alive)]
+//! # enum LifeFSM { Alive, Dying, Dead }
+//! use life_fsm::*;
+//! // error: `Dead: FSMEdgeTo<Alive>` is not satisfied
+//! let path = FSMTypedPath::<LifeFSM>::from(Alive).to(Dying).to(Dead).to(Alive);
+//! ```
+//!
+//! The compile-time check only covers the static table. Requests built from a path still go
+//! through full validation, including guards and overrides.
+
+use core::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::{FSMState, StateChangeRequest};
+
+/// Variant type standing for the state [`Self::STATE`] of the FSM `S`.
+pub trait FSMVariant<S> {
+    /// The state of `S` this type stands for.
+    const STATE: S;
+}
+
+/// Marks `Next` as reachable from `Self` in one transition.
+pub trait FSMEdgeTo<Next> {}
+
+/// Sequence of `S` states whose transitions were checked at compile time, currently ending
+/// in the variant type `At`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FSMTypedPath<S, At = ()> {
+    states: Vec<S>,
+    _at: PhantomData<At>,
+}
+
+impl<S: FSMState> FSMTypedPath<S> {
+    /// Path starting in the state of `start`.
+    pub fn from<V: FSMVariant<S>>(start: V) -> FSMTypedPath<S, V> {
+        let _ = start;
+        FSMTypedPath {
+            states: vec![V::STATE],
+            _at: PhantomData,
+        }
+    }
+}
+
+impl<S: FSMState, At> FSMTypedPath<S, At> {
+    /// Extends the path with the state of `next`; compiles only for edges in the table.
+    #[must_use]
+    pub fn to<N: FSMVariant<S>>(mut self, next: N) -> FSMTypedPath<S, N>
+    where
+        At: FSMEdgeTo<N>,
+    {
+        let _ = next;
+        self.states.push(N::STATE);
+        FSMTypedPath {
+            states: self.states,
+            _at: PhantomData,
+        }
+    }
+
+    /// All states of the path, starting state first.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// Requests for every step after the starting state, in order.
+    pub fn requests(&self, entity: Entity) -> impl Iterator<Item = StateChangeRequest<S>> + '_ {
+        self.states[1..]
+            .iter()
+            .map(move |&state| StateChangeRequest::new(entity, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum LifeFSM {
+        Alive,
+        Dying,
+        Dead,
+    }
+
+    impl FSMState for LifeFSM {}
+
+    impl FSMTransition for LifeFSM {
+        fn can_transition(from: Self, to: Self) -> bool {
+            matches!(
+                (from, to),
+                (LifeFSM::Alive, LifeFSM::Dying) | (LifeFSM::Dying, LifeFSM::Dead)
+            )
+        }
+    }
+
+    // What `#[derive(FSMState)]` generates for a `#[transitions(...)]` table with these edges
+    struct Alive;
+    struct Dying;
+    struct Dead;
+
+    impl FSMVariant<LifeFSM> for Alive {
+        const STATE: LifeFSM = LifeFSM::Alive;
+    }
+    impl FSMVariant<LifeFSM> for Dying {
+        const STATE: LifeFSM = LifeFSM::Dying;
+    }
+    impl FSMVariant<LifeFSM> for Dead {
+        const STATE: LifeFSM = LifeFSM::Dead;
+    }
+    impl FSMEdgeTo<Dying> for Alive {}
+    impl FSMEdgeTo<Dead> for Dying {}
+
+    #[test]
+    fn typed_paths_request_their_steps() {
+        let path = FSMTypedPath::<LifeFSM>::from(Alive).to(Dying).to(Dead);
+        assert_eq!(
+            path.states(),
+            [LifeFSM::Alive, LifeFSM::Dying, LifeFSM::Dead]
+        );
+
+        let mut app = App::new();
+        app.add_plugins(FSMPlugin::<LifeFSM>::default());
+        let npc = app.world_mut().spawn(LifeFSM::Alive).id();
+        app.update();
+        for request in path.requests(npc) {
+            app.world_mut().trigger(request);
+        }
+        app.update();
+        assert_eq!(app.world().get::<LifeFSM>(npc), Some(&LifeFSM::Dead));
+    }
+}
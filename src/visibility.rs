@@ -0,0 +1,159 @@
+//! Show and hide entities (menus, HUD elements) based on an FSM's current state.
+//!
+//! Enabled by the `visibility` feature. Give a node a [`VisibleInState`] and add
+//! [`FSMVisibilityPlugin`]; its [`Visibility`] then follows the FSM without custom systems:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fsm::visibility::{FSMVisibilityPlugin, VisibleInState};
+//! # use bevy_fsm::{FSMPlugin, FSMState, FSMTransition};
+//! # use bevy_enum_event::EnumEvent;
+//! # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! # enum GameFSM { Menu, Playing, Paused }
+//! fn spawn_ui(mut commands: Commands) {
+//!     commands.spawn(GameFSM::Menu);
+//!     // Follows the only `GameFSM` entity in the world.
+//!     commands.spawn(VisibleInState::new([GameFSM::Menu, GameFSM::Paused]));
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins((FSMPlugin::<GameFSM>::default(), FSMVisibilityPlugin::<GameFSM>::default()))
+//!     .add_systems(Startup, spawn_ui);
+//! ```
+//!
+//! Without a source entity the binding follows the single entity carrying `S` (a "global"
+//! FSM); with [`VisibleInState::of`] it follows a specific entity. Bindings whose source
+//! cannot be resolved are left untouched.
+
+use bevy::camera::visibility::VisibilitySystems;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::FSMState;
+
+/// Makes an entity visible only while an FSM is in one of the given states.
+///
+/// Visible means [`Visibility::Inherited`], so hidden parents still hide the node.
+#[derive(Component, Debug, Clone)]
+#[require(Visibility)]
+pub struct VisibleInState<S: FSMState + core::hash::Hash> {
+    states: HashSet<S>,
+    source: Option<Entity>,
+}
+
+impl<S: FSMState + core::hash::Hash> VisibleInState<S> {
+    /// Visible in any of `states` of the single entity carrying `S`.
+    pub fn new(states: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            states: states.into_iter().collect(),
+            source: None,
+        }
+    }
+
+    /// Follow the FSM of `entity` instead.
+    #[must_use]
+    pub fn of(mut self, entity: Entity) -> Self {
+        self.source = Some(entity);
+        self
+    }
+
+    /// Whether the node should be visible while the FSM is in `state`.
+    pub fn matches(&self, state: S) -> bool {
+        self.states.contains(&state)
+    }
+}
+
+/// Plugin keeping [`VisibleInState<S>`] nodes in sync with their FSM.
+pub struct FSMVisibilityPlugin<S> {
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> Default for FSMVisibilityPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: FSMState + core::hash::Hash> Plugin for FSMVisibilityPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            update_state_visibility::<S>.before(VisibilitySystems::VisibilityPropagate),
+        );
+    }
+}
+
+/// Sets the [`Visibility`] of every [`VisibleInState<S>`] node from its FSM's state.
+pub fn update_state_visibility<S: FSMState + core::hash::Hash>(
+    q_states: Query<&S>,
+    mut q_nodes: Query<(&VisibleInState<S>, &mut Visibility)>,
+) {
+    let global = q_states.single().ok().copied();
+    for (binding, mut visibility) in &mut q_nodes {
+        let state = match binding.source {
+            Some(source) => q_states.get(source).ok().copied(),
+            None => global,
+        };
+        let Some(state) = state else {
+            continue;
+        };
+        let target = if binding.matches(state) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSMPlugin, FSMTransition, StateChangeRequest};
+
+    #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum GameFSM {
+        Menu,
+        Playing,
+    }
+
+    impl FSMState for GameFSM {}
+
+    impl FSMTransition for GameFSM {
+        fn can_transition(_: Self, _: Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn visibility_follows_global_and_referenced_fsms() {
+        let mut app = App::new();
+        app.add_plugins((
+            FSMPlugin::<GameFSM>::default(),
+            FSMVisibilityPlugin::<GameFSM>::default(),
+        ));
+
+        let game = app.world_mut().spawn(GameFSM::Menu).id();
+        let menu = app
+            .world_mut()
+            .spawn(VisibleInState::new([GameFSM::Menu]))
+            .id();
+        let hud = app
+            .world_mut()
+            .spawn(VisibleInState::new([GameFSM::Playing]).of(game))
+            .id();
+
+        let visibility = |app: &App, e| *app.world().get::<Visibility>(e).unwrap();
+        app.update();
+        assert_eq!(visibility(&app, menu), Visibility::Inherited);
+        assert_eq!(visibility(&app, hud), Visibility::Hidden);
+
+        app.world_mut()
+            .trigger(StateChangeRequest::new(game, GameFSM::Playing));
+        app.update();
+        assert_eq!(visibility(&app, menu), Visibility::Hidden);
+        assert_eq!(visibility(&app, hud), Visibility::Inherited);
+    }
+}
@@ -0,0 +1,373 @@
+//! Synchronous, fallible state changes for exclusive systems.
+//!
+//! [`StateChangeRequest`] is fire-and-forget: a denied request
+//! simply does nothing. [`FSMWorldExt::try_set_fsm_state`] runs the same validation and
+//! applies the transition immediately, reporting why it failed.
+//!
+//! [`FSMWorldExt::fsm_state`] and [`FSMEntityExt::fsm_state`] read the current state, with a
+//! [`FSMQueryError`] telling a missing component apart from a despawned entity.
+
+use bevy::prelude::*;
+
+use crate::redirect::redirect;
+use crate::{
+    decide_transition, effective_state, fire_transition, FSMState, PendingTransition, QueuedChange,
+    StateChangeRequest, TransitionVerdict,
+};
+
+/// Reasons a synchronous state change can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FSMError<S> {
+    /// The `FSMTransition` rules do not allow `from -> to`.
+    InvalidTransition {
+        /// Current state of the entity.
+        from: S,
+        /// The requested state.
+        to: S,
+    },
+    /// The entity's `FSMOverride` denies `from -> to`.
+    DeniedByOverride {
+        /// Current state of the entity.
+        from: S,
+        /// The requested state.
+        to: S,
+    },
+    /// The FSM is frozen by [`FSMPaused`](crate::FSMPaused).
+    Paused,
+    /// The entity transitioned less than its [`FSMDebounce`](crate::FSMDebounce) window ago.
+    Debounced,
+    /// The entity already transitioned this frame
+    /// (see [`RequestChaining::OncePerFrame`](crate::RequestChaining::OncePerFrame)).
+    AlreadyTransitioned,
+    /// The current state can only be interrupted with a higher priority
+    /// (see [`FSMTransition::min_interrupt_priority`](crate::FSMTransition::min_interrupt_priority)).
+    InsufficientPriority {
+        /// Current state of the entity.
+        state: S,
+        /// The priority needed to leave it.
+        required: u32,
+    },
+    /// `state` already holds as many entities as its cap allows
+    /// (see [`FSMPlugin::with_state_cap`](crate::FSMPlugin::with_state_cap)).
+    StateFull {
+        /// The requested state.
+        state: S,
+    },
+    /// The entity exists but has no `S` component.
+    NoComponent(Entity),
+    /// The entity does not exist.
+    Despawned(Entity),
+}
+
+impl<S: core::fmt::Debug> core::fmt::Display for FSMError<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidTransition { from, to } => {
+                write!(f, "transition {from:?} -> {to:?} is not allowed")
+            }
+            Self::DeniedByOverride { from, to } => {
+                write!(f, "transition {from:?} -> {to:?} is denied by FSMOverride")
+            }
+            Self::Paused => write!(f, "FSM is paused"),
+            Self::Debounced => write!(f, "FSM changed state too recently"),
+            Self::AlreadyTransitioned => write!(f, "FSM already changed state this frame"),
+            Self::StateFull { state } => write!(f, "state {state:?} is full"),
+            Self::InsufficientPriority { state, required } => {
+                write!(
+                    f,
+                    "leaving {state:?} requires interrupt priority {required}"
+                )
+            }
+            Self::NoComponent(entity) => write!(f, "entity {entity} has no FSM component"),
+            Self::Despawned(entity) => write!(f, "entity {entity} does not exist"),
+        }
+    }
+}
+
+impl<S: core::fmt::Debug> std::error::Error for FSMError<S> {}
+
+impl<S> From<FSMQueryError> for FSMError<S> {
+    fn from(err: FSMQueryError) -> Self {
+        match err {
+            FSMQueryError::NoComponent(entity) => Self::NoComponent(entity),
+            FSMQueryError::Despawned(entity) => Self::Despawned(entity),
+        }
+    }
+}
+
+/// Reasons reading the state of an entity can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FSMQueryError {
+    /// The entity exists but has no `S` component.
+    NoComponent(Entity),
+    /// The entity does not exist.
+    Despawned(Entity),
+}
+
+impl core::fmt::Display for FSMQueryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoComponent(entity) => write!(f, "entity {entity} has no FSM component"),
+            Self::Despawned(entity) => write!(f, "entity {entity} does not exist"),
+        }
+    }
+}
+
+impl std::error::Error for FSMQueryError {}
+
+/// [`World`] extension for reading states and validated, immediate state changes.
+pub trait FSMWorldExt {
+    /// The current `S` state of `entity`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMQueryError, FSMWorldExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dead }
+    /// fn is_alive(world: &World, entity: Entity) -> Result<bool, FSMQueryError> {
+    ///     Ok(world.fsm_state::<LifeFSM>(entity)? == LifeFSM::Alive)
+    /// }
+    /// ```
+    fn fsm_state<S: FSMState>(&self, entity: Entity) -> Result<S, FSMQueryError>;
+
+    /// Validates and applies a transition immediately.
+    ///
+    /// Runs the regular `FSMOverride` + `FSMTransition` validation, then fires Exit,
+    /// Transition and Enter events and flushes them before returning. Requesting the
+    /// current state succeeds without firing anything. The change is checked with the
+    /// default interrupt priority (0).
+    ///
+    /// Called mid-transition (e.g. from an `Exit` observer), the change is validated against
+    /// the state being entered and applied once the current transition has completed, like a
+    /// [`StateChangeRequest`] sent at that point.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fsm::{FSMError, FSMWorldExt, FSMState, FSMTransition};
+    /// # use bevy_enum_event::EnumEvent;
+    /// # #[derive(Component, EnumEvent, FSMTransition, FSMState, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    /// # enum LifeFSM { Alive, Dying }
+    /// fn kill(world: &mut World, entity: Entity) {
+    ///     match world.try_set_fsm_state(entity, LifeFSM::Dying) {
+    ///         Ok(()) => {}
+    ///         Err(FSMError::DeniedByOverride { .. }) => println!("invulnerable"),
+    ///         Err(err) => println!("cannot kill: {err}"),
+    ///     }
+    /// }
+    /// ```
+    fn try_set_fsm_state<S: FSMState + core::hash::Hash>(
+        &mut self,
+        entity: Entity,
+        next: S,
+    ) -> Result<(), FSMError<S>>;
+}
+
+impl FSMWorldExt for World {
+    fn fsm_state<S: FSMState>(&self, entity: Entity) -> Result<S, FSMQueryError> {
+        self.get_entity(entity)
+            .map_err(|_| FSMQueryError::Despawned(entity))?
+            .fsm_state()
+    }
+
+    fn try_set_fsm_state<S: FSMState + core::hash::Hash>(
+        &mut self,
+        entity: Entity,
+        next: S,
+    ) -> Result<(), FSMError<S>> {
+        self.fsm_state::<S>(entity)?;
+        let in_flight = self.get::<PendingTransition<S>>(entity).is_some();
+        let Some(cur) = effective_state::<S>(self, entity) else {
+            return Err(FSMError::NoComponent(entity));
+        };
+        let requested = next;
+        let next = redirect(self, entity, next);
+        if cur == next {
+            return Ok(());
+        }
+
+        match decide_transition(self, entity, cur, next, 0) {
+            TransitionVerdict::Allowed => {}
+            TransitionVerdict::DeniedByOverride => {
+                return Err(FSMError::DeniedByOverride {
+                    from: cur,
+                    to: next,
+                })
+            }
+            TransitionVerdict::Paused => return Err(FSMError::Paused),
+            TransitionVerdict::Debounced => return Err(FSMError::Debounced),
+            TransitionVerdict::AlreadyTransitioned => return Err(FSMError::AlreadyTransitioned),
+            TransitionVerdict::DeniedByCap => return Err(FSMError::StateFull { state: next }),
+            TransitionVerdict::DeniedByPriority => {
+                return Err(FSMError::InsufficientPriority {
+                    state: cur,
+                    required: <S as crate::FSMTransition>::min_interrupt_priority(cur),
+                })
+            }
+            _ => {
+                return Err(FSMError::InvalidTransition {
+                    from: cur,
+                    to: next,
+                })
+            }
+        }
+
+        if in_flight {
+            // Validated again when replayed, against the state actually entered
+            if let Some(mut pending) = self.get_mut::<PendingTransition<S>>(entity) {
+                pending
+                    .queued
+                    .push(QueuedChange::Request(StateChangeRequest::new(
+                        entity, requested,
+                    )));
+            }
+            return Ok(());
+        }
+        fire_transition(self, entity, cur, next, None);
+        Ok(())
+    }
+}
+
+/// [`EntityRef`] / [`EntityWorldMut`] extension reading the state of the entity.
+pub trait FSMEntityExt {
+    /// The current `S` state of this entity.
+    fn fsm_state<S: FSMState>(&self) -> Result<S, FSMQueryError>;
+}
+
+impl FSMEntityExt for EntityRef<'_> {
+    fn fsm_state<S: FSMState>(&self) -> Result<S, FSMQueryError> {
+        self.get::<S>()
+            .copied()
+            .ok_or(FSMQueryError::NoComponent(self.id()))
+    }
+}
+
+impl FSMEntityExt for EntityWorldMut<'_> {
+    fn fsm_state<S: FSMState>(&self) -> Result<S, FSMQueryError> {
+        self.get::<S>()
+            .copied()
+            .ok_or(FSMQueryError::NoComponent(self.id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Enter, Exit, FSMOverride, FSMTransition};
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum GateFSM {
+        Open,
+        Closed,
+        Broken,
+    }
+
+    impl FSMState for GateFSM {}
+
+    impl FSMTransition for GateFSM {
+        fn can_transition(from: Self, _to: Self) -> bool {
+            from != GateFSM::Broken
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Entered(Vec<GateFSM>);
+
+    #[test]
+    fn try_set_applies_and_reports_errors() {
+        let mut world = World::new();
+        world.init_resource::<Entered>();
+        world.add_observer(|trigger: On<Enter<GateFSM>>, mut log: ResMut<Entered>| {
+            log.0.push(trigger.event().state);
+        });
+        let gate = world.spawn(GateFSM::Open).id();
+
+        assert_eq!(world.try_set_fsm_state(gate, GateFSM::Closed), Ok(()));
+        assert_eq!(world.resource::<Entered>().0, vec![GateFSM::Closed]);
+
+        world.entity_mut(gate).insert(
+            FSMOverride::<GateFSM>::blacklist([(GateFSM::Closed, GateFSM::Open)]).with_rules(),
+        );
+        assert_eq!(
+            world.try_set_fsm_state(gate, GateFSM::Open),
+            Err(FSMError::DeniedByOverride {
+                from: GateFSM::Closed,
+                to: GateFSM::Open
+            })
+        );
+
+        world.try_set_fsm_state(gate, GateFSM::Broken).unwrap();
+        assert_eq!(
+            world.try_set_fsm_state(gate, GateFSM::Open),
+            Err(FSMError::InvalidTransition {
+                from: GateFSM::Broken,
+                to: GateFSM::Open
+            })
+        );
+
+        let empty = world.spawn_empty().id();
+        assert_eq!(
+            world.try_set_fsm_state(empty, GateFSM::Open),
+            Err(FSMError::NoComponent(empty))
+        );
+        world.despawn(empty);
+        assert_eq!(
+            world.try_set_fsm_state(empty, GateFSM::Open),
+            Err(FSMError::Despawned(empty))
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct Nested(Option<Result<(), FSMError<GateFSM>>>);
+
+    #[test]
+    fn fsm_state_reports_missing_components_and_entities() {
+        let mut world = World::new();
+        let gate = world.spawn(GateFSM::Open).id();
+        let wall = world.spawn_empty().id();
+        let gone = world.spawn(GateFSM::Closed).id();
+        world.despawn(gone);
+
+        assert_eq!(world.fsm_state::<GateFSM>(gate), Ok(GateFSM::Open));
+        assert_eq!(world.entity(gate).fsm_state::<GateFSM>(), Ok(GateFSM::Open));
+        assert_eq!(
+            world.entity_mut(wall).fsm_state::<GateFSM>(),
+            Err(FSMQueryError::NoComponent(wall))
+        );
+        assert_eq!(
+            world.fsm_state::<GateFSM>(gone),
+            Err(FSMQueryError::Despawned(gone))
+        );
+    }
+
+    #[test]
+    fn try_set_mid_transition_chains_onto_the_state_being_entered() {
+        let mut world = World::new();
+        world.init_resource::<Entered>();
+        world.init_resource::<Nested>();
+        world.add_observer(|trigger: On<Enter<GateFSM>>, mut log: ResMut<Entered>| {
+            log.0.push(trigger.event().state);
+        });
+        world.add_observer(|trigger: On<Exit<GateFSM>>, mut commands: Commands| {
+            let gate = trigger.event().entity;
+            if trigger.event().state == GateFSM::Open {
+                commands.queue(move |world: &mut World| {
+                    let result = world.try_set_fsm_state(gate, GateFSM::Broken);
+                    world.resource_mut::<Nested>().0 = Some(result);
+                });
+            }
+        });
+        let gate = world.spawn(GateFSM::Open).id();
+
+        world.try_set_fsm_state(gate, GateFSM::Closed).unwrap();
+
+        assert_eq!(world.resource::<Nested>().0, Some(Ok(())));
+        assert_eq!(*world.get::<GateFSM>(gate).unwrap(), GateFSM::Broken);
+        assert_eq!(
+            world.resource::<Entered>().0,
+            vec![GateFSM::Closed, GateFSM::Broken]
+        );
+    }
+}